@@ -1,3 +1,9 @@
+/// Smallest zoom: with the 10px base cell size this shows 100 cells per pixel
+pub const MIN_ZOOM: f32 = 0.001;
+
+/// Largest zoom
+pub const MAX_ZOOM: f32 = 10.0;
+
 /// Camera manages viewport and zoom for grid navigation
 pub struct Camera {
     pub offset_x: f32,
@@ -16,12 +22,70 @@ impl Camera {
     
     /// Zoom in by factor
     pub fn zoom_in(&mut self, factor: f32) {
-        self.zoom = (self.zoom * factor).clamp(0.5, 10.0);
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
     }
     
     /// Zoom out by factor
     pub fn zoom_out(&mut self, factor: f32) {
-        self.zoom = (self.zoom / factor).clamp(0.5, 10.0);
+        self.zoom = (self.zoom / factor).clamp(MIN_ZOOM, MAX_ZOOM);
+    }
+    
+    /// Zoom by `factor` (> 1 zooms in) keeping the point under `anchor`
+    /// (screen coordinates) fixed
+    pub fn zoom_at(&mut self, factor: f32, anchor: (f32, f32)) {
+        let old_zoom = self.zoom;
+        self.zoom = (self.zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+        let scale = self.zoom / old_zoom;
+        self.offset_x = anchor.0 - (anchor.0 - self.offset_x) * scale;
+        self.offset_y = anchor.1 - (anchor.1 - self.offset_y) * scale;
+    }
+    
+    /// Center the viewport on cell (x, y) without changing zoom
+    pub fn center_on(&mut self, x: f32, y: f32, viewport: (f32, f32), cell_size: f32) {
+        let size = cell_size * self.zoom;
+        self.offset_x = viewport.0 / 2.0 - (x + 0.5) * size;
+        self.offset_y = viewport.1 / 2.0 - (y + 0.5) * size;
+    }
+    
+    /// Grid position (in cells, fractional) at the center of the viewport;
+    /// the inverse of `center_on` up to the half-cell offset
+    pub fn center_cell(&self, viewport: (f32, f32), cell_size: f32) -> (f32, f32) {
+        let size = cell_size * self.zoom;
+        ((viewport.0 / 2.0 - self.offset_x) / size, (viewport.1 / 2.0 - self.offset_y) / size)
+    }
+    
+    /// Zoom and center so the inclusive cell rectangle fills the viewport
+    /// with a small margin
+    pub fn fit_rect(&mut self, rect: (usize, usize, usize, usize), viewport: (f32, f32), cell_size: f32) {
+        let (min_x, min_y, max_x, max_y) = rect;
+        let width = (max_x - min_x + 1) as f32;
+        let height = (max_y - min_y + 1) as f32;
+        
+        let fit = (viewport.0 / (width * cell_size)).min(viewport.1 / (height * cell_size));
+        self.zoom = (fit * 0.9).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.center_on(
+            min_x as f32 + (width - 1.0) / 2.0,
+            min_y as f32 + (height - 1.0) / 2.0,
+            viewport,
+            cell_size,
+        );
+    }
+    
+    /// Snapshot of the current view under `name`
+    pub fn bookmark(&self, name: impl Into<String>) -> Bookmark {
+        Bookmark {
+            name: name.into(),
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+            zoom: self.zoom,
+        }
+    }
+    
+    /// Restore a view saved with `bookmark`
+    pub fn jump_to(&mut self, bookmark: &Bookmark) {
+        self.offset_x = bookmark.offset_x;
+        self.offset_y = bookmark.offset_y;
+        self.zoom = bookmark.zoom;
     }
     
     /// Pan camera
@@ -30,6 +94,13 @@ impl Camera {
         self.offset_y += dy;
     }
     
+    /// Keep showing the same cells after the grid contents moved by
+    /// `offset` cells (e.g. a centered resize)
+    pub fn shift_cells(&mut self, offset: (isize, isize), cell_size: f32) {
+        let scale = cell_size * self.zoom;
+        self.pan(-offset.0 as f32 * scale, -offset.1 as f32 * scale);
+    }
+    
     /// Convert screen coordinates to grid coordinates
     pub fn screen_to_grid(&self, screen_x: f32, screen_y: f32, cell_size: f32) -> (i32, i32) {
         let grid_x = ((screen_x - self.offset_x) / (cell_size * self.zoom)) as i32;
@@ -64,3 +135,100 @@ impl Default for Camera {
         Self::new()
     }
 }
+
+/// A named camera view
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bookmark {
+    pub name: String,
+    pub offset_x: f32,
+    pub offset_y: f32,
+    pub zoom: f32,
+}
+
+/// Number of bookmark slots (keys 1-9)
+pub const BOOKMARK_SLOTS: usize = 9;
+
+/// Fixed slots of saved camera views
+#[derive(Clone, Debug, Default)]
+pub struct Bookmarks {
+    slots: [Option<Bookmark>; BOOKMARK_SLOTS],
+}
+
+impl Bookmarks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    
+    /// Store `bookmark` in `slot` (ignored if out of range)
+    pub fn save(&mut self, slot: usize, bookmark: Bookmark) {
+        if let Some(entry) = self.slots.get_mut(slot) {
+            *entry = Some(bookmark);
+        }
+    }
+    
+    pub fn get(&self, slot: usize) -> Option<&Bookmark> {
+        self.slots.get(slot).and_then(|b| b.as_ref())
+    }
+    
+    /// Rename the bookmark in `slot`, if any
+    pub fn rename(&mut self, slot: usize, name: impl Into<String>) {
+        if let Some(Some(bookmark)) = self.slots.get_mut(slot) {
+            bookmark.name = name.into();
+        }
+    }
+    
+    /// Occupied slots with their bookmarks
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Bookmark)> {
+        self.slots.iter().enumerate().filter_map(|(i, b)| b.as_ref().map(|b| (i, b)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_zoom_at_keeps_anchor_fixed() {
+        let mut camera = Camera::new();
+        camera.pan(30.0, -20.0);
+        let anchor = (250.0, 170.0);
+        let before = camera.screen_to_grid(anchor.0, anchor.1, 10.0);
+        
+        camera.zoom_at(2.0, anchor);
+        assert_eq!(camera.zoom, 2.0);
+        assert_eq!(camera.screen_to_grid(anchor.0, anchor.1, 10.0), before);
+        
+        camera.zoom_at(0.1, anchor);
+        assert_eq!(camera.screen_to_grid(anchor.0, anchor.1, 10.0), before);
+    }
+    
+    #[test]
+    fn test_fit_rect_frames_cells() {
+        let mut camera = Camera::new();
+        camera.fit_rect((100, 40, 199, 59), (800.0, 600.0), 10.0);
+        
+        // 100 cells wide into 800px with a 10% margin
+        assert!((camera.zoom - 0.72).abs() < 1e-4);
+        let (min_x, min_y) = camera.screen_to_grid(0.0, 0.0, 10.0);
+        let (max_x, max_y) = camera.screen_to_grid(800.0, 600.0, 10.0);
+        assert!(min_x <= 100 && max_x >= 199);
+        assert!(min_y <= 40 && max_y >= 59);
+        assert_eq!(camera.screen_to_grid(400.0, 300.0, 10.0), (150, 50));
+    }
+    
+    #[test]
+    fn test_bookmarks_round_trip() {
+        let mut camera = Camera::new();
+        camera.zoom_in(3.0);
+        camera.pan(12.0, 34.0);
+        
+        let mut bookmarks = Bookmarks::new();
+        bookmarks.save(2, camera.bookmark("gun"));
+        bookmarks.save(BOOKMARK_SLOTS, camera.bookmark("ignored"));
+        
+        camera.reset();
+        camera.jump_to(bookmarks.get(2).unwrap());
+        assert_eq!((camera.offset_x, camera.offset_y, camera.zoom), (12.0, 34.0, 3.0));
+        assert_eq!(bookmarks.iter().count(), 1);
+    }
+}
@@ -0,0 +1,567 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::domain::{flood_region, Algorithm, BitGrid, Condition, Mask, Pattern, ProbeSet, ResizeAnchor, Rule};
+use super::GameState;
+
+/// Every mutation of `GameState` expressed as data.
+/// Input handlers translate key presses, clicks and dropdown selections
+/// into commands; `GameState::apply` is the only place they take effect.
+#[derive(Clone)]
+pub enum Command {
+    /// Set a single cell alive or dead
+    Paint { x: usize, y: usize, alive: bool },
+    /// Set every cell alive in `mask` (a brush stroke, drawn shape or
+    /// fill) at once
+    PaintMask { mask: Mask, alive: bool },
+    /// Set the region of cells connected to (x, y) in the other state
+    FloodFill { x: usize, y: usize, alive: bool },
+    /// Stamp a pattern with its top-left corner at (x, y)
+    PlacePattern { pattern: Pattern, x: usize, y: usize },
+    /// Kill every cell and reset the generation counter
+    Clear,
+    /// Fill the grid randomly and reset the generation counter
+    Randomize,
+    SetRule(Arc<dyn Rule>),
+    SetAlgorithm(Algorithm),
+    /// Advance exactly one generation
+    Step,
+    /// Change the grid size, keeping the contents where `anchor` puts them
+    Resize { width: usize, height: usize, anchor: ResizeAnchor },
+    ToggleAutoExpand,
+    TogglePlay,
+    SetRunning(bool),
+    /// Change updates per second by the given delta
+    AdjustSpeed(f32),
+    /// Advance this many generations per update
+    SetStepSize(u64),
+    /// Step size to the next power of two
+    DoubleStepSize,
+    /// Step size to the previous power of two
+    HalveStepSize,
+    /// Run as many generations per frame as the frame budget allows
+    ToggleMaxSpeed,
+    /// Enter placement mode for the pattern at this library index
+    BeginPlacement(usize),
+    CancelPlacement,
+    ToggleStatsGraph,
+    ToggleInspector,
+    CycleHistoryKind,
+    /// Arm a breakpoint that stays until removed
+    AddBreakpoint(Condition),
+    /// Arm a one-shot breakpoint and start running
+    RunUntil(Condition),
+    RemoveBreakpoint(usize),
+    ClearBreakpoints,
+    /// Place a named probe on (x, y), or rename the probe already there
+    AddProbe { x: usize, y: usize, name: String },
+    RemoveProbeAt { x: usize, y: usize },
+    RenameProbe { index: usize, name: String },
+    ClearProbes,
+    /// Replace every probe, e.g. with those saved by the last session
+    SetProbes(ProbeSet),
+}
+
+impl fmt::Debug for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Command::Paint { x, y, alive } => write!(f, "Paint({}, {}, {})", x, y, alive),
+            Command::PaintMask { mask, alive } => write!(f, "PaintMask({} cells, {})", mask.cells.count_alive(), alive),
+            Command::FloodFill { x, y, alive } => write!(f, "FloodFill({}, {}, {})", x, y, alive),
+            Command::PlacePattern { pattern, x, y } => write!(f, "PlacePattern({}, {}, {})", pattern.name, x, y),
+            Command::Clear => write!(f, "Clear"),
+            Command::Randomize => write!(f, "Randomize"),
+            Command::SetRule(rule) => write!(f, "SetRule({})", rule.name()),
+            Command::SetAlgorithm(algorithm) => write!(f, "SetAlgorithm({})", algorithm.name()),
+            Command::Step => write!(f, "Step"),
+            Command::Resize { width, height, anchor } => write!(f, "Resize({}x{}, {:?})", width, height, anchor),
+            Command::ToggleAutoExpand => write!(f, "ToggleAutoExpand"),
+            Command::TogglePlay => write!(f, "TogglePlay"),
+            Command::SetRunning(running) => write!(f, "SetRunning({})", running),
+            Command::AdjustSpeed(delta) => write!(f, "AdjustSpeed({})", delta),
+            Command::SetStepSize(generations) => write!(f, "SetStepSize({})", generations),
+            Command::DoubleStepSize => write!(f, "DoubleStepSize"),
+            Command::HalveStepSize => write!(f, "HalveStepSize"),
+            Command::ToggleMaxSpeed => write!(f, "ToggleMaxSpeed"),
+            Command::BeginPlacement(index) => write!(f, "BeginPlacement({})", index),
+            Command::CancelPlacement => write!(f, "CancelPlacement"),
+            Command::ToggleStatsGraph => write!(f, "ToggleStatsGraph"),
+            Command::ToggleInspector => write!(f, "ToggleInspector"),
+            Command::CycleHistoryKind => write!(f, "CycleHistoryKind"),
+            Command::AddBreakpoint(condition) => write!(f, "AddBreakpoint({})", condition),
+            Command::RunUntil(condition) => write!(f, "RunUntil({})", condition),
+            Command::RemoveBreakpoint(index) => write!(f, "RemoveBreakpoint({})", index),
+            Command::ClearBreakpoints => write!(f, "ClearBreakpoints"),
+            Command::AddProbe { x, y, name } => write!(f, "AddProbe({}, {}, {})", x, y, name),
+            Command::RemoveProbeAt { x, y } => write!(f, "RemoveProbeAt({}, {})", x, y),
+            Command::RenameProbe { index, name } => write!(f, "RenameProbe({}, {})", index, name),
+            Command::ClearProbes => write!(f, "ClearProbes"),
+            Command::SetProbes(probes) => write!(f, "SetProbes({} probes)", probes.len()),
+        }
+    }
+}
+
+impl Command {
+    /// Whether the command edits grid contents, making any generation
+    /// evolving in the background stale
+    pub fn mutates_grid(&self) -> bool {
+        matches!(
+            self,
+            Command::Paint { .. }
+                | Command::PaintMask { .. }
+                | Command::FloodFill { .. }
+                | Command::PlacePattern { .. }
+                | Command::Clear
+                | Command::Randomize
+                | Command::Step
+                | Command::Resize { .. }
+        )
+    }
+
+    /// Whether the command only draws on the grid. Drawing while a
+    /// generation evolves in the background is replayed onto the result
+    /// instead of making it stale.
+    pub fn draws(&self) -> bool {
+        matches!(
+            self,
+            Command::Paint { .. } | Command::PaintMask { .. } | Command::FloodFill { .. } | Command::PlacePattern { .. }
+        )
+    }
+
+    /// Draw the command onto `grid` (nothing for commands that don't draw)
+    pub(crate) fn draw_on(&self, grid: &mut BitGrid) {
+        match self {
+            Command::Paint { x, y, alive } => grid.set(*x, *y, *alive),
+            Command::PaintMask { mask, alive } => mask.paint_on(grid, *alive),
+            Command::FloodFill { x, y, alive } => {
+                if let Some(region) = fill_region(grid, *x, *y, *alive) {
+                    grid.paint_mask(&region, *alive);
+                }
+            }
+            Command::PlacePattern { pattern, x, y } => pattern.place_on(grid, *x, *y),
+            _ => {}
+        }
+    }
+}
+
+/// The region a fill from (x, y) would change on `grid`, or None when
+/// the cell is off the grid or already in the fill state
+pub(crate) fn fill_region(grid: &BitGrid, x: usize, y: usize, alive: bool) -> Option<BitGrid> {
+    let (width, height) = grid.dimensions();
+    (x < width && y < height && grid.get(x, y) != alive).then(|| flood_region(grid, x, y))
+}
+
+impl GameState {
+    /// Apply a command. This is the single entry point for state changes.
+    pub fn apply(mut self, command: Command) -> Self {
+        if command.draws() && self.is_evolving() {
+            self.queue_edit(command.clone());
+        } else if command.mutates_grid() {
+            self.bump_grid_epoch();
+        }
+
+        match command {
+            Command::Paint { .. } | Command::PaintMask { .. } | Command::FloodFill { .. } => {
+                command.draw_on(&mut self.grid);
+                self
+            }
+            Command::PlacePattern { .. } => {
+                command.draw_on(&mut self.grid);
+                self.pending_pattern_index = None;
+                self
+            }
+            Command::Clear => self.clear(),
+            Command::Randomize => self.randomize(),
+            Command::SetRule(rule) => {
+                // A generation evolving under the old rule is stale
+                self.bump_grid_epoch();
+                self.set_rule(rule);
+                self
+            }
+            Command::SetAlgorithm(algorithm) => {
+                // Algorithms agree on the result, but the generation in
+                // flight was timed (and picked) for the old one
+                self.bump_grid_epoch();
+                self.set_algorithm(algorithm);
+                self
+            }
+            Command::Step => self.step(),
+            Command::Resize { width, height, anchor } => {
+                self.resize_grid(width, height, anchor);
+                self
+            }
+            Command::ToggleAutoExpand => self.toggle_auto_expand(),
+            Command::TogglePlay => self.toggle_running(),
+            Command::SetRunning(running) => self.with_running(running),
+            Command::AdjustSpeed(delta) => self.adjust_speed(delta),
+            Command::SetStepSize(generations) => self.set_step_size(generations),
+            Command::DoubleStepSize => self.scale_step_size(true),
+            Command::HalveStepSize => self.scale_step_size(false),
+            Command::ToggleMaxSpeed => self.toggle_max_speed(),
+            Command::BeginPlacement(index) => {
+                self.pending_pattern_index = Some(index);
+                self.with_running(false)
+            }
+            Command::CancelPlacement => {
+                self.pending_pattern_index = None;
+                self
+            }
+            Command::ToggleStatsGraph => self.toggle_stats_graph(),
+            Command::ToggleInspector => self.toggle_inspector(),
+            Command::CycleHistoryKind => self.cycle_history_kind(),
+            Command::AddBreakpoint(condition) => {
+                self.bump_watch_version();
+                self.breakpoints.add(condition);
+                self
+            }
+            Command::RunUntil(condition) => {
+                self.bump_watch_version();
+                self.breakpoints.add_once(condition);
+                // A condition that already holds stops before anything runs
+                match self.breakpoints.check(&self.grid, &self.grid, self.generation) {
+                    Some(hit) => {
+                        self.breakpoint_hit = Some(hit);
+                        self.with_running(false)
+                    }
+                    None => self.with_running(true),
+                }
+            }
+            Command::RemoveBreakpoint(index) => {
+                self.bump_watch_version();
+                self.breakpoints.remove(index);
+                self
+            }
+            Command::ClearBreakpoints => {
+                self.bump_watch_version();
+                self.breakpoints.clear();
+                self.breakpoint_hit = None;
+                self
+            }
+            Command::AddProbe { x, y, name } => {
+                self.bump_watch_version();
+                self.probes.add(name, x, y, &self.grid, self.generation);
+                self
+            }
+            Command::RemoveProbeAt { x, y } => {
+                self.bump_watch_version();
+                self.probes.remove_at(x, y);
+                self
+            }
+            Command::RenameProbe { index, name } => {
+                self.bump_watch_version();
+                self.probes.rename(index, name);
+                self
+            }
+            Command::ClearProbes => {
+                self.bump_watch_version();
+                self.probes.clear();
+                self
+            }
+            Command::SetProbes(probes) => {
+                self.bump_watch_version();
+                self.probes = probes;
+                self
+            }
+        }
+    }
+
+    /// Apply a sequence of commands in order
+    pub fn apply_all(self, commands: impl IntoIterator<Item = Command>) -> Self {
+        commands.into_iter().fold(self, GameState::apply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::game_state::{AUTO_EXPAND_BATCH, AUTO_EXPAND_MARGIN};
+    use crate::domain::{presets, HighLifeRule};
+
+    #[test]
+    fn test_paint_and_clear() {
+        let state = GameState::new(10, 10).apply_all([
+            Command::Paint { x: 1, y: 2, alive: true },
+            Command::Paint { x: 3, y: 4, alive: true },
+            Command::Paint { x: 3, y: 4, alive: false },
+        ]);
+        assert!(state.grid.get(1, 2));
+        assert!(!state.grid.get(3, 4));
+
+        let state = state.apply(Command::Clear);
+        assert_eq!(state.grid.count_alive(), 0);
+        assert_eq!(state.generation, 0);
+    }
+
+    #[test]
+    fn test_place_pattern_leaves_placement_mode() {
+        let state = GameState::new(20, 20)
+            .apply(Command::BeginPlacement(0))
+            .apply(Command::PlacePattern { pattern: presets::glider(), x: 5, y: 5 });
+
+        assert_eq!(state.pending_pattern_index, None);
+        assert_eq!(state.grid.count_alive(), 5);
+        assert!(state.grid.get(6, 5));
+    }
+
+    #[test]
+    fn test_step_advances_one_generation() {
+        let state = GameState::new(10, 10)
+            .apply(Command::PlacePattern { pattern: presets::blinker(), x: 4, y: 4 })
+            .apply(Command::SetAlgorithm(Algorithm::Simd))
+            .apply(Command::Step);
+
+        assert_eq!(state.generation, 1);
+        assert!(!state.is_running);
+        // Horizontal blinker at row 5 turned vertical around (5, 5)
+        assert!(state.grid.get(5, 4));
+        assert!(state.grid.get(5, 6));
+        assert!(!state.grid.get(4, 5));
+    }
+
+    #[test]
+    fn test_step_size_advances_many_generations_per_update() {
+        let state = GameState::new(64, 64)
+            .apply(Command::PlacePattern { pattern: presets::blinker(), x: 30, y: 30 })
+            .apply_all([Command::SetStepSize(5), Command::TogglePlay])
+            .tick(1.0);
+
+        assert_eq!(state.generation, 5);
+        // Odd number of blinker flips leaves it vertical
+        assert!(state.grid.get(31, 30));
+        assert!(!state.grid.get(30, 31));
+    }
+
+    #[test]
+    fn test_step_size_powers_of_two() {
+        let state = GameState::new(10, 10).apply_all([
+            Command::DoubleStepSize,
+            Command::DoubleStepSize,
+            Command::DoubleStepSize,
+        ]);
+        assert_eq!(state.step_size, 8);
+
+        let state = state.apply_all([Command::SetStepSize(10), Command::HalveStepSize]);
+        assert_eq!(state.step_size, 8);
+        let state = state.apply_all(std::iter::repeat_n(Command::HalveStepSize, 5));
+        assert_eq!(state.step_size, 1);
+    }
+
+    #[test]
+    fn test_run_until_generation_stops_exactly() {
+        let mut state = GameState::new(64, 64)
+            .apply_all([Command::SetStepSize(16), Command::RunUntil(Condition::Generation(40))]);
+
+        while state.is_running {
+            state = state.tick(1.0);
+        }
+
+        assert_eq!(state.generation, 40);
+        assert_eq!(state.breakpoint_hit.map(|hit| hit.condition), Some(Condition::Generation(40)));
+        assert!(state.breakpoints.is_empty());
+
+        let state = state.apply(Command::TogglePlay);
+        assert!(state.breakpoint_hit.is_none());
+    }
+
+    #[test]
+    fn test_run_until_stable_stops_inside_a_batch() {
+        let state = GameState::new(64, 64).apply_all([
+            Command::PlacePattern { pattern: presets::blinker(), x: 10, y: 10 },
+            Command::SetStepSize(1000),
+            Command::RunUntil(Condition::Stabilized),
+        ]);
+
+        // One update runs until the blinker is back where it started
+        let state = state.tick(1.0);
+        assert!(!state.is_running);
+        assert_eq!(state.generation, 2);
+        assert_eq!(state.breakpoint_hit.map(|hit| hit.generation), Some(2));
+    }
+
+    #[test]
+    fn test_run_until_condition_that_already_holds_does_not_run() {
+        let state = GameState::new(64, 64).apply(Command::RunUntil(Condition::PopulationBelow(10)));
+        assert!(!state.is_running);
+        assert_eq!(state.generation, 0);
+        assert_eq!(state.breakpoint_hit.map(|hit| hit.condition), Some(Condition::PopulationBelow(10)));
+        assert!(state.breakpoints.is_empty());
+    }
+
+    #[test]
+    fn test_probes_sample_every_step() {
+        let state = GameState::new(10, 10)
+            .apply(Command::PlacePattern { pattern: presets::blinker(), x: 4, y: 4 })
+            .apply(Command::AddProbe { x: 4, y: 5, name: "end".into() })
+            .apply_all([Command::Step, Command::Step]);
+
+        let probe = state.probes.iter().next().unwrap();
+        let waveform: Vec<bool> = probe.samples_between(0, 2).map(|(_, alive)| alive).collect();
+        assert_eq!(waveform, vec![true, false, true]);
+
+        let state = state.apply(Command::RemoveProbeAt { x: 4, y: 5 });
+        assert!(state.probes.is_empty());
+    }
+
+    #[test]
+    fn test_probes_sample_inside_batches() {
+        let mut state = GameState::new(10, 10).with_background_worker().apply_all([
+            Command::PlacePattern { pattern: presets::blinker(), x: 4, y: 4 },
+            Command::AddProbe { x: 4, y: 5, name: "end".into() },
+            Command::SetStepSize(8),
+            Command::TogglePlay,
+        ]);
+        let start = std::time::Instant::now();
+        while state.generation < 16 {
+            assert!(start.elapsed().as_secs() < 10, "batches never arrived");
+            state = state.tick(1.0);
+        }
+
+        // Every generation has its own sample, not just every eighth
+        let probe = state.probes.iter().next().unwrap();
+        let waveform: Vec<bool> = probe.samples_between(0, 16).map(|(_, alive)| alive).collect();
+        assert_eq!(waveform.len(), 17);
+        assert!(waveform.iter().enumerate().all(|(generation, &alive)| alive == (generation % 2 == 0)));
+    }
+
+    #[test]
+    fn test_resize_and_rule() {
+        let state = GameState::new(10, 10).apply_all([
+            Command::Resize { width: 30, height: 20, anchor: ResizeAnchor::TopLeft },
+            Command::SetRule(Arc::new(HighLifeRule)),
+            Command::TogglePlay,
+        ]);
+
+        assert_eq!(state.grid.dimensions(), (30, 20));
+        assert_eq!(state.rule.name(), "HighLife");
+        assert!(state.is_running);
+    }
+
+    #[test]
+    fn test_resize_keeps_contents_and_generation() {
+        let state = GameState::new(10, 10)
+            .apply(Command::PlacePattern { pattern: presets::blinker(), x: 4, y: 4 })
+            .apply(Command::AddProbe { x: 5, y: 5, name: "mid".into() })
+            .apply(Command::AddBreakpoint(Condition::CellAlive { x: 6, y: 6 }))
+            .apply_all([Command::Step, Command::Step, Command::Step])
+            .apply(Command::Resize { width: 20, height: 30, anchor: ResizeAnchor::Center });
+
+        assert_eq!(state.generation, 3);
+        assert_eq!(state.grid.count_alive(), 3);
+        // Contents, probes and breakpoints moved by (5, 10)
+        assert!(state.grid.get(10, 14) && state.grid.get(10, 15) && state.grid.get(10, 16));
+        assert_eq!(state.probes.index_at(10, 15), Some(0));
+        assert_eq!(state.breakpoints.iter().next().unwrap().condition, Condition::CellAlive { x: 11, y: 16 });
+    }
+
+    #[test]
+    fn test_auto_expand_gives_patterns_room() {
+        // A glider heading down-right from near the bottom-right corner
+        let mut state = GameState::new(40, 40)
+            .apply(Command::PlacePattern { pattern: presets::glider(), x: 24, y: 24 })
+            .apply(Command::ToggleAutoExpand);
+        for _ in 0..40 {
+            state = state.apply(Command::Step);
+        }
+
+        let (width, height) = state.grid.dimensions();
+        assert!(width > 40 && height > 40, "{}x{}", width, height);
+        // The glider never reached the edge, so it survives intact
+        assert_eq!(state.grid.count_alive(), 5);
+        let (_, _, max_x, max_y) = state.grid.bounding_box().unwrap();
+        assert!(max_x + AUTO_EXPAND_MARGIN <= width && max_y + AUTO_EXPAND_MARGIN <= height);
+    }
+
+    #[test]
+    fn test_auto_expand_ignores_content_already_at_the_edge() {
+        // Blinkers tiled over the whole grid crowd every edge from the start
+        let mut state = GameState::new(40, 40);
+        for y in (1..40).step_by(4) {
+            for x in (0..40).step_by(4) {
+                state = state.apply(Command::PlacePattern { pattern: presets::blinker(), x, y });
+            }
+        }
+        state = state.apply_all([Command::ToggleAutoExpand, Command::SetStepSize(64)]);
+        assert_eq!(state.generations_per_update(), AUTO_EXPAND_BATCH);
+
+        for _ in 0..10 {
+            state = state.apply(Command::Step);
+        }
+        assert_eq!(state.grid.dimensions(), (40, 40));
+    }
+
+    #[test]
+    fn test_painting_while_evolving_in_background() {
+        let mut state = GameState::new(64, 64).with_background_worker().apply_all([
+            Command::PlacePattern { pattern: presets::block(), x: 10, y: 10 },
+            Command::TogglePlay,
+        ]);
+        // Start a generation evolving, then draw before it arrives
+        state = state.tick(1.0);
+        assert!(state.is_evolving());
+        let mut square = BitGrid::new(64, 64);
+        for (x, y) in [(40, 40), (41, 40), (40, 41), (41, 41)] {
+            square.set(x, y, true);
+        }
+        state = state.apply_all([
+            Command::PaintMask { mask: Mask::new((0, 0), square), alive: true },
+            Command::Paint { x: 10, y: 10, alive: false },
+        ]);
+        assert!(state.grid.get(40, 40) && !state.grid.get(10, 10));
+
+        let start = std::time::Instant::now();
+        while state.generation == 0 {
+            assert!(start.elapsed().as_secs() < 10, "generation never arrived");
+            state = state.tick(0.0);
+        }
+        // The generation was kept and the edits were replayed onto it
+        assert_eq!(state.generation, 1);
+        assert_eq!(state.grid.count_alive(), 7);
+        assert!(state.grid.get(41, 41) && !state.grid.get(10, 10));
+    }
+
+    #[test]
+    fn test_fill_while_evolving_keeps_the_region_shown() {
+        // A hollow box, which does not survive the next generation intact
+        let mut outline = BitGrid::new(20, 20);
+        for (x, y) in crate::domain::rectangle((2, 2), (6, 6), false, ((0, 0), (19, 19))) {
+            outline.set(x as usize, y as usize, true);
+        }
+        let mut state = GameState::new(20, 20).with_background_worker().apply_all([
+            Command::PaintMask { mask: Mask::new((0, 0), outline), alive: true },
+            Command::TogglePlay,
+        ]);
+        state = state.tick(1.0);
+        assert!(state.is_evolving());
+        let mut expected = state.grid.evolve(state.rule.as_ref());
+        expected.paint_mask(&flood_region(&state.grid, 4, 4), true);
+        state = state.apply(Command::FloodFill { x: 4, y: 4, alive: true });
+
+        let start = std::time::Instant::now();
+        while state.generation == 0 {
+            assert!(start.elapsed().as_secs() < 10, "generation never arrived");
+            state = state.tick(0.0);
+        }
+        // The 3x3 inside of the box was filled, not a region of the new grid
+        assert_eq!(state.grid.difference(&expected).count_alive(), 0);
+    }
+
+    #[test]
+    fn test_pausing_drops_the_generation_in_flight() {
+        let mut state = GameState::new(64, 64).with_background_worker().apply_all([
+            Command::PlacePattern { pattern: presets::blinker(), x: 10, y: 10 },
+            Command::TogglePlay,
+        ]);
+        state = state.tick(1.0);
+        assert!(state.is_evolving());
+        state = state.apply(Command::TogglePlay);
+
+        // Wait until the worker has finished, then keep ticking while paused
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        for _ in 0..10 {
+            state = state.tick(0.1);
+        }
+        assert_eq!(state.generation, 0);
+        // Still horizontal
+        assert!(state.grid.get(11, 11) && state.grid.get(12, 11) && !state.grid.get(11, 10));
+    }
+}
@@ -0,0 +1,193 @@
+//! Two simulations of the same initial grid stepped in lockstep.
+//!
+//! The left side is the reference; the right side usually runs another
+//! rule or another `Algorithm`. Both sides always show the same
+//! generation, and the first generation where their grids differ is
+//! recorded.
+
+use std::sync::Arc;
+
+use crate::domain::{Algorithm, BitGrid, Rule};
+use super::{Command, GameState};
+
+/// Side of a comparison
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+pub struct Comparison {
+    pub left: GameState,
+    pub right: GameState,
+    /// Whether cells that differ between the sides are highlighted
+    pub show_diff: bool,
+    /// First generation at which the grids differed (None = identical so far)
+    pub divergence: Option<u64>,
+    update_timer: f32,
+}
+
+impl Comparison {
+    /// Start from `base`'s grid and speed settings, with the right side
+    /// running `rule` and `algorithm`
+    pub fn new(base: &GameState, rule: Arc<dyn Rule>, algorithm: Algorithm) -> Self {
+        let side = |rule: Arc<dyn Rule>, algorithm: Algorithm| {
+            let (width, height) = base.grid.dimensions();
+            let mut state = GameState::new(width, height).with_grid(base.grid.clone());
+            state.set_rule(rule);
+            state.set_algorithm(algorithm);
+            state.updates_per_second = base.updates_per_second;
+            state.set_step_size(base.step_size)
+        };
+
+        Self {
+            left: side(base.rule.clone(), base.algorithm),
+            right: side(rule, algorithm),
+            show_diff: true,
+            divergence: None,
+            update_timer: 0.0,
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.left.is_running
+    }
+
+    /// Apply a command to both sides. Grid edits keep the sides identical
+    /// and restart divergence tracking.
+    pub fn apply(mut self, command: Command) -> Self {
+        match command {
+            Command::Step => return self.advance(1),
+            // Random fills differ per call; copy the left result instead
+            Command::Randomize => {
+                self.left = self.left.apply(Command::Randomize);
+                self.right = self.right.with_grid(self.left.grid.clone());
+            }
+            command => {
+                let mutates_grid = command.mutates_grid();
+                self.right = self.right.apply(command.clone());
+                self.left = self.left.apply(command);
+                if !mutates_grid {
+                    return self;
+                }
+            }
+        }
+
+        self.divergence = self.differs().then_some(self.left.generation);
+        self
+    }
+
+    /// Apply a command to one side only, e.g. `SetRule` or `SetAlgorithm`
+    pub fn apply_to(mut self, side: Side, command: Command) -> Self {
+        match side {
+            Side::Left => self.left = self.left.apply(command),
+            Side::Right => self.right = self.right.apply(command),
+        }
+        self
+    }
+
+    /// Toggle the diff overlay (builder pattern)
+    pub fn toggle_diff(mut self) -> Self {
+        self.show_diff = !self.show_diff;
+        self
+    }
+
+    /// Advance both sides when the shared update timer fires
+    pub fn tick(mut self, delta_time: f32) -> Self {
+        if !self.is_running() {
+            return self;
+        }
+
+        self.update_timer += delta_time;
+        if self.left.max_speed || self.update_timer >= 1.0 / self.left.updates_per_second {
+            self.update_timer = 0.0;
+            let generations = self.left.generations_per_update().min(self.right.generations_per_update());
+            self = self.advance(generations);
+        }
+        self
+    }
+
+    /// Advance both sides `generations` steps. Until the sides diverge they
+    /// are stepped one generation at a time so the divergence is exact.
+    pub fn advance(mut self, generations: u64) -> Self {
+        let mut remaining = generations;
+        while self.divergence.is_none() && remaining > 0 {
+            self.left = self.left.step();
+            self.right = self.right.step();
+            remaining -= 1;
+            if self.differs() {
+                self.divergence = Some(self.left.generation);
+            }
+        }
+
+        if remaining > 0 {
+            self.left = self.left.advance(remaining);
+            self.right = self.right.advance(remaining);
+        }
+        self
+    }
+
+    /// Whether the two grids currently differ
+    pub fn differs(&self) -> bool {
+        self.left.grid.count_changes(&self.right.grid) != (0, 0)
+    }
+
+    /// Cells that differ between the sides, alive in the returned grid
+    pub fn diff(&self) -> BitGrid {
+        self.left.grid.difference(&self.right.grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ConwayRule, HighLifeRule};
+
+    fn base_with(cells: &[(usize, usize)]) -> GameState {
+        let mut state = GameState::new(64, 64);
+        for &(x, y) in cells {
+            state = state.apply(Command::Paint { x, y, alive: true });
+        }
+        state
+    }
+
+    #[test]
+    fn test_divergence_reported_at_first_differing_generation() {
+        // Six neighbors around (10, 10): born under HighLife (B36), not Conway
+        let base = base_with(&[(9, 9), (10, 9), (11, 9), (9, 11), (10, 11), (11, 11)]);
+        let comparison = Comparison::new(&base, Arc::new(HighLifeRule), base.algorithm);
+        assert_eq!(comparison.divergence, None);
+
+        let comparison = comparison.advance(5);
+        assert_eq!(comparison.divergence, Some(1));
+        assert_eq!(comparison.left.generation, 5);
+        assert_eq!(comparison.right.generation, 5);
+        assert!(comparison.diff().count_alive() > 0);
+    }
+
+    #[test]
+    fn test_algorithms_agree() {
+        let base = GameState::new(128, 128).apply(Command::Randomize);
+        let comparison = Comparison::new(&base, Arc::new(ConwayRule), Algorithm::BitGridNaive)
+            .apply_to(Side::Left, Command::SetAlgorithm(Algorithm::SimdParallel))
+            .advance(20);
+
+        assert_eq!(comparison.divergence, None);
+        assert_eq!(comparison.left.grid.count_alive(), comparison.right.grid.count_alive());
+    }
+
+    #[test]
+    fn test_edits_apply_to_both_sides() {
+        let base = GameState::new(64, 64);
+        let comparison = Comparison::new(&base, Arc::new(HighLifeRule), base.algorithm)
+            .apply(Command::Randomize)
+            .apply(Command::Paint { x: 3, y: 4, alive: true });
+
+        assert!(!comparison.differs());
+        assert!(comparison.right.grid.get(3, 4));
+
+        let comparison = comparison.apply(Command::TogglePlay).tick(1.0);
+        assert!(comparison.is_running());
+        assert_eq!(comparison.left.generation, comparison.right.generation);
+    }
+}
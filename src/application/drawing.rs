@@ -0,0 +1,347 @@
+//! Drawing tools: the selected tool, brush and symmetry, and the stroke
+//! in progress. Input handlers report where the mouse button went down,
+//! moved and came up; the tools answer with the commands that edit the
+//! grid. A freehand stroke is interpolated between mouse positions, so
+//! fast drags leave no gaps. Everything a tool draws is built as one mask
+//! per mirror image, covering just the part of the grid drawn on, and
+//! painted with `PaintMask` commands.
+
+use crate::domain::{flood_region, line, rectangle, BitGrid, BrushShape, Mask, Symmetry};
+use super::Command;
+
+/// Corners (top-left, bottom-right, both included) of an area of cells
+type Area = ((isize, isize), (isize, isize));
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Freehand,
+    Line,
+    Rectangle,
+    FilledRectangle,
+    /// Flood fill the connected region under the cursor
+    Fill,
+}
+
+impl Tool {
+    pub fn all() -> [Tool; 5] {
+        [Tool::Freehand, Tool::Line, Tool::Rectangle, Tool::FilledRectangle, Tool::Fill]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Freehand => "Freehand",
+            Tool::Line => "Line",
+            Tool::Rectangle => "Rectangle",
+            Tool::FilledRectangle => "Filled rect",
+            Tool::Fill => "Fill",
+        }
+    }
+}
+
+/// A mouse drag that has not been committed yet
+#[derive(Clone, Copy, Debug)]
+struct Stroke {
+    start: (isize, isize),
+    last: (isize, isize),
+    alive: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct DrawingTools {
+    pub tool: Tool,
+    /// Brush width in cells (1 = single cell)
+    pub brush_size: usize,
+    pub brush_shape: BrushShape,
+    pub symmetry: Symmetry,
+    stroke: Option<Stroke>,
+}
+
+impl DrawingTools {
+    pub fn new() -> Self {
+        Self {
+            tool: Tool::Freehand,
+            brush_size: 1,
+            brush_shape: BrushShape::Square,
+            symmetry: Symmetry::None,
+            stroke: None,
+        }
+    }
+
+    /// Whether a stroke or shape is being dragged out
+    pub fn is_drawing(&self) -> bool {
+        self.stroke.is_some()
+    }
+
+    /// Drop the stroke in progress without drawing anything more
+    pub fn cancel(&mut self) {
+        self.stroke = None;
+    }
+
+    /// The mouse button went down over `cell`, drawing live (or dead)
+    /// cells on `grid`
+    pub fn press(&mut self, cell: (isize, isize), alive: bool, grid: &BitGrid) -> Vec<Command> {
+        let size = grid.dimensions();
+        match self.tool {
+            Tool::Fill => {
+                // Mirror images in the same region fill it once
+                let mut region = BitGrid::new(size.0, size.1);
+                for (x, y) in self.seeds(cell, size) {
+                    if grid.get(x, y) != alive && !region.get(x, y) {
+                        region.paint_mask(&flood_region(grid, x, y), true);
+                    }
+                }
+                Self::paint_commands(vec![Mask::new((0, 0), region)], alive)
+            }
+            Tool::Freehand => {
+                self.stroke = Some(Stroke { start: cell, last: cell, alive });
+                Self::paint_commands(self.stamp(&[cell], None, size), alive)
+            }
+            Tool::Line | Tool::Rectangle | Tool::FilledRectangle => {
+                self.stroke = Some(Stroke { start: cell, last: cell, alive });
+                Vec::new()
+            }
+        }
+    }
+
+    /// The mouse moved to `cell` with the button held
+    pub fn drag(&mut self, cell: (isize, isize), grid_size: (usize, usize)) -> Vec<Command> {
+        let Some(stroke) = self.stroke.as_mut() else {
+            return Vec::new();
+        };
+        let from = std::mem::replace(&mut stroke.last, cell);
+        let alive = stroke.alive;
+        if self.tool == Tool::Freehand && from != cell {
+            Self::paint_commands(self.stamp(&line(from, cell), None, grid_size), alive)
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// The mouse button came up over `cell`, finishing a line or rectangle
+    pub fn release(&mut self, cell: (isize, isize), grid_size: (usize, usize)) -> Vec<Command> {
+        let mut commands = self.drag(cell, grid_size);
+        if let Some(stroke) = self.stroke.take()
+            && let Some(masks) = self.shape(stroke.start, cell, grid_size)
+        {
+            commands.extend(Self::paint_commands(masks, stroke.alive));
+        }
+        commands
+    }
+
+    /// Masks of the cells the tool would draw with the mouse over `cell`:
+    /// the pending line or rectangle, the brush footprint, or the cells
+    /// a fill would start from
+    pub fn preview(&self, cell: (isize, isize), grid_size: (usize, usize)) -> Vec<Mask> {
+        if let Some(masks) = self.stroke.and_then(|stroke| self.shape(stroke.start, cell, grid_size)) {
+            return masks;
+        }
+        if self.tool == Tool::Fill {
+            let mut seed = BitGrid::new(1, 1);
+            seed.set(0, 0, true);
+            return self.seeds(cell, grid_size).into_iter().map(|at| Mask::new(at, seed.clone())).collect();
+        }
+        self.stamp(&[cell], None, grid_size)
+    }
+
+    /// Masks of the line or rectangle between two corners (None for the
+    /// other tools). Rectangles are clipped to the cells the brush can
+    /// reach on the grid before anything is listed.
+    fn shape(&self, start: (isize, isize), end: (isize, isize), grid_size: (usize, usize)) -> Option<Vec<Mask>> {
+        let reach = self.reach(grid_size);
+        match self.tool {
+            Tool::Line => Some(self.stamp(&line(start, end), None, grid_size)),
+            Tool::Rectangle => Some(self.stamp(&rectangle(start, end, false, reach), None, grid_size)),
+            Tool::FilledRectangle => {
+                let outline = rectangle(start, end, false, reach);
+                let inside = ((start.0.min(end.0), start.1.min(end.1)), (start.0.max(end.0), start.1.max(end.1)));
+                Some(self.stamp(&outline, Some(inside), grid_size))
+            }
+            Tool::Freehand | Tool::Fill => None,
+        }
+    }
+
+    /// A `PaintMask` command for each mask that draws anything
+    fn paint_commands(masks: Vec<Mask>, alive: bool) -> Vec<Command> {
+        masks
+            .into_iter()
+            .filter(|mask| mask.cells.count_alive() > 0)
+            .map(|mask| Command::PaintMask { mask, alive })
+            .collect()
+    }
+
+    /// Smallest and largest brush offset along either axis
+    fn brush_extent(&self) -> (isize, isize) {
+        let brush = self.brush_shape.offsets(self.brush_size);
+        let lo = brush.iter().map(|&(dx, dy)| dx.min(dy)).min().unwrap_or(0);
+        let hi = brush.iter().map(|&(dx, dy)| dx.max(dy)).max().unwrap_or(0);
+        (lo, hi)
+    }
+
+    /// Path cells from which the brush still touches the grid
+    fn reach(&self, (width, height): (usize, usize)) -> Area {
+        let (lo, hi) = self.brush_extent();
+        ((-hi, -hi), (width as isize - 1 - lo, height as isize - 1 - lo))
+    }
+
+    /// One mask per mirror image of the brush stamped along `path`, plus
+    /// the `filled` area (filled a row of chunks at a time). Each mask
+    /// covers the image's bounding box clipped to the grid, and images
+    /// entirely off the grid are left out.
+    fn stamp(&self, path: &[(isize, isize)], filled: Option<Area>, grid_size: (usize, usize)) -> Vec<Mask> {
+        let brush = self.brush_shape.offsets(self.brush_size);
+        let (lo, hi) = self.brush_extent();
+        let mut bounds = filled;
+        for &(x, y) in path {
+            let ((x0, y0), (x1, y1)) = bounds.unwrap_or(((x, y), (x, y)));
+            bounds = Some(((x0.min(x + lo), y0.min(y + lo)), (x1.max(x + hi), y1.max(y + hi))));
+        }
+        let Some(bounds) = bounds else {
+            return Vec::new();
+        };
+
+        let (width, height) = (grid_size.0 as isize, grid_size.1 as isize);
+        let mut masks = Vec::new();
+        for &(flip_x, flip_y) in self.symmetry.flips() {
+            let mirror = |(x, y): (isize, isize)| {
+                (if flip_x { width - 1 - x } else { x }, if flip_y { height - 1 - y } else { y })
+            };
+            let Some((left, top, right, bottom)) = clip(bounds, mirror, grid_size) else {
+                continue;
+            };
+            let mut cells = BitGrid::new(right - left + 1, bottom - top + 1);
+            let local = |x: isize, y: isize| ((x - left as isize) as usize, (y - top as isize) as usize);
+            if let Some(area) = filled
+                && let Some((x0, y0, x1, y1)) = clip(area, mirror, grid_size)
+            {
+                cells.fill_rect((x0 - left, y0 - top), (x1 - left, y1 - top));
+            }
+            for &(x, y) in path {
+                for &(dx, dy) in &brush {
+                    let (mx, my) = mirror((x + dx, y + dy));
+                    if (0..width).contains(&mx) && (0..height).contains(&my) {
+                        let (cx, cy) = local(mx, my);
+                        cells.set(cx, cy, true);
+                    }
+                }
+            }
+            masks.push(Mask::new((left, top), cells));
+        }
+        masks
+    }
+
+    /// `cell` and its mirror images, or nothing when it is off the grid
+    fn seeds(&self, (x, y): (isize, isize), grid_size: (usize, usize)) -> Vec<(usize, usize)> {
+        if x < 0 || y < 0 || x >= grid_size.0 as isize || y >= grid_size.1 as isize {
+            return Vec::new();
+        }
+        self.symmetry.images((x as usize, y as usize), grid_size)
+    }
+}
+
+/// `area` mirrored and clipped to a grid of `grid_size`, as (left, top,
+/// right, bottom), or None when none of it is on the grid
+fn clip(
+    area: Area,
+    mirror: impl Fn((isize, isize)) -> (isize, isize),
+    (width, height): (usize, usize),
+) -> Option<(usize, usize, usize, usize)> {
+    let (a, b) = (mirror(area.0), mirror(area.1));
+    let (left, right) = (a.0.min(b.0).max(0), a.0.max(b.0).min(width as isize - 1));
+    let (top, bottom) = (a.1.min(b.1).max(0), a.1.max(b.1).min(height as isize - 1));
+    (left <= right && top <= bottom).then_some((left as usize, top as usize, right as usize, bottom as usize))
+}
+
+impl Default for DrawingTools {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::GameState;
+
+    fn count(masks: &[Mask]) -> usize {
+        masks.iter().map(|mask| mask.cells.count_alive()).sum()
+    }
+
+    #[test]
+    fn test_fast_freehand_drag_leaves_no_gaps() {
+        let mut tools = DrawingTools::new();
+        let state = GameState::new(50, 50);
+        let mut commands = tools.press((2, 10), true, &state.grid);
+        commands.extend(tools.drag((20, 10), (50, 50)));
+        commands.extend(tools.release((20, 10), (50, 50)));
+
+        let state = state.apply_all(commands);
+        assert_eq!(state.grid.count_alive(), 19);
+        assert!((2..=20).all(|x| state.grid.get(x, 10)));
+    }
+
+    #[test]
+    fn test_rectangle_with_symmetry_and_brush() {
+        let mut tools = DrawingTools::new();
+        tools.tool = Tool::FilledRectangle;
+        tools.symmetry = Symmetry::Horizontal;
+        let state = GameState::new(20, 10);
+
+        assert!(tools.press((1, 1), true, &state.grid).is_empty());
+        assert_eq!(count(&tools.preview((3, 2), (20, 10))), 12);
+        let state = state.apply_all(tools.release((3, 2), (20, 10)));
+        assert_eq!(state.grid.count_alive(), 12);
+        assert!(state.grid.get(1, 1) && state.grid.get(18, 1) && state.grid.get(16, 2));
+        assert!(!tools.is_drawing());
+
+        // A 3-cell circle brush stamps a plus sign, clipped at the edge
+        tools.tool = Tool::Freehand;
+        tools.symmetry = Symmetry::None;
+        tools.brush_shape = BrushShape::Circle;
+        tools.brush_size = 3;
+        assert_eq!(count(&tools.preview((0, 5), (20, 10))), 4);
+    }
+
+    #[test]
+    fn test_huge_drag_is_clipped_to_the_grid() {
+        let mut tools = DrawingTools::new();
+        tools.tool = Tool::FilledRectangle;
+        tools.symmetry = Symmetry::FourWay;
+        tools.brush_size = 3;
+        let state = GameState::new(100, 80);
+
+        tools.press((-1_000_000_000, 40), true, &state.grid);
+        let preview = tools.preview((1_000_000_000, 1_000_000_000), (100, 80));
+        // Each image is only as large as the part of the grid it covers,
+        // the rectangle's lower half plus a row for the brush
+        assert!(preview.iter().all(|mask| mask.cells.dimensions() == (100, 41)));
+        let state = state.apply_all(tools.release((1_000_000_000, 1_000_000_000), (100, 80)));
+        assert_eq!(state.grid.count_alive(), 100 * 80);
+
+        // A brush stroke is masked around the brush only
+        tools.tool = Tool::Freehand;
+        tools.symmetry = Symmetry::None;
+        let preview = tools.preview((50, 79), (100, 80));
+        assert_eq!(preview.len(), 1);
+        assert_eq!((preview[0].origin, preview[0].cells.dimensions()), ((49, 78), (3, 2)));
+    }
+
+    #[test]
+    fn test_fill_tool_fills_enclosed_region() {
+        let mut tools = DrawingTools::new();
+        tools.tool = Tool::Rectangle;
+        let mut state = GameState::new(20, 20);
+        tools.press((5, 5), true, &state.grid);
+        state = state.apply_all(tools.release((9, 9), (20, 20)));
+        assert_eq!(state.grid.count_alive(), 16);
+
+        tools.tool = Tool::Fill;
+        let fill = tools.press((7, 7), true, &state.grid);
+        state = state.apply_all(fill);
+        assert_eq!(state.grid.count_alive(), 25);
+
+        // Filling with dead cells clears the connected live block
+        let clear = tools.press((5, 5), false, &state.grid);
+        state = state.apply_all(clear);
+        assert_eq!(state.grid.count_alive(), 0);
+    }
+}
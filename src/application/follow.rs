@@ -0,0 +1,227 @@
+//! Follow camera: keeps a moving object, or the whole population, in the
+//! middle of the view.
+//!
+//! The target is re-acquired whenever the generation changes. An object
+//! is searched for near where its last known velocity predicts it, so a
+//! spaceship stays locked even when several generations pass between
+//! frames. The camera eases toward the target instead of jumping, which
+//! hides the per-phase wobble of the centroid.
+
+use crate::domain::{object_near, population_centroid, BitGrid, LINK_DISTANCE};
+use super::Camera;
+
+/// How quickly the camera closes the distance to the target (per second).
+/// At 6 about 90% of the gap is covered in 0.4s.
+const FOLLOW_RATE: f32 = 6.0;
+
+/// Longest gap in generations the predicted position is trusted across
+const MAX_PREDICTION: u64 = 64;
+
+/// What the camera is locked onto
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FollowTarget {
+    /// The connected group of cells picked by the user
+    Object,
+    /// The centroid of every live cell
+    Population,
+}
+
+#[derive(Clone, Debug)]
+pub struct Follow {
+    pub target: FollowTarget,
+    /// Tracked centroid in cells (cell centers are at +0.5)
+    pub position: (f32, f32),
+    /// Half the tracked object's extent; zero when following the population
+    pub radius: (f32, f32),
+    /// Average movement in cells per generation
+    velocity: (f32, f32),
+    /// Generation `position` was measured at
+    generation: u64,
+    grid_size: (usize, usize),
+}
+
+impl Follow {
+    /// Lock onto the object with a live cell nearest `pos`, if any lies
+    /// within `search` cells
+    pub fn object(grid: &BitGrid, pos: (f32, f32), search: usize, generation: u64) -> Option<Self> {
+        let object = object_near(grid, pos, search)?;
+        Some(Self {
+            target: FollowTarget::Object,
+            position: object.centroid,
+            radius: object.radius,
+            velocity: (0.0, 0.0),
+            generation,
+            grid_size: grid.dimensions(),
+        })
+    }
+
+    /// Follow the centroid of the whole population (None if the grid is empty)
+    pub fn population(grid: &BitGrid, generation: u64) -> Option<Self> {
+        Some(Self {
+            target: FollowTarget::Population,
+            position: population_centroid(grid)?,
+            radius: (0.0, 0.0),
+            velocity: (0.0, 0.0),
+            generation,
+            grid_size: grid.dimensions(),
+        })
+    }
+
+    /// Carry on following after the grid was resized to `grid_size`,
+    /// moving its contents by `offset` cells
+    pub fn shift(&mut self, offset: (isize, isize), grid_size: (usize, usize)) {
+        self.position.0 += offset.0 as f32;
+        self.position.1 += offset.1 as f32;
+        self.grid_size = grid_size;
+    }
+
+    /// Re-acquire the target in `grid`, now at `generation`. Returns false
+    /// once the target is lost (the object died or the grid emptied).
+    pub fn update(&mut self, grid: &BitGrid, generation: u64) -> bool {
+        if grid.dimensions() != self.grid_size {
+            return false;
+        }
+        if generation == self.generation {
+            return true;
+        }
+        let elapsed = generation.abs_diff(self.generation).max(1);
+        self.generation = generation;
+
+        let centroid = match self.target {
+            FollowTarget::Population => population_centroid(grid),
+            FollowTarget::Object => {
+                let steps = elapsed.min(MAX_PREDICTION) as f32;
+                let predicted = (
+                    self.position.0 + self.velocity.0 * steps,
+                    self.position.1 + self.velocity.1 * steps,
+                );
+                let search = self.radius.0.max(self.radius.1).ceil() as usize
+                    + steps as usize
+                    + LINK_DISTANCE as usize;
+                object_near(grid, predicted, search).map(|object| {
+                    self.radius = object.radius;
+                    object.centroid
+                })
+            }
+        };
+        let Some(centroid) = centroid else {
+            return false;
+        };
+
+        // Blend in the new measurement: an object's centroid wobbles from
+        // phase to phase even when it moves at a constant speed
+        let moved = (
+            wrapped_delta(centroid.0, self.position.0, self.grid_size.0) / elapsed as f32,
+            wrapped_delta(centroid.1, self.position.1, self.grid_size.1) / elapsed as f32,
+        );
+        self.velocity = (
+            (self.velocity.0 + moved.0) / 2.0,
+            (self.velocity.1 + moved.1) / 2.0,
+        );
+        self.position = centroid;
+        true
+    }
+
+    /// Ease the camera toward the target over `delta_time` seconds. A
+    /// target that wrapped across the grid edge is jumped to directly.
+    pub fn steer(&self, camera: &mut Camera, viewport: (f32, f32), cell_size: f32, delta_time: f32) {
+        let (x, y) = camera.center_cell(viewport, cell_size);
+        let (dx, dy) = (self.position.0 - x, self.position.1 - y);
+        let wrapped = dx.abs() > self.grid_size.0 as f32 / 2.0 || dy.abs() > self.grid_size.1 as f32 / 2.0;
+
+        let t = if wrapped { 1.0 } else { 1.0 - (-FOLLOW_RATE * delta_time).exp() };
+        // center_on takes a cell index and centers its middle
+        camera.center_on(x + dx * t - 0.5, y + dy * t - 0.5, viewport, cell_size);
+    }
+}
+
+/// `to - from` along a toroidal axis of `size` cells, taking the short way
+fn wrapped_delta(to: f32, from: f32, size: usize) -> f32 {
+    let size = size as f32;
+    let delta = to - from;
+    if delta > size / 2.0 {
+        delta - size
+    } else if delta < -size / 2.0 {
+        delta + size
+    } else {
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::{Command, GameState};
+    use crate::domain::presets;
+
+    fn lwss_state(x: usize, y: usize) -> GameState {
+        let mut grid = BitGrid::new(64, 64);
+        presets::lwss().place_on(&mut grid, x, y);
+        GameState::new(64, 64).with_grid(grid)
+    }
+
+    #[test]
+    fn test_tracks_lwss_across_edge() {
+        // The LWSS moves left at c/2, so 20 generations carry it 10 cells
+        // and across the left edge
+        let mut state = lwss_state(4, 30);
+        let mut follow = Follow::object(&state.grid, (5.0, 31.0), 2, 0).expect("lwss");
+        let start = follow.position;
+
+        for _ in 0..20 {
+            state = state.step();
+            assert!(follow.update(&state.grid, state.generation));
+        }
+        let dx = wrapped_delta(follow.position.0, start.0, 64);
+        assert!((dx + 10.0).abs() < 1.5, "moved {dx}");
+        assert!(follow.position.0 > 32.0, "should have wrapped: {:?}", follow.position);
+        assert!((follow.position.1 - start.1).abs() < 1.5);
+    }
+
+    #[test]
+    fn test_tracks_lwss_across_skipped_generations() {
+        let mut state = lwss_state(40, 10);
+        let mut follow = Follow::object(&state.grid, (41.0, 11.0), 2, 0).expect("lwss");
+        let start = follow.position;
+
+        for _ in 0..5 {
+            state = state.advance(8);
+            assert!(follow.update(&state.grid, state.generation));
+        }
+        assert!((follow.position.0 - (start.0 - 20.0)).abs() < 1.5, "{:?}", follow.position);
+    }
+
+    #[test]
+    fn test_target_lost_when_cleared() {
+        let state = lwss_state(20, 20);
+        let mut follow = Follow::population(&state.grid, 0).expect("population");
+        assert_eq!(follow.target, FollowTarget::Population);
+
+        let state = state.apply(Command::Clear);
+        assert!(!follow.update(&state.grid, state.generation + 1));
+        assert!(Follow::object(&state.grid, (20.0, 20.0), 4, 0).is_none());
+    }
+
+    #[test]
+    fn test_steer_eases_then_arrives() {
+        let grid = {
+            let mut grid = BitGrid::new(64, 64);
+            presets::block().place_on(&mut grid, 40, 40);
+            grid
+        };
+        let follow = Follow::object(&grid, (40.0, 40.0), 1, 0).expect("block");
+        let (viewport, cell_size) = ((200.0, 200.0), 10.0);
+        let mut camera = Camera::new();
+        camera.center_on(30.0, 30.0, viewport, cell_size);
+
+        follow.steer(&mut camera, viewport, cell_size, 1.0 / 60.0);
+        let (x, _) = camera.center_cell(viewport, cell_size);
+        assert!(x > 30.5 && x < 41.0, "one frame should move part way: {x}");
+
+        for _ in 0..120 {
+            follow.steer(&mut camera, viewport, cell_size, 1.0 / 60.0);
+        }
+        let (x, y) = camera.center_cell(viewport, cell_size);
+        assert!((x - 41.0).abs() < 0.01 && (y - 41.0).abs() < 0.01, "{x}, {y}");
+    }
+}
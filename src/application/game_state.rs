@@ -1,19 +1,78 @@
-use crate::domain::{BitGrid, Grid, Cell, Rule, Algorithm, default_rule, simd_life, temporal_blocking};
+use std::sync::Arc;
+
+use crate::domain::{
+    BitGrid, Rule, Algorithm, GenerationStats, StatsHistory, CellHistory, HistoryKind,
+    Breakpoints, BreakpointHit, Mask, ProbeSet, ResizeAnchor, default_rule, MAX_GRID_CELLS,
+};
+use super::Command;
+use super::command::fill_region;
+use super::simulation_worker::{SimulationWorker, Watch, Watched};
+
+/// Largest number of generations a single update may advance
+pub const MAX_STEP_SIZE: u64 = 1 << 20;
+
+/// Evolution time per frame targeted by max speed mode
+const MAX_SPEED_FRAME_BUDGET_MS: f32 = 12.0;
+
+/// Auto-expand grows the grid once live cells come this close to an edge
+pub const AUTO_EXPAND_MARGIN: usize = 8;
+
+/// Most generations per update while auto-expand is on. Cells spread at
+/// most one cell per generation, so a batch this short cannot carry
+/// content from outside the margin to the wrapping edge.
+pub const AUTO_EXPAND_BATCH: u64 = AUTO_EXPAND_MARGIN as u64 - 1;
 
 /// GameState orchestrates the simulation.
 /// This is the application layer that coordinates domain logic.
 pub struct GameState {
     pub grid: BitGrid,
-    pub rule: Box<dyn Rule + Send + Sync>,
+    pub rule: Arc<dyn Rule>,
     pub algorithm: Algorithm,
     pub is_running: bool,
     pub generation: u64,
     pub update_timer: f32,
     pub updates_per_second: f32,
+    /// Generations advanced per update (1 = watch every generation)
+    pub step_size: u64,
+    /// Ignore the update rate and run as many generations as fit in a frame
+    pub max_speed: bool,
     pub last_evolution_time_ms: f32,  // Evolution performance metric
     pub last_render_time_ms: f32,     // Render performance metric
     /// Index of pattern pending placement (None = normal mode)
     pub pending_pattern_index: Option<usize>,
+    /// Per-generation population samples
+    pub stats: StatsHistory,
+    /// Whether the population graph overlay is drawn
+    pub show_stats_graph: bool,
+    /// Whether hovering a cell shows its neighbors, fate and object
+    pub show_inspector: bool,
+    /// Optional age / heat / trail layer (None = plain rendering)
+    pub cell_history: Option<CellHistory>,
+    /// Measured generations completed per second (independent of FPS)
+    pub generations_per_second: f32,
+    /// Run-until conditions checked after every step
+    pub breakpoints: Breakpoints,
+    /// Breakpoint that last stopped the simulation (cleared on resume)
+    pub breakpoint_hit: Option<BreakpointHit>,
+    /// Named cells whose state is recorded every generation
+    pub probes: ProbeSet,
+    /// Grow the grid around its contents when live cells near an edge
+    pub auto_expand: bool,
+    /// Background evolution thread (None = evolve on the calling thread)
+    worker: Option<SimulationWorker>,
+    /// Bumped whenever the grid is edited, so results computed from an
+    /// older grid are discarded instead of overwriting the edit
+    grid_epoch: u64,
+    /// Drawing done while a generation evolves in the background, replayed
+    /// onto that generation when it arrives
+    queued_edits: Vec<Command>,
+    /// Bumped whenever breakpoints or probes are added, removed or renamed,
+    /// so what a batch saw of older ones is not taken over
+    watch_version: u64,
+    rate_timer: f32,
+    rate_generations: u64,
+    /// Measured cost of one generation, used to size max speed batches
+    ms_per_generation: f32,
 }
 
 impl GameState {
@@ -21,27 +80,65 @@ impl GameState {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
             grid: BitGrid::new(width, height),
-            rule: default_rule(),
+            rule: Arc::from(default_rule()),
             algorithm: Algorithm::default(),
             is_running: false,
             generation: 0,
             update_timer: 0.0,
             updates_per_second: 10.0,
+            step_size: 1,
+            max_speed: false,
             last_evolution_time_ms: 0.0,
             last_render_time_ms: 0.0,
             pending_pattern_index: None,
+            stats: StatsHistory::default(),
+            show_stats_graph: true,
+            show_inspector: false,
+            cell_history: None,
+            generations_per_second: 0.0,
+            breakpoints: Breakpoints::new(),
+            breakpoint_hit: None,
+            probes: ProbeSet::default(),
+            auto_expand: false,
+            worker: None,
+            grid_epoch: 0,
+            queued_edits: Vec::new(),
+            watch_version: 0,
+            rate_timer: 0.0,
+            rate_generations: 0,
+            ms_per_generation: 0.0,
         }
+        .with_fresh_tracking()
     }
     
-    /// Resize grid to new dimensions
-    pub fn resize_grid(&mut self, width: usize, height: usize) {
-        self.grid = BitGrid::new(width, height);
-        self.generation = 0;
-        self.is_running = false;
+    /// Evolve on a background thread so rendering never waits (builder pattern)
+    pub fn with_background_worker(mut self) -> Self {
+        self.worker = Some(SimulationWorker::spawn());
+        self
+    }
+    
+    /// Resize grid to new dimensions. The contents stay where `anchor`
+    /// puts them (cropped when shrinking), and so do the probes and
+    /// located breakpoints; the generation counter and statistics carry on.
+    pub fn resize_grid(&mut self, width: usize, height: usize, anchor: ResizeAnchor) {
+        // A generation still evolving has the old size
+        self.bump_grid_epoch();
+        let offset = anchor.offset(self.grid.dimensions(), (width, height));
+        self.grid = self.grid.resized(width, height, anchor);
+        self.probes.shift(offset, (width, height));
+        self.breakpoints.shift(offset, (width, height));
+        // The history layer is per cell and starts over at the new size
+        self.set_history_kind(self.cell_history.as_ref().map(|h| h.kind()));
+    }
+    
+    /// Toggle growing the grid when live cells near an edge
+    pub fn toggle_auto_expand(mut self) -> Self {
+        self.auto_expand = !self.auto_expand;
+        self
     }
     
     /// Set the cellular automaton rule
-    pub fn set_rule(&mut self, rule: Box<dyn Rule + Send + Sync>) {
+    pub fn set_rule(&mut self, rule: Arc<dyn Rule>) {
         self.rule = rule;
     }
     
@@ -51,16 +148,21 @@ impl GameState {
     }
     
     /// Set running state (builder pattern)
-    #[allow(dead_code)]
     pub fn with_running(mut self, running: bool) -> Self {
         self.is_running = running;
+        if running {
+            self.breakpoint_hit = None;
+        } else if self.is_evolving() {
+            // The batch in flight would otherwise land after the pause
+            self.bump_grid_epoch();
+        }
         self
     }
     
     /// Toggle play/pause state
-    pub fn toggle_running(mut self) -> Self {
-        self.is_running = !self.is_running;
-        self
+    pub fn toggle_running(self) -> Self {
+        let running = !self.is_running;
+        self.with_running(running)
     }
     
     /// Clear grid and reset generation counter
@@ -68,7 +170,7 @@ impl GameState {
         self.grid.clear();
         self.generation = 0;
         self.is_running = false;
-        self
+        self.with_fresh_tracking()
     }
     
     /// Randomize grid and reset generation counter
@@ -76,6 +178,77 @@ impl GameState {
         self.grid.randomize();
         self.generation = 0;
         self.is_running = false;
+        self.with_fresh_tracking()
+    }
+    
+    /// Replace the grid and reset the generation counter (builder pattern)
+    pub fn with_grid(mut self, grid: BitGrid) -> Self {
+        self.bump_grid_epoch();
+        self.grid = grid;
+        self.generation = 0;
+        self.is_running = false;
+        self.with_fresh_tracking()
+    }
+    
+    /// Toggle the population graph overlay
+    pub fn toggle_stats_graph(mut self) -> Self {
+        self.show_stats_graph = !self.show_stats_graph;
+        self
+    }
+    
+    /// Toggle the cell inspector shown on hover
+    pub fn toggle_inspector(mut self) -> Self {
+        self.show_inspector = !self.show_inspector;
+        self
+    }
+    
+    /// Current population.
+    /// While running the latest sample is exact; when paused the grid may
+    /// have been painted since, so count it directly.
+    pub fn population(&self) -> usize {
+        match self.stats.latest() {
+            Some(sample) if self.is_running => sample.population,
+            _ => self.grid.count_alive(),
+        }
+    }
+    
+    /// Select which per-cell history layer to maintain (None disables it)
+    pub fn set_history_kind(&mut self, kind: Option<HistoryKind>) {
+        let trail_length = self.cell_history.as_ref().map(|h| h.trail_length());
+        self.cell_history = kind.map(|kind| {
+            let history = CellHistory::new(kind, &self.grid);
+            match trail_length {
+                Some(length) => history.with_trail_length(length),
+                None => history,
+            }
+        });
+    }
+    
+    /// Cycle plain -> age -> heat map -> trails -> changes -> plain
+    pub fn cycle_history_kind(mut self) -> Self {
+        let kinds = HistoryKind::all();
+        let next = match self.cell_history.as_ref().map(|h| h.kind()) {
+            None => Some(kinds[0]),
+            Some(current) => kinds.iter()
+                .position(|k| *k == current)
+                .and_then(|i| kinds.get(i + 1))
+                .copied(),
+        };
+        self.set_history_kind(next);
+        self
+    }
+    
+    /// Start a new statistics series and history layer from the current grid
+    fn restart_tracking(&mut self) {
+        self.stats.clear();
+        self.stats.record(GenerationStats::from_grid(&self.grid, self.generation));
+        self.probes.clear_samples();
+        self.probes.record(&self.grid, self.generation);
+        self.set_history_kind(self.cell_history.as_ref().map(|h| h.kind()));
+    }
+    
+    fn with_fresh_tracking(mut self) -> Self {
+        self.restart_tracking();
         self
     }
     
@@ -85,9 +258,62 @@ impl GameState {
         self
     }
     
+    /// Set how many generations each update advances
+    pub fn set_step_size(mut self, generations: u64) -> Self {
+        self.step_size = generations.clamp(1, MAX_STEP_SIZE);
+        self
+    }
+    
+    /// Move the step size to the next (or previous) power of two
+    pub fn scale_step_size(self, increase: bool) -> Self {
+        let current = self.step_size;
+        let next = if increase {
+            (current + 1).next_power_of_two()
+        } else if current.is_power_of_two() {
+            current / 2
+        } else {
+            current.next_power_of_two() / 2
+        };
+        self.set_step_size(next)
+    }
+    
+    /// Toggle running as fast as the frame budget allows
+    pub fn toggle_max_speed(mut self) -> Self {
+        self.max_speed = !self.max_speed;
+        self
+    }
+    
+    /// Average evolution cost of a single generation in the last update
+    pub fn ms_per_generation(&self) -> f32 {
+        self.ms_per_generation
+    }
+    
+    /// Generations the next update should advance.
+    /// In max speed mode this is sized from the measured per-generation
+    /// cost so one batch fits the frame budget. Auto-expand caps it at
+    /// `AUTO_EXPAND_BATCH`.
+    pub fn generations_per_update(&self) -> u64 {
+        let generations = if !self.max_speed {
+            self.step_size
+        } else if self.ms_per_generation <= 0.0 {
+            1
+        } else {
+            let fitting = (MAX_SPEED_FRAME_BUDGET_MS / self.ms_per_generation) as u64;
+            fitting.clamp(1, MAX_STEP_SIZE)
+        };
+        if self.auto_expand {
+            generations.min(AUTO_EXPAND_BATCH)
+        } else {
+            generations
+        }
+    }
+    
     /// Update simulation by one frame
     /// This is the main game loop coordination
     pub fn tick(mut self, delta_time: f32) -> Self {
+        self.collect_worker_result();
+        self.update_generation_rate(delta_time);
+        
         if !self.is_running {
             return self;
         }
@@ -95,75 +321,201 @@ impl GameState {
         self.update_timer += delta_time;
         let update_interval = 1.0 / self.updates_per_second;
         
-        if self.update_timer >= update_interval {
-            // Measure evolution time
-            let start = std::time::Instant::now();
-            
-            // Dispatch to selected algorithm
-            self.grid = match self.algorithm {
-                Algorithm::Original => {
-                    let grid = Self::bitgrid_to_grid(&self.grid);
-                    let evolved = grid.evolve(self.rule.as_ref());
-                    Self::grid_to_bitgrid(&evolved)
-                }
-                Algorithm::OriginalParallel => {
-                    let grid = Self::bitgrid_to_grid(&self.grid);
-                    let evolved = grid.evolve_parallel(self.rule.as_ref());
-                    Self::grid_to_bitgrid(&evolved)
-                }
-                Algorithm::BitGridNaive => {
-                    self.grid.evolve(self.rule.as_ref())
-                }
-                Algorithm::BitGridNaiveParallel => {
-                    self.grid.evolve_parallel(self.rule.as_ref())
-                }
-                Algorithm::Simd => {
-                    simd_life::evolve_simd(&self.grid, self.rule.as_ref())
-                }
-                Algorithm::SimdParallel => {
-                    simd_life::evolve_simd_parallel(&self.grid, self.rule.as_ref())
-                }
-                Algorithm::TemporalBlocking => {
-                    temporal_blocking::evolve_temporal_blocking(&self.grid, self.rule.as_ref(), 4)
+        if self.max_speed || self.update_timer >= update_interval {
+            // Never step past a generation target; other breakpoints are
+            // checked inside the batch
+            let generations = match self.breakpoints.max_batch(self.generation) {
+                Some(limit) => self.generations_per_update().min(limit),
+                None => self.generations_per_update(),
+            };
+            let watch = self.watch();
+            match self.worker.as_mut() {
+                // Previous batch still evolving; keep showing the front buffer
+                Some(worker) if worker.is_busy() => {}
+                Some(worker) => {
+                    // Edits so far are part of the submitted grid
+                    self.queued_edits.clear();
+                    worker.submit(&self.grid, self.rule.clone(), self.algorithm, generations, self.grid_epoch, watch);
+                    self.update_timer = 0.0;
                 }
-                Algorithm::TemporalBlockingParallel => {
-                    temporal_blocking::evolve_temporal_blocking_parallel(&self.grid, self.rule.as_ref(), 4)
+                None => {
+                    self.update_timer = 0.0;
+                    self = self.advance(generations);
                 }
-            };
-            
-            self.last_evolution_time_ms = start.elapsed().as_secs_f32() * 1000.0;
-            self.generation += 1;
-            self.update_timer = 0.0;
+            }
         }
         
         self
     }
     
-    /// Convert BitGrid to Grid for Original algorithms
-    fn bitgrid_to_grid(bg: &BitGrid) -> Grid {
-        let (w, h) = bg.dimensions();
-        let mut grid = Grid::new(w, h);
-        for y in 0..h {
-            for x in 0..w {
-                if bg.get(x, y) {
-                    grid.set(x, y, Cell::Alive);
-                }
+    /// Advance exactly one generation on the calling thread
+    pub fn step(self) -> Self {
+        self.advance(1)
+    }
+    
+    /// Advance `generations` steps on the calling thread, stopping early
+    /// at a breakpoint
+    pub fn advance(mut self, generations: u64) -> Self {
+        // Measure evolution time
+        let start = std::time::Instant::now();
+        let (next, generations, watched) =
+            self.watch().evolve(&self.grid, self.rule.as_ref(), self.algorithm, generations);
+        self.record_evolution_time(start.elapsed().as_secs_f32() * 1000.0, generations);
+        
+        self.commit_generations(next, generations, watched);
+        self
+    }
+    
+    /// What the next batch checks after every generation
+    fn watch(&self) -> Watch {
+        Watch {
+            breakpoints: self.breakpoints.clone(),
+            probe_cells: self.probes.iter().map(|probe| (probe.x, probe.y)).collect(),
+            generation: self.generation,
+            version: self.watch_version,
+        }
+    }
+    
+    /// Note that breakpoints or probes changed
+    pub(crate) fn bump_watch_version(&mut self) {
+        self.watch_version += 1;
+    }
+    
+    /// Invalidate any generation currently evolving in the background
+    pub(crate) fn bump_grid_epoch(&mut self) {
+        self.grid_epoch += 1;
+        self.queued_edits.clear();
+        self.breakpoints.reset_history();
+    }
+    
+    /// Identifies what the front grid holds: it changes with every new
+    /// generation, edit and rule change
+    pub(crate) fn grid_version(&self) -> (u64, u64, usize) {
+        (self.generation, self.grid_epoch, self.queued_edits.len())
+    }
+    
+    /// Whether a generation is evolving in the background
+    pub(crate) fn is_evolving(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| w.is_busy())
+    }
+    
+    /// Remember drawing done on the front buffer, to be applied to the
+    /// evolving generation between generations. A fill is kept as the
+    /// region it covers on the front buffer, which the user saw, rather
+    /// than redone on the new generation.
+    pub(crate) fn queue_edit(&mut self, command: Command) {
+        let command = match command {
+            Command::FloodFill { x, y, alive } => match fill_region(&self.grid, x, y, alive) {
+                Some(region) => Command::PaintMask { mask: Mask::new((0, 0), region), alive },
+                None => return,
+            },
+            other => other,
+        };
+        self.queued_edits.push(command);
+        self.breakpoints.reset_history();
+    }
+    
+    /// Swap in a finished background generation if one is ready
+    fn collect_worker_result(&mut self) {
+        let Some(evolved) = self.worker.as_mut().and_then(|w| w.try_take()) else {
+            return;
+        };
+        
+        // The grid was edited after the job started; drop the stale result
+        if evolved.epoch != self.grid_epoch {
+            return;
+        }
+        
+        // Drawing done while it evolved lands on the new generation
+        let mut grid = evolved.grid;
+        let edited = !self.queued_edits.is_empty();
+        for edit in self.queued_edits.drain(..) {
+            edit.draw_on(&mut grid);
+        }
+        self.record_evolution_time(evolved.elapsed_ms, evolved.generations);
+        self.commit_generations(grid, evolved.generations, evolved.watched);
+        if edited {
+            self.probes.record(&self.grid, self.generation);
+            self.breakpoints.reset_history();
+        }
+    }
+    
+    fn record_evolution_time(&mut self, elapsed_ms: f32, generations: u64) {
+        self.last_evolution_time_ms = elapsed_ms;
+        self.ms_per_generation = elapsed_ms / generations.max(1) as f32;
+    }
+    
+    /// Make `next` (which is `generations` ahead) the current grid and
+    /// record bookkeeping. When generations are skipped, births, deaths
+    /// and the history layer reflect the net change across the skip;
+    /// breakpoints and probes were looked at inside the batch (`watched`).
+    fn commit_generations(&mut self, next: BitGrid, generations: u64, watched: Watched) {
+        self.generation += generations;
+        self.rate_generations += generations;
+        
+        let previous = std::mem::replace(&mut self.grid, next);
+        self.stats.record(GenerationStats::between(&previous, &self.grid, self.generation));
+        if let Some(history) = self.cell_history.as_mut() {
+            history.update(&previous, &self.grid);
+        }
+        
+        let hit = if watched.version == self.watch_version {
+            self.breakpoints = watched.breakpoints;
+            for (generation, states) in &watched.probe_samples {
+                self.probes.record_states(*generation, states);
             }
+            watched.hit
+        } else {
+            // Changed while the batch evolved; only the end can be checked
+            self.probes.record(&self.grid, self.generation);
+            self.breakpoints.check(&previous, &self.grid, self.generation)
+        };
+        if let Some(hit) = hit {
+            self.is_running = false;
+            self.breakpoint_hit = Some(hit);
+        }
+        if self.auto_expand {
+            self.expand_to_fit(&previous);
         }
-        grid
     }
     
-    /// Convert Grid to BitGrid after evolution
-    fn grid_to_bitgrid(g: &Grid) -> BitGrid {
-        let (w, h) = g.dimensions();
-        let mut bg = BitGrid::new(w, h);
-        for y in 0..h {
-            for x in 0..w {
-                if g.get(x, y) == Some(Cell::Alive) {
-                    bg.set(x, y, true);
-                }
+    /// Grow the grid by half around its contents along each axis where a
+    /// live cell came within `AUTO_EXPAND_MARGIN` of an edge since
+    /// `previous`, so patterns get room instead of wrapping around.
+    /// Content that already crowded the edge (such as a soup filling the
+    /// grid) does not grow it again every generation. Stops at
+    /// `MAX_GRID_CELLS`.
+    fn expand_to_fit(&mut self, previous: &BitGrid) {
+        let Some((min_x, min_y, max_x, max_y)) = self.grid.bounding_box() else {
+            return;
+        };
+        let before = previous.bounding_box();
+        let (width, height) = self.grid.dimensions();
+        let crowded = |size: usize, (min, max): (usize, usize)| {
+            min < AUTO_EXPAND_MARGIN || max + AUTO_EXPAND_MARGIN >= size
+        };
+        let grow = |size: usize, before: Option<(usize, usize)>, now: (usize, usize)| {
+            if crowded(size, now) && !before.is_some_and(|span| crowded(size, span)) {
+                size + (size / 2).max(2 * AUTO_EXPAND_MARGIN)
+            } else {
+                size
             }
+        };
+        
+        let new_width = grow(width, before.map(|(x0, _, x1, _)| (x0, x1)), (min_x, max_x));
+        let new_height = grow(height, before.map(|(_, y0, _, y1)| (y0, y1)), (min_y, max_y));
+        if (new_width, new_height) != (width, height) && new_width.saturating_mul(new_height) <= MAX_GRID_CELLS {
+            self.resize_grid(new_width, new_height, ResizeAnchor::Center);
+        }
+    }
+    
+    /// Refresh `generations_per_second` about once a second
+    fn update_generation_rate(&mut self, delta_time: f32) {
+        self.rate_timer += delta_time;
+        if self.rate_timer >= 1.0 {
+            self.generations_per_second = self.rate_generations as f32 / self.rate_timer;
+            self.rate_timer = 0.0;
+            self.rate_generations = 0;
         }
-        bg
     }
 }
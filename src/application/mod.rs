@@ -1,5 +1,16 @@
 mod game_state;
 mod camera;
+mod command;
+mod simulation_worker;
+mod comparison;
+mod follow;
+mod rule_editor;
+mod drawing;
 
-pub use game_state::GameState;
-pub use camera::Camera;
+pub use game_state::{GameState, MAX_STEP_SIZE};
+pub use camera::{Camera, Bookmark, Bookmarks, BOOKMARK_SLOTS};
+pub use command::Command;
+pub use comparison::{Comparison, Side};
+pub use follow::{Follow, FollowTarget};
+pub use rule_editor::{RuleEditor, RULE_HISTORY_LEN};
+pub use drawing::{DrawingTools, Tool};
@@ -0,0 +1,99 @@
+//! State behind the rule editor: random and mutated rules for exploring
+//! rule space, and a short history of the rules recently in use.
+
+use rand::Rng;
+
+use crate::domain::{LifeLikeRule, Rule};
+
+/// Rules kept in the history, including the current one
+pub const RULE_HISTORY_LEN: usize = 8;
+
+#[derive(Clone, Debug, Default)]
+pub struct RuleEditor {
+    /// Most recent first; the front is the rule currently in use
+    history: Vec<LifeLikeRule>,
+}
+
+impl RuleEditor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note the rule in use. Called every frame; when the rule changed,
+    /// however it was chosen, it moves to the front of the history.
+    pub fn observe(&mut self, rule: &dyn Rule) {
+        let rule = LifeLikeRule::from_rule(rule);
+        if self.history.first() == Some(&rule) {
+            return;
+        }
+        self.history.retain(|r| *r != rule);
+        self.history.insert(0, rule);
+        self.history.truncate(RULE_HISTORY_LEN);
+    }
+
+    /// Rules used before the current one, most recent first
+    pub fn recent(&self) -> &[LifeLikeRule] {
+        self.history.get(1..).unwrap_or_default()
+    }
+
+    /// A random rule. B0 is left out: births on empty neighborhoods make
+    /// the whole grid flash.
+    pub fn random_rule(rng: &mut impl Rng) -> LifeLikeRule {
+        let birth = rng.random_range(1..=LifeLikeRule::MASK) & !1;
+        let birth = if birth == 0 { 1 << 3 } else { birth };
+        LifeLikeRule::new(birth, rng.random_range(0..=LifeLikeRule::MASK))
+    }
+
+    /// `rule` with one neighbor count (other than B0) flipped
+    pub fn mutate(rule: &dyn Rule, rng: &mut impl Rng) -> LifeLikeRule {
+        let rule = LifeLikeRule::from_rule(rule);
+        // 1-8 are births, 9-17 survivals 0-8
+        match rng.random_range(1..18usize) {
+            n @ 1..=8 => rule.with_birth(n, rule.birth() & (1 << n) == 0),
+            n => {
+                let n = n - 9;
+                rule.with_survival(n, rule.survival() & (1 << n) == 0)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ConwayRule, HighLifeRule, SeedsRule};
+
+    #[test]
+    fn test_history_keeps_recent_rules_once() {
+        let mut editor = RuleEditor::new();
+        editor.observe(&ConwayRule);
+        editor.observe(&ConwayRule);
+        assert!(editor.recent().is_empty());
+
+        editor.observe(&HighLifeRule);
+        editor.observe(&SeedsRule);
+        editor.observe(&ConwayRule);
+        let names: Vec<&str> = editor.recent().iter().map(|r| r.name()).collect();
+        assert_eq!(names, ["B2/S", "B36/S23"]);
+
+        for birth in 1..20 {
+            editor.observe(&LifeLikeRule::new(birth, 0));
+        }
+        assert_eq!(editor.recent().len(), RULE_HISTORY_LEN - 1);
+    }
+
+    #[test]
+    fn test_random_and_mutated_rules() {
+        let mut rng = rand::rng();
+        for _ in 0..100 {
+            let rule = RuleEditor::random_rule(&mut rng);
+            assert_eq!(rule.birth() & 1, 0);
+            assert_ne!(rule.birth(), 0);
+
+            let mutated = RuleEditor::mutate(&rule, &mut rng);
+            let flipped = (rule.birth() ^ mutated.birth()).count_ones() + (rule.survival() ^ mutated.survival()).count_ones();
+            assert_eq!(flipped, 1);
+            assert_eq!(mutated.birth() & 1, 0);
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+use crate::domain::{Algorithm, BitGrid, BreakpointHit, Breakpoints, Rule};
+
+/// What a batch looks at after every generation it evolves: breakpoints
+/// are checked and probe cells sampled one generation at a time, so a
+/// batch stops exactly where a condition fires and probes see every
+/// generation, not just the batch boundaries
+#[derive(Clone, Debug, Default)]
+pub struct Watch {
+    pub breakpoints: Breakpoints,
+    /// Probe cells, in probe order
+    pub probe_cells: Vec<(usize, usize)>,
+    /// Generation counter of the submitted grid
+    pub generation: u64,
+    /// Version of the breakpoints and probes this was taken from
+    pub version: u64,
+}
+
+/// What a watched batch saw
+#[derive(Debug)]
+pub struct Watched {
+    /// The breakpoints after checking every generation of the batch
+    pub breakpoints: Breakpoints,
+    /// The breakpoint the batch stopped at
+    pub hit: Option<BreakpointHit>,
+    /// Probe states per generation: `(generation, states)` with one state
+    /// per probe cell
+    pub probe_samples: Vec<(u64, Vec<bool>)>,
+    pub version: u64,
+}
+
+impl Watch {
+    /// Evolve `grid` by up to `generations` steps, stopping right after a
+    /// breakpoint fires. Returns the grid, the number of generations it is
+    /// ahead of `grid` and what was seen on the way. With nothing to look
+    /// at in between, the batch runs in one go and is checked at the end.
+    pub fn evolve(self, grid: &BitGrid, rule: &dyn Rule, algorithm: Algorithm, generations: u64) -> (BitGrid, u64, Watched) {
+        let Watch { mut breakpoints, probe_cells, generation, version } = self;
+        let mut watched = Watched { breakpoints: Breakpoints::new(), hit: None, probe_samples: Vec::new(), version };
+
+        if !breakpoints.needs_every_generation() && probe_cells.is_empty() {
+            let next = algorithm.evolve_generations(grid, rule, generations);
+            watched.hit = breakpoints.check(grid, &next, generation + generations);
+            watched.breakpoints = breakpoints;
+            return (next, generations, watched);
+        }
+
+        let mut current = grid.clone();
+        for step in 1..=generations {
+            let next = algorithm.evolve_generations(&current, rule, 1);
+            let states = probe_cells.iter().map(|&(x, y)| next.get(x, y)).collect();
+            watched.probe_samples.push((generation + step, states));
+            watched.hit = breakpoints.check(&current, &next, generation + step);
+            current = next;
+            if watched.hit.is_some() {
+                watched.breakpoints = breakpoints;
+                return (current, step, watched);
+            }
+        }
+        watched.breakpoints = breakpoints;
+        (current, generations, watched)
+    }
+}
+
+/// Evolution request handed to the worker thread
+struct Job {
+    grid: BitGrid,
+    rule: Arc<dyn Rule>,
+    algorithm: Algorithm,
+    generations: u64,
+    /// Grid epoch the job was started from (see `GameState::apply`)
+    epoch: u64,
+    watch: Watch,
+}
+
+/// Finished generation published by the worker
+pub struct EvolvedGrid {
+    pub grid: BitGrid,
+    /// Number of generations `grid` is ahead of the submitted one (fewer
+    /// than asked for when a breakpoint fired)
+    pub generations: u64,
+    pub epoch: u64,
+    pub elapsed_ms: f32,
+    pub watched: Watched,
+}
+
+/// Runs evolution on a dedicated thread so the render loop never blocks.
+///
+/// The caller keeps the current grid as the front buffer for drawing and
+/// submits a copy; the evolved back buffer is picked up with `try_take`
+/// once it is ready. At most one job is in flight at a time.
+pub struct SimulationWorker {
+    jobs: Sender<Job>,
+    results: Receiver<EvolvedGrid>,
+    busy: bool,
+}
+
+impl SimulationWorker {
+    /// Spawn the worker thread. It exits when the worker is dropped.
+    pub fn spawn() -> Self {
+        let (job_tx, job_rx) = mpsc::channel::<Job>();
+        let (result_tx, result_rx) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("simulation".into())
+            .spawn(move || {
+                for job in job_rx {
+                    let start = Instant::now();
+                    let (grid, generations, watched) =
+                        job.watch.evolve(&job.grid, job.rule.as_ref(), job.algorithm, job.generations);
+                    let evolved = EvolvedGrid {
+                        grid,
+                        generations,
+                        epoch: job.epoch,
+                        elapsed_ms: start.elapsed().as_secs_f32() * 1000.0,
+                        watched,
+                    };
+                    if result_tx.send(evolved).is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn simulation thread");
+
+        Self {
+            jobs: job_tx,
+            results: result_rx,
+            busy: false,
+        }
+    }
+
+    /// True while a submitted job has not been collected yet
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    /// Start evolving a copy of `grid` by `generations` steps, looking at
+    /// every generation as `watch` asks. Ignored if a job is already running.
+    pub fn submit(&mut self, grid: &BitGrid, rule: Arc<dyn Rule>, algorithm: Algorithm, generations: u64, epoch: u64, watch: Watch) {
+        if self.busy {
+            return;
+        }
+        let job = Job { grid: grid.clone(), rule, algorithm, generations, epoch, watch };
+        self.busy = self.jobs.send(job).is_ok();
+    }
+
+    /// Collect the finished generation, if any, without blocking
+    pub fn try_take(&mut self) -> Option<EvolvedGrid> {
+        match self.results.try_recv() {
+            Ok(evolved) => {
+                self.busy = false;
+                Some(evolved)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.busy = false;
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ConwayRule;
+
+    /// Poll until the in-flight job finishes
+    fn wait_for(worker: &mut SimulationWorker) -> EvolvedGrid {
+        loop {
+            if let Some(evolved) = worker.try_take() {
+                return evolved;
+            }
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn test_worker_matches_direct_evolution() {
+        let mut grid = BitGrid::new(100, 100);
+        grid.randomize();
+        let rule: Arc<dyn Rule> = Arc::new(ConwayRule);
+
+        let mut worker = SimulationWorker::spawn();
+        worker.submit(&grid, rule.clone(), Algorithm::SimdParallel, 3, 7, Watch::default());
+        assert!(worker.is_busy());
+
+        let evolved = wait_for(&mut worker);
+        assert!(!worker.is_busy());
+        assert_eq!(evolved.epoch, 7);
+        assert_eq!(evolved.generations, 3);
+
+        let expected = Algorithm::Simd.evolve_generations(&grid, rule.as_ref(), 3);
+        assert_eq!(evolved.grid.chunks(), expected.chunks());
+    }
+
+    #[test]
+    fn test_submit_ignored_while_busy() {
+        let grid = BitGrid::new(10, 10);
+        let rule: Arc<dyn Rule> = Arc::new(ConwayRule);
+
+        let mut worker = SimulationWorker::spawn();
+        worker.submit(&grid, rule.clone(), Algorithm::Simd, 1, 1, Watch::default());
+        worker.submit(&grid, rule, Algorithm::Simd, 1, 2, Watch::default());
+
+        assert_eq!(wait_for(&mut worker).epoch, 1);
+        assert!(worker.try_take().is_none());
+    }
+
+    #[test]
+    fn test_batch_stops_at_the_first_breakpoint() {
+        // A glider reaches (8, 8) after some generations
+        let mut grid = BitGrid::new(20, 20);
+        for (x, y) in [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)] {
+            grid.set(x, y, true);
+        }
+        let rule: Arc<dyn Rule> = Arc::new(ConwayRule);
+        let mut watch = Watch { generation: 10, probe_cells: vec![(2, 2)], ..Watch::default() };
+        watch.breakpoints.add(crate::domain::Condition::CellAlive { x: 8, y: 8 });
+
+        let mut worker = SimulationWorker::spawn();
+        worker.submit(&grid, rule.clone(), Algorithm::Simd, 1000, 0, watch);
+        let evolved = wait_for(&mut worker);
+
+        let hit = evolved.watched.hit.expect("glider arrives");
+        assert_eq!(hit.generation, 10 + evolved.generations);
+        assert!(evolved.generations < 1000 && evolved.grid.get(8, 8));
+        let before = Algorithm::Simd.evolve_generations(&grid, rule.as_ref(), evolved.generations - 1);
+        assert!(!before.get(8, 8), "stopped at the first generation it fired");
+        // One probe sample per generation taken
+        assert_eq!(evolved.watched.probe_samples.len() as u64, evolved.generations);
+        assert_eq!(evolved.watched.probe_samples[0], (11, vec![grid.evolve(rule.as_ref()).get(2, 2)]));
+    }
+}
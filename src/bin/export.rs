@@ -0,0 +1,229 @@
+//! Headless image export.
+//!
+//! Renders a pattern (or a random soup) to a PNG snapshot, or to an
+//! animated PNG covering a range of generations, without opening a window.
+//!
+//! ```text
+//! export --pattern glider --frames 32 --scale 8 -o glider.png
+//! export --random --size 512x512 --skip 100 --theme print -o soup.png
+//! ```
+
+use std::process::ExitCode;
+
+use game_of_life::domain::{all_rules, presets, Algorithm, BitGrid, CellRect, LifeLikeRule, Rule};
+use game_of_life::export::{content_rect, fit_region, render_region, save_apng, save_png, union_rect, Theme, MAX_IMAGE_PIXELS};
+
+const USAGE: &str = "\
+Usage: export [options] -o OUT.png
+
+Source:
+  --pattern NAME     Preset pattern placed at the grid center (default: glider)
+  --random           Random soup instead of a pattern
+  --size WxH         Grid size in cells (default: 256x256)
+  --rule NAME        Rule name or B/S notation, e.g. HighLife, B36/S23 (default: Conway)
+  --skip N           Generations to run before the first frame (default: 0)
+
+Output:
+  --frames N         Number of frames; more than one writes an APNG (default: 1)
+  --step K           Generations between frames (default: 1)
+  --delay MS         Frame delay in milliseconds (default: 100)
+  --scale S          Pixels per cell (default: 4)
+  --theme NAME       dark, light or print (default: dark)
+  --region X,Y,W,H   Cells to render (default: live cells plus a margin)
+  --margin M         Margin around live cells (default: 4)
+  -o, --output PATH  Output file";
+
+/// Parsed command line
+struct Options {
+    pattern: String,
+    random: bool,
+    size: (usize, usize),
+    rule: String,
+    skip: u64,
+    frames: usize,
+    step: u64,
+    delay_ms: u16,
+    scale: usize,
+    theme: Theme,
+    region: Option<CellRect>,
+    margin: usize,
+    output: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            pattern: "glider".to_string(),
+            random: false,
+            size: (256, 256),
+            rule: "Conway".to_string(),
+            skip: 0,
+            frames: 1,
+            step: 1,
+            delay_ms: 100,
+            scale: 4,
+            theme: Theme::default(),
+            region: None,
+            margin: 4,
+            output: String::new(),
+        }
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+        match flag.as_str() {
+            "--pattern" => options.pattern = value()?,
+            "--random" => options.random = true,
+            "--size" => options.size = parse_size(&value()?)?,
+            "--rule" => options.rule = value()?,
+            "--skip" => options.skip = parse_number(&value()?, "--skip")?,
+            "--frames" => options.frames = parse_number::<usize>(&value()?, "--frames")?.max(1),
+            "--step" => options.step = parse_number::<u64>(&value()?, "--step")?.max(1),
+            "--delay" => options.delay_ms = parse_number(&value()?, "--delay")?,
+            "--scale" => options.scale = parse_number::<usize>(&value()?, "--scale")?.max(1),
+            "--theme" => {
+                let name = value()?;
+                options.theme = Theme::by_name(&name).ok_or_else(|| format!("unknown theme '{}'", name))?;
+            }
+            "--region" => options.region = Some(parse_region(&value()?)?),
+            "--margin" => options.margin = parse_number(&value()?, "--margin")?,
+            "-o" | "--output" => options.output = value()?,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("unknown option '{}'\n\n{}", other, USAGE)),
+        }
+    }
+
+    if options.output.is_empty() {
+        return Err(format!("missing --output\n\n{}", USAGE));
+    }
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str, flag: &str) -> Result<T, String> {
+    text.trim().parse().map_err(|_| format!("{}: invalid number '{}'", flag, text))
+}
+
+/// Parse "WxH"
+fn parse_size(text: &str) -> Result<(usize, usize), String> {
+    let (w, h) = text.split_once(['x', 'X']).ok_or_else(|| format!("--size: expected WxH, got '{}'", text))?;
+    let size = (parse_number(w, "--size")?, parse_number(h, "--size")?);
+    if size.0 == 0 || size.1 == 0 {
+        return Err("--size: dimensions must be positive".to_string());
+    }
+    Ok(size)
+}
+
+/// Parse "X,Y,W,H" into an inclusive cell rectangle
+fn parse_region(text: &str) -> Result<CellRect, String> {
+    let parts = text
+        .split(',')
+        .map(|part| parse_number::<usize>(part, "--region"))
+        .collect::<Result<Vec<_>, _>>()?;
+    match parts[..] {
+        [x, y, w, h] if w > 0 && h > 0 => match (x.checked_add(w - 1), y.checked_add(h - 1)) {
+            (Some(max_x), Some(max_y)) => Ok((x, y, max_x, max_y)),
+            _ => Err(format!("--region: '{}' runs past the largest coordinate", text)),
+        },
+        _ => Err(format!("--region: expected X,Y,W,H with positive size, got '{}'", text)),
+    }
+}
+
+fn find_rule(name: &str) -> Result<Box<dyn Rule>, String> {
+    if let Some((_, rule)) = all_rules().into_iter().find(|(rule_name, _)| rule_name.eq_ignore_ascii_case(name)) {
+        return Ok(rule);
+    }
+    LifeLikeRule::parse(name)
+        .map(|rule| Box::new(rule) as Box<dyn Rule>)
+        .map_err(|err| format!("unknown rule '{}': {}", name, err))
+}
+
+fn initial_grid(options: &Options) -> Result<BitGrid, String> {
+    let (width, height) = options.size;
+    let mut grid = BitGrid::new(width, height);
+    if options.random {
+        grid.randomize();
+        return Ok(grid);
+    }
+
+    let pattern = presets::all_patterns()
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(&options.pattern))
+        .ok_or_else(|| format!("unknown pattern '{}'", options.pattern))?;
+    if pattern.width > width || pattern.height > height {
+        return Err(format!("pattern '{}' does not fit a {}x{} grid", pattern.name, width, height));
+    }
+    pattern.place_on(&mut grid, (width - pattern.width) / 2, (height - pattern.height) / 2);
+    Ok(grid)
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let rule = find_rule(&options.rule)?;
+    let algorithm = Algorithm::default();
+
+    let mut grid = initial_grid(&options)?;
+    if options.skip > 0 {
+        grid = algorithm.evolve_generations(&grid, rule.as_ref(), options.skip);
+    }
+
+    let mut grids = vec![grid];
+    for _ in 1..options.frames {
+        let next = algorithm.evolve_generations(grids.last().expect("first frame"), rule.as_ref(), options.step);
+        grids.push(next);
+    }
+
+    // One region for every frame, so moving patterns stay in view
+    let region = options.region.unwrap_or_else(|| {
+        grids
+            .iter()
+            .map(|grid| content_rect(grid, options.margin))
+            .reduce(union_rect)
+            .expect("at least one frame")
+    });
+
+    // Like GUI snapshots, large images get a smaller scale; a region
+    // too big even at one pixel per cell is refused rather than cropped
+    let (fitted, scale) = fit_region(region, options.scale, MAX_IMAGE_PIXELS);
+    if fitted != region {
+        return Err(format!("region is more than {} cells; pick a smaller --region", MAX_IMAGE_PIXELS));
+    }
+    if scale < options.scale {
+        println!("Scale lowered to {} to stay within {} pixels", scale, MAX_IMAGE_PIXELS);
+    }
+    let frames = grids
+        .iter()
+        .map(|grid| render_region(grid, region, scale, &options.theme))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let result = if frames.len() == 1 {
+        save_png(&frames[0], &options.output)
+    } else {
+        save_apng(&frames, options.delay_ms, &options.output)
+    };
+    result.map_err(|e| format!("failed to write {}: {}", options.output, e))?;
+
+    println!(
+        "Wrote {} ({} frame{}, {}x{} px)",
+        options.output,
+        frames.len(),
+        if frames.len() == 1 { "" } else { "s" },
+        frames[0].width,
+        frames[0].height,
+    );
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args(std::env::args().skip(1)).and_then(run);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Terminal frontend for running simulations over SSH.
+//!
+//! ```text
+//! tui [--size WxH]
+//! ```
+
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use game_of_life::GameState;
+use game_of_life::tui::{self, terminal, TuiView, DOT_CELL_SIZE};
+
+/// Target time per frame
+const FRAME_TIME: Duration = Duration::from_millis(33);
+
+/// How often to poll the terminal size (each poll runs `stty`)
+const RESIZE_POLL: Duration = Duration::from_millis(500);
+
+/// Size used when the terminal does not report one
+const FALLBACK_TERMINAL_SIZE: (usize, usize) = (80, 24);
+
+fn parse_size(args: &[String]) -> Result<(usize, usize), String> {
+    match args {
+        [] => Ok((256, 256)),
+        [flag, size] if flag == "--size" => {
+            let parsed = size
+                .split_once(['x', 'X'])
+                .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)));
+            match parsed {
+                Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+                _ => Err(format!("--size: expected WxH, got '{}'", size)),
+            }
+        }
+        _ => Err("Usage: tui [--size WxH]".to_string()),
+    }
+}
+
+fn run(width: usize, height: usize) -> io::Result<()> {
+    let (columns, rows) = terminal::size().unwrap_or(FALLBACK_TERMINAL_SIZE);
+    let mut view = TuiView::new(columns, rows);
+    let mut state = GameState::new(width, height).with_background_worker();
+
+    // Start with the whole grid in view
+    let (vw, vh) = view.viewport();
+    view.camera.fit_rect((0, 0, width - 1, height - 1), (vw, vh), DOT_CELL_SIZE);
+
+    let _session = terminal::Session::start()?;
+    let keys = terminal::spawn_key_reader();
+    let mut out = io::stdout();
+    let mut last_frame = Instant::now();
+    let mut last_resize_poll = Instant::now();
+
+    while !view.quit {
+        loop {
+            match keys.try_recv() {
+                Ok(batch) => {
+                    for key in batch {
+                        state = tui::handle_key(state, &mut view, key);
+                    }
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    view.quit = true;
+                    break;
+                }
+            }
+        }
+
+        // Follow terminal resizes
+        if last_resize_poll.elapsed() >= RESIZE_POLL {
+            last_resize_poll = Instant::now();
+            if let Some((columns, rows)) = terminal::size()
+                && (columns, rows) != (view.columns, view.rows)
+            {
+                view.columns = columns;
+                view.rows = rows;
+                write!(out, "\x1b[2J")?;
+            }
+        }
+
+        let now = Instant::now();
+        state = state.tick((now - last_frame).as_secs_f32());
+        last_frame = now;
+
+        let start = Instant::now();
+        write!(out, "{}", tui::render_frame(&state, &view))?;
+        out.flush()?;
+        state.last_render_time_ms = start.elapsed().as_secs_f32() * 1000.0;
+
+        thread::sleep(FRAME_TIME.saturating_sub(now.elapsed()));
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let result = parse_size(&args).and_then(|(w, h)| run(w, h).map_err(|e| format!("terminal error: {}", e)));
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("{}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides a unified way to select between different
 //! Game of Life evolution algorithms for demo and benchmarking purposes.
 
+use super::{BitGrid, Grid, Cell, Rule, simd_life, temporal_blocking};
+
 /// Available evolution algorithms for demo comparison.
 /// Each algorithm trades off between speed and flexibility.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -68,6 +70,84 @@ impl Algorithm {
             Algorithm::TemporalBlockingParallel => "4 gens/tile, parallel tiles",
         }
     }
+    
+    /// Evolve `grid` one generation with this algorithm
+    pub fn evolve(&self, grid: &BitGrid, rule: &dyn Rule) -> BitGrid {
+        self.evolve_generations(grid, rule, 1)
+    }
+    
+    /// Evolve `grid` by `generations` steps.
+    /// Temporal blocking runs its passes with as many generations per tile
+    /// as possible; the other algorithms simply repeat single steps.
+    pub fn evolve_generations(&self, grid: &BitGrid, rule: &dyn Rule, generations: u64) -> BitGrid {
+        let mut current = grid.clone();
+        let mut remaining = generations;
+        
+        while remaining > 0 {
+            let batch = match self {
+                Algorithm::TemporalBlocking | Algorithm::TemporalBlockingParallel => {
+                    remaining.min(temporal_blocking::GENERATIONS_PER_TILE as u64)
+                }
+                _ => 1,
+            };
+            current = self.evolve_batch(&current, rule, batch as usize);
+            remaining -= batch;
+        }
+        
+        current
+    }
+    
+    /// Single pass of the algorithm; only temporal blocking accepts batch > 1
+    fn evolve_batch(&self, grid: &BitGrid, rule: &dyn Rule, batch: usize) -> BitGrid {
+        match self {
+            Algorithm::Original => {
+                let evolved = bitgrid_to_grid(grid).evolve(rule);
+                grid_to_bitgrid(&evolved)
+            }
+            Algorithm::OriginalParallel => {
+                let evolved = bitgrid_to_grid(grid).evolve_parallel(rule);
+                grid_to_bitgrid(&evolved)
+            }
+            Algorithm::BitGridNaive => grid.evolve(rule),
+            Algorithm::BitGridNaiveParallel => grid.evolve_parallel(rule),
+            Algorithm::Simd => simd_life::evolve_simd(grid, rule),
+            Algorithm::SimdParallel => simd_life::evolve_simd_parallel(grid, rule),
+            Algorithm::TemporalBlocking => {
+                temporal_blocking::evolve_temporal_blocking(grid, rule, batch)
+            }
+            Algorithm::TemporalBlockingParallel => {
+                temporal_blocking::evolve_temporal_blocking_parallel(grid, rule, batch)
+            }
+        }
+    }
+}
+
+/// Convert BitGrid to Grid for Original algorithms
+fn bitgrid_to_grid(bg: &BitGrid) -> Grid {
+    let (w, h) = bg.dimensions();
+    let mut grid = Grid::new(w, h);
+    for y in 0..h {
+        for x in 0..w {
+            if bg.get(x, y) {
+                grid.set(x, y, Cell::Alive);
+            }
+        }
+    }
+    grid
+}
+
+/// Convert Grid to BitGrid after evolution
+fn grid_to_bitgrid(g: &Grid) -> BitGrid {
+    let (w, h) = g.dimensions();
+    let mut bg = BitGrid::new(w, h);
+    for y in 0..h {
+        for x in 0..w {
+            if g.get(x, y) == Some(Cell::Alive) {
+                bg.set(x, y, true);
+            }
+        }
+    }
+    bg
 }
 
 #[cfg(test)]
@@ -92,4 +172,55 @@ mod tests {
         unique.dedup();
         assert_eq!(names.len(), unique.len());
     }
+    
+    #[test]
+    fn test_evolve_generations_matches_repeated_steps() {
+        let rule = crate::domain::ConwayRule;
+        let mut grid = BitGrid::new(128, 128);
+        grid.randomize();
+        
+        let mut expected = grid.clone();
+        for _ in 0..10 {
+            expected = simd_life::evolve_simd(&expected, &rule);
+        }
+        
+        // 10 is not a multiple of the temporal blocking pass size
+        for algorithm in [Algorithm::Simd, Algorithm::TemporalBlocking, Algorithm::TemporalBlockingParallel] {
+            let evolved = algorithm.evolve_generations(&grid, &rule, 10);
+            assert_eq!(evolved.chunks(), expected.chunks(), "{}", algorithm.name());
+        }
+    }
+    
+    #[test]
+    fn test_temporal_blocking_evolve_is_single_generation() {
+        let rule = crate::domain::ConwayRule;
+        let mut grid = BitGrid::new(128, 64);
+        grid.randomize();
+        
+        let expected = simd_life::evolve_simd(&grid, &rule);
+        let evolved = Algorithm::TemporalBlockingParallel.evolve(&grid, &rule);
+        assert_eq!(evolved.chunks(), expected.chunks());
+    }
+    
+    #[test]
+    fn test_all_algorithms_match_naive_on_odd_sizes() {
+        let rule = crate::domain::ConwayRule;
+        
+        // Widths that are not multiples of 64 (or of the tile size), narrow
+        // strips and non-square grids all wrap around correctly
+        for (width, height) in [(63, 40), (65, 33), (1001, 7), (3, 70), (1, 5), (300, 2)] {
+            let mut grid = BitGrid::new(width, height);
+            grid.randomize();
+            
+            let mut expected = grid.clone();
+            for _ in 0..6 {
+                expected = expected.evolve(&rule);
+            }
+            
+            for algorithm in Algorithm::all() {
+                let evolved = algorithm.evolve_generations(&grid, &rule, 6);
+                assert_eq!(evolved.chunks(), expected.chunks(), "{} at {}x{}", algorithm.name(), width, height);
+            }
+        }
+    }
 }
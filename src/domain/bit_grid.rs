@@ -50,6 +50,33 @@ impl Chunk64 {
     }
 }
 
+/// Largest grid users may create or auto-expansion may grow to, in cells
+/// (as many as 10K×10K)
+pub const MAX_GRID_CELLS: usize = 10_000 * 10_000;
+
+/// Where the old contents end up when a grid is resized
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResizeAnchor {
+    /// Keep the top-left corner in place; growing adds space right and below
+    TopLeft,
+    /// Keep the middle in place; growing adds space on all sides
+    Center,
+}
+
+impl ResizeAnchor {
+    /// Offset in cells of the old contents within the resized grid
+    /// (negative where they are cropped)
+    pub fn offset(self, old: (usize, usize), new: (usize, usize)) -> (isize, isize) {
+        match self {
+            ResizeAnchor::TopLeft => (0, 0),
+            ResizeAnchor::Center => (
+                (new.0 as isize - old.0 as isize) / 2,
+                (new.1 as isize - old.1 as isize) / 2,
+            ),
+        }
+    }
+}
+
 /// Bit-packed grid storing cells as individual bits
 #[derive(Clone)]
 pub struct BitGrid {
@@ -66,7 +93,7 @@ pub struct BitGrid {
 impl BitGrid {
     /// Create new empty bit grid
     pub fn new(width: usize, height: usize) -> Self {
-        let chunk_width = (width + 63) / 64;  // Ceiling division
+        let chunk_width = width.div_ceil(64);  // Ceiling division
         let total_chunks = chunk_width * height;
         
         Self {
@@ -82,7 +109,7 @@ impl BitGrid {
     /// # Safety
     /// This is safe because Chunk64 is #[repr(transparent)] over u64
     pub fn from_chunks(width: usize, height: usize, raw_chunks: Vec<u64>) -> Self {
-        let chunk_width = (width + 63) / 64;
+        let chunk_width = width.div_ceil(64);
         debug_assert_eq!(raw_chunks.len(), chunk_width * height);
         
         // Zero-copy conversion: Vec<u64> -> Vec<Chunk64>
@@ -140,6 +167,15 @@ impl BitGrid {
         self.chunks[y * self.chunk_width + chunk_x].0
     }
     
+    /// Bits of chunk `chunk_x` that hold cells. The last chunk of a row is
+    /// only partly used when the width is not a multiple of 64; its
+    /// padding bits are kept dead.
+    #[inline]
+    pub fn chunk_mask(&self, chunk_x: usize) -> u64 {
+        let used = self.width.saturating_sub(chunk_x * 64);
+        if used >= 64 { u64::MAX } else { (1u64 << used) - 1 }
+    }
+
     /// Set raw chunk at (chunk_x, row_y) for SIMD operations
     pub fn set_chunk(&mut self, chunk_x: usize, y: usize, value: u64) {
         if y >= self.height || chunk_x >= self.chunk_width {
@@ -240,7 +276,181 @@ impl BitGrid {
     pub fn clear(&mut self) {
         self.chunks.iter_mut().for_each(|c| *c = Chunk64::empty());
     }
-    
+
+    /// Raw chunk storage, row-major with `chunk_width` chunks per row
+    pub fn chunks(&self) -> &[Chunk64] {
+        &self.chunks
+    }
+
+    /// Count live cells in row `y` with x in `start..end` using popcounts
+    pub fn count_alive_in_row(&self, y: usize, start: usize, end: usize) -> usize {
+        let end = end.min(self.width);
+        if y >= self.height || start >= end {
+            return 0;
+        }
+
+        let row = &self.chunks[y * self.chunk_width..(y + 1) * self.chunk_width];
+        let (first, last) = (start / 64, (end - 1) / 64);
+        let low_mask = u64::MAX << (start % 64);
+        let high_mask = u64::MAX >> (63 - (end - 1) % 64);
+
+        if first == last {
+            return (row[first].0 & low_mask & high_mask).count_ones() as usize;
+        }
+
+        let middle: u32 = row[first + 1..last].iter().map(|c| c.count_alive()).sum();
+        ((row[first].0 & low_mask).count_ones()
+            + middle
+            + (row[last].0 & high_mask).count_ones()) as usize
+    }
+
+    /// Count cells born and cells that died going from `previous` to `self`.
+    /// Both grids must have the same dimensions.
+    pub fn count_changes(&self, previous: &BitGrid) -> (usize, usize) {
+        debug_assert_eq!(self.dimensions(), previous.dimensions());
+
+        self.chunks.iter()
+            .zip(previous.chunks.iter())
+            .fold((0, 0), |(births, deaths), (now, before)| {
+                (
+                    births + (now.0 & !before.0).count_ones() as usize,
+                    deaths + (before.0 & !now.0).count_ones() as usize,
+                )
+            })
+    }
+
+    /// Set every cell alive in `mask` to `alive`. Both grids must have the
+    /// same dimensions.
+    pub fn paint_mask(&mut self, mask: &BitGrid, alive: bool) {
+        debug_assert_eq!(self.dimensions(), mask.dimensions());
+
+        for (chunk, mask) in self.chunks.iter_mut().zip(&mask.chunks) {
+            if alive {
+                chunk.0 |= mask.0;
+            } else {
+                chunk.0 &= !mask.0;
+            }
+        }
+    }
+
+    /// Set every cell alive in `mask` to `alive`, with the mask's top-left
+    /// corner at `origin`. The mask may be any size; whatever falls past
+    /// the grid edges is dropped. Works a chunk at a time, shifting each
+    /// mask chunk across the two grid chunks it lands on.
+    pub fn paint_mask_at(&mut self, mask: &BitGrid, origin: (usize, usize), alive: bool) {
+        let (ox, oy) = origin;
+        if ox >= self.width || oy >= self.height {
+            return;
+        }
+        let shift = ox % 64;
+        for my in 0..mask.height.min(self.height - oy) {
+            let row = (oy + my) * self.chunk_width;
+            for mx in 0..mask.chunk_width {
+                let bits = mask.chunks[my * mask.chunk_width + mx].0;
+                if bits == 0 {
+                    continue;
+                }
+                let chunk_x = ox / 64 + mx;
+                let carry = if shift == 0 { 0 } else { bits >> (64 - shift) };
+                for (chunk_x, part) in [(chunk_x, bits << shift), (chunk_x + 1, carry)] {
+                    if chunk_x >= self.chunk_width || part == 0 {
+                        continue;
+                    }
+                    let part = part & self.chunk_mask(chunk_x);
+                    let chunk = &mut self.chunks[row + chunk_x].0;
+                    if alive {
+                        *chunk |= part;
+                    } else {
+                        *chunk &= !part;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Set every cell in the rectangle from (x0, y0) to (x1, y1), corners
+    /// included, alive. Clipped to the grid; filled a chunk at a time.
+    pub fn fill_rect(&mut self, (x0, y0): (usize, usize), (x1, y1): (usize, usize)) {
+        if x0 > x1 || y0 > y1 || x0 >= self.width || y0 >= self.height {
+            return;
+        }
+        let (x1, y1) = (x1.min(self.width - 1), y1.min(self.height - 1));
+        let (first, last) = (x0 / 64, x1 / 64);
+        for y in y0..=y1 {
+            for chunk_x in first..=last {
+                let lo = if chunk_x == first { x0 % 64 } else { 0 };
+                let hi = if chunk_x == last { x1 % 64 } else { 63 };
+                self.chunks[y * self.chunk_width + chunk_x].0 |= (u64::MAX << lo) & (u64::MAX >> (63 - hi));
+            }
+        }
+    }
+
+    /// Grid with exactly the cells that differ between `self` and `other`
+    /// alive. Both grids must have the same dimensions.
+    pub fn difference(&self, other: &BitGrid) -> BitGrid {
+        debug_assert_eq!(self.dimensions(), other.dimensions());
+
+        let mut diff = BitGrid::new(self.width, self.height);
+        for (out, (a, b)) in diff.chunks.iter_mut().zip(self.chunks.iter().zip(&other.chunks)) {
+            out.0 = a.0 ^ b.0;
+        }
+        diff
+    }
+
+    /// Smallest rectangle containing every live cell as inclusive
+    /// (min_x, min_y, max_x, max_y), or None if the grid is empty
+    pub fn bounding_box(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+
+        for y in 0..self.height {
+            let row = &self.chunks[y * self.chunk_width..(y + 1) * self.chunk_width];
+
+            // First and last occupied chunk give the row's extent
+            let Some(first) = row.iter().position(|c| !c.is_empty()) else {
+                continue;
+            };
+            let last = row.iter().rposition(|c| !c.is_empty()).unwrap_or(first);
+
+            let row_min = first * 64 + row[first].0.trailing_zeros() as usize;
+            let row_max = (last * 64 + 63 - row[last].0.leading_zeros() as usize)
+                .min(self.width - 1);
+
+            bounds = Some(match bounds {
+                None => (row_min, y, row_max, y),
+                Some((min_x, min_y, max_x, _)) => (min_x.min(row_min), min_y, max_x.max(row_max), y),
+            });
+        }
+
+        bounds
+    }
+
+    /// Copy of the grid at a new size with the contents placed by
+    /// `anchor`; whatever falls outside the new size is cropped
+    pub fn resized(&self, width: usize, height: usize, anchor: ResizeAnchor) -> BitGrid {
+        let (dx, dy) = anchor.offset(self.dimensions(), (width, height));
+        let mut resized = BitGrid::new(width, height);
+        
+        // Columns that stay inside the new grid, copied 64 at a time
+        let src_x = (-dx).max(0) as usize;
+        let dst_x = dx.max(0) as usize;
+        let copy_width = self.width.saturating_sub(src_x).min(width.saturating_sub(dst_x));
+        
+        for y in 0..self.height {
+            let new_y = y as isize + dy;
+            if new_y < 0 || new_y >= height as isize {
+                continue;
+            }
+            for start in (0..copy_width).step_by(64) {
+                let bits = (copy_width - start).min(64);
+                let mask = if bits == 64 { u64::MAX } else { (1u64 << bits) - 1 };
+                let word = self.get_word64(src_x + start, y) & mask;
+                resized.set_word64_or(dst_x + start, new_y as usize, word);
+            }
+        }
+        
+        resized
+    }
+
     /// Evolve grid by one generation using specified rule
     pub fn evolve(&self, rule: &dyn Rule) -> BitGrid {
         let mut next = BitGrid::new(self.width, self.height);
@@ -303,9 +513,13 @@ impl BitGrid {
         use rand::Rng;
         let mut rng = rand::rng();
         
-        for chunk in &mut self.chunks {
+        let last_mask = self.chunk_mask(self.chunk_width.saturating_sub(1));
+        for (i, chunk) in self.chunks.iter_mut().enumerate() {
             // Random bits with ~25% density
             chunk.0 = rng.random::<u64>() & rng.random::<u64>();
+            if (i + 1) % self.chunk_width == 0 {
+                chunk.0 &= last_mask;
+            }
         }
     }
 }
@@ -434,6 +648,93 @@ mod tests {
         assert_eq!(grid.count_alive(), 0);
     }
     
+    #[test]
+    fn test_randomize_keeps_padding_dead() {
+        let mut grid = BitGrid::new(65, 20);
+        grid.randomize();
+        
+        let counted = (0..20).map(|y| (0..65).filter(|&x| grid.get(x, y)).count()).sum::<usize>();
+        assert_eq!(grid.count_alive(), counted);
+        assert_eq!(grid.chunk_mask(0), u64::MAX);
+        assert_eq!(grid.chunk_mask(1), 1);
+    }
+    
+    #[test]
+    fn test_resized_keeps_contents() {
+        let mut grid = BitGrid::new(65, 10);
+        grid.set(0, 0, true);
+        grid.set(64, 9, true);
+        grid.set(30, 5, true);
+        
+        let grown = grid.resized(200, 20, ResizeAnchor::TopLeft);
+        assert_eq!(grown.dimensions(), (200, 20));
+        assert_eq!(grown.count_alive(), 3);
+        assert!(grown.get(0, 0) && grown.get(64, 9) && grown.get(30, 5));
+        
+        // Centered growth moves everything by half the added size
+        let centered = grid.resized(135, 30, ResizeAnchor::Center);
+        assert_eq!(ResizeAnchor::Center.offset((65, 10), (135, 30)), (35, 10));
+        assert!(centered.get(35, 10) && centered.get(99, 19) && centered.get(65, 15));
+        
+        // Shrinking crops the cells that no longer fit
+        let cropped = grid.resized(33, 4, ResizeAnchor::Center);
+        assert_eq!(cropped.count_alive(), 1);
+        assert!(cropped.get(14, 2));
+        assert_eq!(grid.resized(64, 10, ResizeAnchor::TopLeft).count_alive(), 2);
+    }
+    
+    #[test]
+    fn test_bitgrid_bounding_box() {
+        let mut grid = BitGrid::new(200, 50);
+        assert_eq!(grid.bounding_box(), None);
+        
+        grid.set(70, 10, true);
+        assert_eq!(grid.bounding_box(), Some((70, 10, 70, 10)));
+        
+        // Cells in different chunks and rows widen the box
+        grid.set(3, 20, true);
+        grid.set(150, 15, true);
+        assert_eq!(grid.bounding_box(), Some((3, 10, 150, 20)));
+    }
+    
+    #[test]
+    fn test_bitgrid_count_changes() {
+        let mut before = BitGrid::new(100, 10);
+        before.set(1, 1, true);
+        before.set(2, 1, true);
+        
+        let mut after = BitGrid::new(100, 10);
+        after.set(2, 1, true);
+        after.set(80, 5, true);
+        after.set(81, 5, true);
+        
+        // (1,1) died, (80,5) and (81,5) were born, (2,1) survived
+        assert_eq!(after.count_changes(&before), (2, 1));
+
+        let diff = after.difference(&before);
+        assert_eq!(diff.count_alive(), 3);
+        assert!(diff.get(1, 1) && diff.get(80, 5) && diff.get(81, 5));
+        assert!(!diff.get(2, 1));
+    }
+
+    #[test]
+    fn test_fill_rect_and_paint_mask_at() {
+        let mut mask = BitGrid::new(70, 3);
+        mask.fill_rect((60, 1), (200, 1));
+        assert_eq!(mask.count_alive(), 10);
+        assert!(mask.get(60, 1) && mask.get(69, 1) && !mask.get(59, 1));
+
+        // Placed across a chunk boundary and clipped at the right edge,
+        // leaving the padding bits dead (they would count as alive)
+        let mut grid = BitGrid::new(150, 10);
+        grid.paint_mask_at(&mask, (85, 4), true);
+        assert_eq!(grid.count_alive(), 5);
+        assert!(grid.get(145, 5) && grid.get(149, 5) && !grid.get(144, 5));
+
+        grid.paint_mask_at(&mask, (85, 4), false);
+        assert_eq!(grid.count_alive(), 0);
+    }
+
     #[test]
     fn test_blinker_evolution() {
         let rule = ConwayRule;
@@ -510,4 +811,19 @@ mod tests {
             }
         }
     }
+    
+    #[test]
+    fn test_count_alive_in_row() {
+        let mut grid = BitGrid::new(200, 3);
+        for x in (0..200).step_by(3) {
+            grid.set(x, 1, true);
+        }
+        
+        for (start, end) in [(0, 200), (5, 6), (6, 7), (10, 150), (63, 65), (64, 128), (190, 500)] {
+            let expected = (start..end.min(200)).filter(|x| x % 3 == 0).count();
+            assert_eq!(grid.count_alive_in_row(1, start, end), expected, "{}..{}", start, end);
+        }
+        assert_eq!(grid.count_alive_in_row(0, 0, 200), 0);
+        assert_eq!(grid.count_alive_in_row(5, 0, 200), 0);
+    }
 }
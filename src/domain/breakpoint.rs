@@ -0,0 +1,475 @@
+//! Run-until conditions checked after every committed generation.
+//!
+//! A `Breakpoints` set holds the armed conditions. After each step the
+//! application passes the previous and current grid; the first condition
+//! that fires is reported as a `BreakpointHit` with the region to
+//! highlight.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use super::BitGrid;
+
+/// Number of recent states compared when detecting stabilization,
+/// i.e. the longest oscillator period that counts as stable
+pub const STABILIZATION_WINDOW: usize = 16;
+
+/// Inclusive cell rectangle (min_x, min_y, max_x, max_y)
+pub type CellRect = (usize, usize, usize, usize);
+
+/// Condition that stops the simulation when it becomes true
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Condition {
+    /// Generation counter reached N
+    Generation(u64),
+    /// Population rose above the threshold
+    PopulationAbove(usize),
+    /// Population fell below the threshold
+    PopulationBelow(usize),
+    /// Any cell inside the rectangle changed state
+    RegionChanged(CellRect),
+    /// Grid repeats a state from the last `STABILIZATION_WINDOW` generations
+    Stabilized,
+    /// The cell at (x, y) became alive
+    CellAlive { x: usize, y: usize },
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Condition::Generation(n) => write!(f, "Generation {}", n),
+            Condition::PopulationAbove(n) => write!(f, "Population > {}", n),
+            Condition::PopulationBelow(n) => write!(f, "Population < {}", n),
+            Condition::RegionChanged((x0, y0, x1, y1)) => {
+                write!(f, "Change in ({},{})-({},{})", x0, y0, x1, y1)
+            }
+            Condition::Stabilized => write!(f, "Stabilized"),
+            Condition::CellAlive { x, y } => write!(f, "Cell ({},{}) alive", x, y),
+        }
+    }
+}
+
+impl Condition {
+    /// Parse a condition typed in the panel: `gen 500`, `pop < 100`,
+    /// `pop > 2000`, `region 10,10 40,30`, `cell 5,5` or `stable`
+    /// (keywords are case-insensitive)
+    pub fn parse(text: &str) -> Result<Condition, String> {
+        let text = text.trim().to_ascii_lowercase();
+        let (keyword, rest) = text.split_once(char::is_whitespace).unwrap_or((&text, ""));
+        let rest = rest.trim();
+        let number = |s: &str| s.trim().parse::<usize>().map_err(|_| format!("'{}' is not a number", s.trim()));
+        let point = |s: &str| -> Result<(usize, usize), String> {
+            let (x, y) = s.split_once(',').ok_or_else(|| format!("expected x,y but got '{}'", s))?;
+            Ok((number(x)?, number(y)?))
+        };
+
+        match keyword {
+            "gen" | "generation" => Ok(Condition::Generation(number(rest)? as u64)),
+            "pop" | "population" => match rest.chars().next() {
+                Some('<') => Ok(Condition::PopulationBelow(number(&rest[1..])?)),
+                Some('>') => Ok(Condition::PopulationAbove(number(&rest[1..])?)),
+                _ => Err("expected pop < N or pop > N".to_string()),
+            },
+            "region" => {
+                let (a, b) = rest.split_once(char::is_whitespace).ok_or("expected region x0,y0 x1,y1")?;
+                let ((x0, y0), (x1, y1)) = (point(a)?, point(b.trim())?);
+                Ok(Condition::RegionChanged((x0, y0, x1, y1)).normalized())
+            }
+            "cell" => {
+                let (x, y) = point(rest)?;
+                Ok(Condition::CellAlive { x, y })
+            }
+            "stable" | "stabilized" => Ok(Condition::Stabilized),
+            _ => Err(format!("unknown condition '{}'", keyword)),
+        }
+    }
+
+    /// The same condition with a region given by any two opposite corners
+    /// turned into (min_x, min_y, max_x, max_y)
+    pub fn normalized(self) -> Condition {
+        match self {
+            Condition::RegionChanged((x0, y0, x1, y1)) => {
+                Condition::RegionChanged((x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1)))
+            }
+            other => other,
+        }
+    }
+}
+
+/// An armed condition
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breakpoint {
+    pub condition: Condition,
+    /// Remove the breakpoint after it fires ("run until")
+    pub once: bool,
+    /// Not checked since it was armed, so a population threshold that is
+    /// already passed counts as crossed
+    fresh: bool,
+}
+
+/// Report of the breakpoint that stopped the simulation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BreakpointHit {
+    pub condition: Condition,
+    pub generation: u64,
+    /// Cells that triggered the condition, if it has a location
+    pub highlight: Option<CellRect>,
+}
+
+/// Armed breakpoints plus the state needed to evaluate them
+#[derive(Clone, Debug, Default)]
+pub struct Breakpoints {
+    list: Vec<Breakpoint>,
+    /// Hashes of recent grids, newest last (only kept while a
+    /// `Stabilized` breakpoint is armed)
+    recent_states: VecDeque<u64>,
+    /// `Stabilized` fired and the grid has only repeated itself since;
+    /// it fires again once the pattern changes and settles anew
+    settled: bool,
+}
+
+impl Breakpoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arm a breakpoint that stays until removed
+    pub fn add(&mut self, condition: Condition) {
+        self.list.push(Breakpoint { condition: condition.normalized(), once: false, fresh: true });
+    }
+
+    /// Arm a breakpoint that is removed once it fires
+    pub fn add_once(&mut self, condition: Condition) {
+        self.list.push(Breakpoint { condition: condition.normalized(), once: true, fresh: true });
+    }
+
+    /// Remove the breakpoint at `index`, if any
+    pub fn remove(&mut self, index: usize) {
+        if index < self.list.len() {
+            self.list.remove(index);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.reset_history();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Breakpoint> {
+        self.list.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Forget remembered states, e.g. after the grid was edited
+    pub fn reset_history(&mut self) {
+        self.recent_states.clear();
+        self.settled = false;
+    }
+
+    /// Move located conditions by `offset` along with the grid contents
+    /// after a resize to `size`. Regions are cropped to the new grid;
+    /// cells and regions that no longer overlap it are dropped.
+    pub fn shift(&mut self, offset: (isize, isize), size: (usize, usize)) {
+        let max = (size.0 as isize - 1, size.1 as isize - 1);
+        let shift_rect = |(x0, y0, x1, y1): CellRect| -> Option<CellRect> {
+            let (x0, y0) = (x0 as isize + offset.0, y0 as isize + offset.1);
+            let (x1, y1) = (x1 as isize + offset.0, y1 as isize + offset.1);
+            if x1 < 0 || y1 < 0 || x0 > max.0 || y0 > max.1 {
+                return None;
+            }
+            Some((x0.max(0) as usize, y0.max(0) as usize, x1.min(max.0) as usize, y1.min(max.1) as usize))
+        };
+
+        self.list.retain_mut(|bp| {
+            let moved = match bp.condition {
+                Condition::RegionChanged(rect) => shift_rect(rect).map(Condition::RegionChanged),
+                Condition::CellAlive { x, y } => shift_rect((x, y, x, y)).map(|(x, y, _, _)| Condition::CellAlive { x, y }),
+                other => Some(other),
+            };
+            match moved {
+                Some(condition) => {
+                    bp.condition = condition;
+                    true
+                }
+                None => false,
+            }
+        });
+        self.reset_history();
+    }
+
+    /// Largest number of generations that can be taken from `generation`
+    /// without stepping over a `Generation` target. The other conditions
+    /// do not limit batches; they are checked generation by generation
+    /// inside them (see `needs_every_generation`).
+    pub fn max_batch(&self, generation: u64) -> Option<u64> {
+        self.list
+            .iter()
+            .filter_map(|bp| match bp.condition {
+                Condition::Generation(target) if target > generation => Some(target - generation),
+                _ => None,
+            })
+            .min()
+    }
+
+    /// Whether any condition has to be checked against every pair of
+    /// consecutive generations rather than only where a batch ends
+    pub fn needs_every_generation(&self) -> bool {
+        self.list.iter().any(|bp| !matches!(bp.condition, Condition::Generation(_)))
+    }
+
+    /// Evaluate all breakpoints for the step from `previous` to `current`.
+    /// Returns the first one that fired; one-shot breakpoints that fired
+    /// are removed.
+    pub fn check(&mut self, previous: &BitGrid, current: &BitGrid, generation: u64) -> Option<BreakpointHit> {
+        if self.list.is_empty() {
+            return None;
+        }
+
+        let repeated = self.track_stabilization(current);
+        let population = || (previous.count_alive(), current.count_alive());
+
+        // Population thresholds fire on crossing, or right away when
+        // armed on the far side
+        let hit_index = self.list.iter().position(|bp| match bp.condition {
+            Condition::Generation(target) => generation >= target,
+            Condition::PopulationAbove(threshold) => {
+                let (before, after) = population();
+                after > threshold && (bp.fresh || before <= threshold)
+            }
+            Condition::PopulationBelow(threshold) => {
+                let (before, after) = population();
+                after < threshold && (bp.fresh || before >= threshold)
+            }
+            Condition::RegionChanged(rect) => changed_cells_in(previous, current, rect).is_some(),
+            Condition::Stabilized => repeated && !self.settled,
+            Condition::CellAlive { x, y } => !previous.get(x, y) && current.get(x, y),
+        });
+        self.list.iter_mut().for_each(|bp| bp.fresh = false);
+        if !repeated {
+            self.settled = false;
+        }
+        let hit_index = hit_index?;
+
+        let breakpoint = self.list[hit_index];
+        if breakpoint.condition == Condition::Stabilized {
+            self.settled = true;
+        }
+        if breakpoint.once {
+            self.list.remove(hit_index);
+        }
+        // A generation target is passed for good; drop it so play resumes
+        if let Condition::Generation(_) = breakpoint.condition {
+            self.list.retain(|bp| bp.condition != breakpoint.condition);
+        }
+
+        let highlight = match breakpoint.condition {
+            Condition::RegionChanged(rect) => changed_cells_in(previous, current, rect),
+            Condition::CellAlive { x, y } => Some((x, y, x, y)),
+            Condition::Generation(_) => None,
+            _ => current.bounding_box(),
+        };
+
+        Some(BreakpointHit {
+            condition: breakpoint.condition,
+            generation,
+            highlight,
+        })
+    }
+
+    /// Record the hash of `current` and report whether it was seen recently
+    fn track_stabilization(&mut self, current: &BitGrid) -> bool {
+        if !self.list.iter().any(|bp| bp.condition == Condition::Stabilized) {
+            self.recent_states.clear();
+            return false;
+        }
+
+        let hash = grid_hash(current);
+        let repeated = self.recent_states.contains(&hash);
+        if self.recent_states.len() == STABILIZATION_WINDOW {
+            self.recent_states.pop_front();
+        }
+        self.recent_states.push_back(hash);
+        repeated
+    }
+}
+
+/// Hash of the grid contents
+fn grid_hash(grid: &BitGrid) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    grid.dimensions().hash(&mut hasher);
+    grid.chunks().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Bounding box of cells inside `rect` that differ between the grids
+fn changed_cells_in(previous: &BitGrid, current: &BitGrid, rect: CellRect) -> Option<CellRect> {
+    let (width, height) = current.dimensions();
+    let (x0, y0, x1, y1) = rect;
+    let mut changed: Option<CellRect> = None;
+
+    for y in y0..=y1.min(height.saturating_sub(1)) {
+        for x in x0..=x1.min(width.saturating_sub(1)) {
+            if previous.get(x, y) != current.get(x, y) {
+                changed = Some(match changed {
+                    None => (x, y, x, y),
+                    Some((a, b, c, d)) => (a.min(x), b.min(y), c.max(x), d.max(y)),
+                });
+            }
+        }
+    }
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ConwayRule;
+
+    fn blinker() -> BitGrid {
+        let mut grid = BitGrid::new(10, 10);
+        grid.set(4, 5, true);
+        grid.set(5, 5, true);
+        grid.set(6, 5, true);
+        grid
+    }
+
+    #[test]
+    fn test_cell_alive_fires_on_birth() {
+        let before = blinker();
+        let after = before.evolve(&ConwayRule);
+
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add(Condition::CellAlive { x: 5, y: 4 });
+        breakpoints.add(Condition::CellAlive { x: 0, y: 0 });
+
+        let hit = breakpoints.check(&before, &after, 1).expect("birth at (5, 4)");
+        assert_eq!(hit.condition, Condition::CellAlive { x: 5, y: 4 });
+        assert_eq!(hit.highlight, Some((5, 4, 5, 4)));
+        assert_eq!(breakpoints.len(), 2, "persistent breakpoints stay armed");
+    }
+
+    #[test]
+    fn test_shift_moves_crops_and_drops() {
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add(Condition::CellAlive { x: 1, y: 1 });
+        breakpoints.add(Condition::CellAlive { x: 8, y: 8 });
+        breakpoints.add(Condition::RegionChanged((0, 0, 5, 5)));
+        breakpoints.add(Condition::Generation(10));
+
+        // Shrink to 6x6, keeping the cells that were at (2..8, 2..8)
+        breakpoints.shift((-2, -2), (6, 6));
+        let conditions: Vec<_> = breakpoints.iter().map(|bp| bp.condition).collect();
+        assert_eq!(conditions, vec![
+            Condition::RegionChanged((0, 0, 3, 3)),
+            Condition::Generation(10),
+        ]);
+
+        breakpoints.shift((3, 1), (20, 20));
+        assert_eq!(breakpoints.iter().next().unwrap().condition, Condition::RegionChanged((3, 1, 6, 4)));
+    }
+
+    #[test]
+    fn test_population_threshold_crossing() {
+        let empty = BitGrid::new(10, 10);
+        let before = blinker();
+
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add_once(Condition::PopulationBelow(1));
+
+        // Blinker keeps 3 cells; no crossing
+        assert!(breakpoints.check(&before, &before.evolve(&ConwayRule), 1).is_none());
+
+        let hit = breakpoints.check(&before, &empty, 2).expect("population dropped to 0");
+        assert_eq!(hit.generation, 2);
+        assert!(breakpoints.is_empty(), "one-shot breakpoint removed");
+    }
+
+    #[test]
+    fn test_population_already_past_threshold_fires_once_armed() {
+        let grid = blinker();
+        let next = grid.evolve(&ConwayRule);
+
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add(Condition::PopulationBelow(10));
+        assert!(breakpoints.check(&grid, &next, 1).is_some(), "3 < 10 when armed");
+        assert!(breakpoints.check(&next, &grid, 2).is_none(), "persistent one waits for the next crossing");
+
+        // Checking the grid against itself evaluates it as it stands
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add_once(Condition::PopulationAbove(1));
+        assert!(breakpoints.check(&grid, &grid, 0).is_some());
+    }
+
+    #[test]
+    fn test_parse_condition() {
+        assert_eq!(Condition::parse("gen 500"), Ok(Condition::Generation(500)));
+        assert_eq!(Condition::parse(" Pop < 100"), Ok(Condition::PopulationBelow(100)));
+        assert_eq!(Condition::parse("pop >2000"), Ok(Condition::PopulationAbove(2000)));
+        assert_eq!(Condition::parse("region 40,30 10,10"), Ok(Condition::RegionChanged((10, 10, 40, 30))));
+        assert_eq!(Condition::parse("cell 5,7"), Ok(Condition::CellAlive { x: 5, y: 7 }));
+        assert_eq!(Condition::parse("stable"), Ok(Condition::Stabilized));
+        assert!(Condition::parse("pop = 3").is_err());
+        assert!(Condition::parse("gen soon").is_err());
+        assert!(Condition::parse("region 1,2").is_err());
+        assert!(Condition::parse("banana").is_err());
+    }
+
+    #[test]
+    fn test_region_change_and_stabilization() {
+        let rule = ConwayRule;
+        let gen0 = blinker();
+        let gen1 = gen0.evolve(&rule);
+        let gen2 = gen1.evolve(&rule);
+
+        let mut breakpoints = Breakpoints::new();
+        // Corners in either order
+        breakpoints.add(Condition::RegionChanged((4, 9, 0, 0)));
+        assert_eq!(breakpoints.iter().next().unwrap().condition, Condition::RegionChanged((0, 0, 4, 9)));
+        let hit = breakpoints.check(&gen0, &gen1, 1).expect("left end of blinker died");
+        assert_eq!(hit.highlight, Some((4, 5, 4, 5)));
+
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add(Condition::Stabilized);
+        assert!(breakpoints.check(&gen0, &gen1, 1).is_none());
+        assert!(breakpoints.check(&gen1, &gen2, 2).is_none());
+        let hit = breakpoints.check(&gen2, &gen1, 3).expect("period 2 repeat");
+        assert_eq!(hit.condition, Condition::Stabilized);
+
+        // Still oscillating: the persistent breakpoint stays quiet
+        assert!(breakpoints.check(&gen1, &gen2, 4).is_none());
+        assert!(breakpoints.check(&gen2, &gen1, 5).is_none());
+        // Once the pattern changes, it fires when it settles again
+        let block = BitGrid::from_chunks(10, 10, vec![0b11 << 2, 0b11 << 2, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(breakpoints.check(&gen1, &block, 6).is_none());
+        assert!(breakpoints.check(&block, &block, 7).is_some());
+    }
+
+    #[test]
+    fn test_generation_limits_batch() {
+        let mut breakpoints = Breakpoints::new();
+        breakpoints.add(Condition::Generation(100));
+
+        assert_eq!(breakpoints.max_batch(40), Some(60));
+        assert_eq!(breakpoints.max_batch(100), None);
+        assert!(!breakpoints.needs_every_generation());
+        breakpoints.add(Condition::CellAlive { x: 1, y: 1 });
+        assert_eq!(breakpoints.max_batch(40), Some(60), "cell changes are checked inside the batch");
+        assert!(breakpoints.needs_every_generation());
+        breakpoints.remove(1);
+
+        let grid = BitGrid::new(4, 4);
+        assert!(breakpoints.check(&grid, &grid, 99).is_none());
+        assert!(breakpoints.check(&grid, &grid, 100).is_some());
+        assert!(breakpoints.is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+//! Optional per-cell history layer maintained alongside a `BitGrid`.
+//!
+//! The layer stores one `u16` per cell whose meaning depends on the
+//! `HistoryKind`. It is updated from the birth/death masks between two
+//! consecutive grids, so only chunks with live cells or active trails
+//! are touched. The `Changes` kind instead keeps the previous generation's
+//! chunks, so births, survivors and deaths are plain bit operations.
+
+use rayon::prelude::*;
+
+use super::BitGrid;
+
+/// Default number of generations a death trail stays visible
+pub const DEFAULT_TRAIL_LENGTH: u16 = 32;
+
+/// What the history layer tracks for each cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryKind {
+    /// Consecutive generations the cell has been alive (0 = dead)
+    Age,
+    /// Total generations the cell has ever been alive ("ever alive" heat map)
+    Activity,
+    /// Generations of trail left after the cell died, counting down to 0
+    Trail,
+    /// Whether the cell was alive in the previous update (1) or not (0),
+    /// to tell births, survivors and deaths apart. With a step size above
+    /// 1 that is the generation before the batch, so this shows the net
+    /// change across the batch: a cell born and killed again within it
+    /// does not show up.
+    Changes,
+}
+
+impl HistoryKind {
+    /// All kinds in UI cycling order
+    pub fn all() -> [HistoryKind; 4] {
+        [HistoryKind::Age, HistoryKind::Activity, HistoryKind::Trail, HistoryKind::Changes]
+    }
+
+    /// Display name for UI
+    pub fn name(&self) -> &'static str {
+        match self {
+            HistoryKind::Age => "Age",
+            HistoryKind::Activity => "Heat map",
+            HistoryKind::Trail => "Trails",
+            HistoryKind::Changes => "Changes",
+        }
+    }
+}
+
+/// Per-cell counters updated once per generation
+#[derive(Clone)]
+pub struct CellHistory {
+    kind: HistoryKind,
+    width: usize,
+    height: usize,
+    chunk_width: usize,
+    /// One value per cell, row-major, padded to whole chunks
+    values: Vec<u16>,
+    /// Bit set for every cell with a non-zero trail (Trail kind only)
+    trail_mask: Vec<u64>,
+    /// Previous generation's chunks (Changes kind only)
+    previous: Vec<u64>,
+    trail_length: u16,
+}
+
+impl CellHistory {
+    /// Start tracking from the current state of `grid`
+    pub fn new(kind: HistoryKind, grid: &BitGrid) -> Self {
+        let (width, height) = grid.dimensions();
+        let chunk_width = width.div_ceil(64);
+
+        // Changes only needs the previous chunks: every live cell starts
+        // out as a survivor
+        if kind == HistoryKind::Changes {
+            return Self {
+                kind,
+                width,
+                height,
+                chunk_width,
+                values: Vec::new(),
+                trail_mask: Vec::new(),
+                previous: grid.chunks().iter().map(|c| c.0).collect(),
+                trail_length: DEFAULT_TRAIL_LENGTH,
+            };
+        }
+
+        let mut history = Self {
+            kind,
+            width,
+            height,
+            chunk_width,
+            values: vec![0; chunk_width * 64 * height],
+            trail_mask: vec![0; chunk_width * height],
+            previous: Vec::new(),
+            trail_length: DEFAULT_TRAIL_LENGTH,
+        };
+
+        // Cells alive right now count as one generation old
+        if kind != HistoryKind::Trail {
+            for (idx, chunk) in grid.chunks().iter().enumerate() {
+                for_each_bit(chunk.0, |bit| history.values[idx * 64 + bit] = 1);
+            }
+        }
+
+        history
+    }
+
+    /// Set how many generations a death trail lasts
+    pub fn with_trail_length(mut self, generations: u16) -> Self {
+        self.trail_length = generations.max(1);
+        self
+    }
+
+    pub fn kind(&self) -> HistoryKind {
+        self.kind
+    }
+
+    pub fn trail_length(&self) -> u16 {
+        self.trail_length
+    }
+
+    pub fn dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// Value tracked for the cell at (x, y), 0 outside the grid
+    #[inline]
+    pub fn get(&self, x: usize, y: usize) -> u16 {
+        if x >= self.width || y >= self.height {
+            return 0;
+        }
+        if self.kind == HistoryKind::Changes {
+            let chunk = self.previous[y * self.chunk_width + x / 64];
+            return ((chunk >> (x % 64)) & 1) as u16;
+        }
+        self.values[y * self.chunk_width * 64 + x]
+    }
+
+    /// Previous generation's chunks, row-major like `BitGrid::chunks`
+    /// (empty unless the kind is `Changes`)
+    pub fn previous_chunks(&self) -> &[u64] {
+        &self.previous
+    }
+
+    /// Advance the layer from `previous` to `current`.
+    /// Both grids must match the dimensions the layer was created with.
+    pub fn update(&mut self, previous: &BitGrid, current: &BitGrid) {
+        debug_assert_eq!(previous.dimensions(), (self.width, self.height));
+        debug_assert_eq!(current.dimensions(), (self.width, self.height));
+
+        if self.kind == HistoryKind::Changes {
+            for (kept, chunk) in self.previous.iter_mut().zip(previous.chunks()) {
+                *kept = chunk.0;
+            }
+            return;
+        }
+
+        let kind = self.kind;
+        let trail_length = self.trail_length;
+        let chunk_width = self.chunk_width;
+        let row_cells = chunk_width * 64;
+        let before = previous.chunks();
+        let after = current.chunks();
+
+        self.values
+            .par_chunks_mut(row_cells)
+            .zip(self.trail_mask.par_chunks_mut(chunk_width))
+            .enumerate()
+            .for_each(|(y, (row_values, row_trails))| {
+                let row_start = y * chunk_width;
+
+                for (chunk_x, trails) in row_trails.iter_mut().enumerate() {
+                    let old = before[row_start + chunk_x].0;
+                    let new = after[row_start + chunk_x].0;
+                    let cells = &mut row_values[chunk_x * 64..(chunk_x + 1) * 64];
+
+                    match kind {
+                        HistoryKind::Age => {
+                            // Survivors age, newborns start at 1, deaths reset
+                            for_each_bit(new & old, |bit| cells[bit] = cells[bit].saturating_add(1));
+                            for_each_bit(new & !old, |bit| cells[bit] = 1);
+                            for_each_bit(old & !new, |bit| cells[bit] = 0);
+                        }
+                        HistoryKind::Activity => {
+                            for_each_bit(new, |bit| cells[bit] = cells[bit].saturating_add(1));
+                        }
+                        // Tracked as whole chunks above
+                        HistoryKind::Changes => {}
+                        HistoryKind::Trail => {
+                            // Existing trails fade, fresh deaths start a full trail
+                            for_each_bit(*trails, |bit| cells[bit] = cells[bit].saturating_sub(1));
+                            let deaths = old & !new;
+                            for_each_bit(deaths, |bit| cells[bit] = trail_length);
+                            // Cells that came back to life drop their trail
+                            for_each_bit(new & *trails, |bit| cells[bit] = 0);
+
+                            let mut active = 0u64;
+                            for_each_bit((*trails | deaths) & !new, |bit| {
+                                if cells[bit] > 0 {
+                                    active |= 1u64 << bit;
+                                }
+                            });
+                            *trails = active;
+                        }
+                    }
+                }
+            });
+    }
+}
+
+/// Call `f` with the index of every set bit in `bits`
+#[inline]
+fn for_each_bit(mut bits: u64, mut f: impl FnMut(usize)) {
+    while bits != 0 {
+        f(bits.trailing_zeros() as usize);
+        bits &= bits - 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ConwayRule;
+
+    fn blinker() -> BitGrid {
+        let mut grid = BitGrid::new(80, 10);
+        grid.set(69, 5, true);
+        grid.set(70, 5, true);
+        grid.set(71, 5, true);
+        grid
+    }
+
+    #[test]
+    fn test_age_counts_consecutive_generations() {
+        let rule = ConwayRule;
+        let gen0 = blinker();
+        let gen1 = gen0.evolve(&rule);
+        let gen2 = gen1.evolve(&rule);
+
+        let mut history = CellHistory::new(HistoryKind::Age, &gen0);
+        assert_eq!(history.get(70, 5), 1);
+
+        history.update(&gen0, &gen1);
+        assert_eq!(history.get(70, 5), 2, "center survives");
+        assert_eq!(history.get(70, 4), 1, "newborn");
+        assert_eq!(history.get(69, 5), 0, "died");
+
+        history.update(&gen1, &gen2);
+        assert_eq!(history.get(70, 5), 3);
+        assert_eq!(history.get(69, 5), 1, "reborn");
+        assert_eq!(history.get(70, 4), 0);
+    }
+
+    #[test]
+    fn test_activity_never_resets() {
+        let rule = ConwayRule;
+        let gen0 = blinker();
+        let gen1 = gen0.evolve(&rule);
+        let gen2 = gen1.evolve(&rule);
+
+        let mut history = CellHistory::new(HistoryKind::Activity, &gen0);
+        history.update(&gen0, &gen1);
+        history.update(&gen1, &gen2);
+
+        assert_eq!(history.get(70, 5), 3);
+        assert_eq!(history.get(69, 5), 2);
+        assert_eq!(history.get(70, 4), 1);
+        assert_eq!(history.get(0, 0), 0);
+    }
+
+    #[test]
+    fn test_changes_keep_previous_generation() {
+        let rule = ConwayRule;
+        let gen0 = blinker();
+        let gen1 = gen0.evolve(&rule);
+
+        let mut history = CellHistory::new(HistoryKind::Changes, &gen0);
+        assert_eq!(history.get(69, 5), 1, "alive cells start as survivors");
+
+        history.update(&gen0, &gen1);
+        assert_eq!(history.get(70, 5), 1, "center was alive");
+        assert_eq!(history.get(69, 5), 1, "left end was alive (now dead)");
+        assert_eq!(history.get(70, 4), 0, "newborn was dead");
+        assert_eq!(history.previous_chunks(), gen0.chunks().iter().map(|c| c.0).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_trails_fade_out() {
+        let mut alive = BitGrid::new(10, 10);
+        alive.set(3, 3, true);
+        let empty = BitGrid::new(10, 10);
+
+        let mut history = CellHistory::new(HistoryKind::Trail, &alive).with_trail_length(3);
+        history.update(&alive, &empty);
+        assert_eq!(history.get(3, 3), 3);
+
+        history.update(&empty, &empty);
+        assert_eq!(history.get(3, 3), 2);
+        history.update(&empty, &empty);
+        history.update(&empty, &empty);
+        assert_eq!(history.get(3, 3), 0);
+
+        // Fully faded trails stop being tracked
+        history.update(&empty, &empty);
+        assert_eq!(history.get(3, 3), 0);
+    }
+}
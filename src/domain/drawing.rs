@@ -0,0 +1,281 @@
+//! Geometry behind the drawing tools: brush footprints, straight lines,
+//! rectangles, mirror images and flood fill regions. Shapes are produced
+//! in signed cell coordinates so they may run past the grid edges; the
+//! caller clips them.
+
+use super::BitGrid;
+
+/// Largest brush, in cells across
+pub const MAX_BRUSH_SIZE: usize = 15;
+
+/// Outline of the brush stamped at every cell of a stroke
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrushShape {
+    Square,
+    Circle,
+}
+
+impl BrushShape {
+    pub fn all() -> [BrushShape; 2] {
+        [BrushShape::Square, BrushShape::Circle]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BrushShape::Square => "Square",
+            BrushShape::Circle => "Circle",
+        }
+    }
+
+    /// Offsets of the cells covered by a brush `size` cells across,
+    /// centered on (0, 0) (even sizes extend one more cell right and down)
+    pub fn offsets(&self, size: usize) -> Vec<(isize, isize)> {
+        let size = size.max(1) as isize;
+        let (lo, hi) = (-(size - 1) / 2, size / 2);
+        let center = (lo + hi) as f32 / 2.0;
+        let radius = (size - 1) as f32 / 2.0;
+
+        let mut offsets = Vec::new();
+        for dy in lo..=hi {
+            for dx in lo..=hi {
+                let (fx, fy) = (dx as f32 - center, dy as f32 - center);
+                if *self == BrushShape::Square || fx * fx + fy * fy <= radius * radius + 0.5 {
+                    offsets.push((dx, dy));
+                }
+            }
+        }
+        offsets
+    }
+}
+
+/// Mirror symmetry applied to everything drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Symmetry {
+    None,
+    /// Mirrored left to right across the vertical center line
+    Horizontal,
+    /// Mirrored top to bottom across the horizontal center line
+    Vertical,
+    /// Mirrored both ways
+    FourWay,
+    /// Turned half way around the grid center
+    Rotational,
+}
+
+impl Symmetry {
+    pub fn all() -> [Symmetry; 5] {
+        [Symmetry::None, Symmetry::Horizontal, Symmetry::Vertical, Symmetry::FourWay, Symmetry::Rotational]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Symmetry::None => "None",
+            Symmetry::Horizontal => "Horizontal",
+            Symmetry::Vertical => "Vertical",
+            Symmetry::FourWay => "4-way",
+            Symmetry::Rotational => "Rotational",
+        }
+    }
+
+    /// The transforms giving each image, as (flip left to right, flip top
+    /// to bottom); the first leaves everything in place
+    pub fn flips(&self) -> &'static [(bool, bool)] {
+        match self {
+            Symmetry::None => &[(false, false)],
+            Symmetry::Horizontal => &[(false, false), (true, false)],
+            Symmetry::Vertical => &[(false, false), (false, true)],
+            Symmetry::FourWay => &[(false, false), (true, false), (false, true), (true, true)],
+            Symmetry::Rotational => &[(false, false), (true, true)],
+        }
+    }
+
+    /// (x, y) and its mirror images on a grid of `size`, without duplicates
+    pub fn images(&self, (x, y): (usize, usize), size: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut images: Vec<_> = self
+            .flips()
+            .iter()
+            .map(|&(flip_x, flip_y)| {
+                (if flip_x { size.0 - 1 - x } else { x }, if flip_y { size.1 - 1 - y } else { y })
+            })
+            .collect();
+        images.sort_unstable();
+        images.dedup();
+        images
+    }
+}
+
+/// Cells on the straight line from `from` to `to`, both included
+/// (Bresenham, so consecutive cells always touch)
+pub fn line(from: (isize, isize), to: (isize, isize)) -> Vec<(isize, isize)> {
+    let (dx, dy) = ((to.0 - from.0).abs(), -(to.1 - from.1).abs());
+    let (step_x, step_y) = ((to.0 - from.0).signum(), (to.1 - from.1).signum());
+    let mut error = dx + dy;
+    let (mut x, mut y) = from;
+
+    let mut cells = vec![(x, y)];
+    while (x, y) != to {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+        cells.push((x, y));
+    }
+    cells
+}
+
+/// Cells of the rectangle with corners `a` and `b`: its outline, or every
+/// cell inside when `filled`. Only the cells within `bounds` (top-left and
+/// bottom-right corners, included) are listed, so a rectangle dragged far
+/// past the grid costs no more than the part that can be drawn.
+pub fn rectangle(
+    a: (isize, isize),
+    b: (isize, isize),
+    filled: bool,
+    bounds: ((isize, isize), (isize, isize)),
+) -> Vec<(isize, isize)> {
+    let (x0, x1) = (a.0.min(b.0), a.0.max(b.0));
+    let (y0, y1) = (a.1.min(b.1), a.1.max(b.1));
+    let (left, right) = (x0.max(bounds.0.0), x1.min(bounds.1.0));
+    let (top, bottom) = (y0.max(bounds.0.1), y1.min(bounds.1.1));
+
+    let mut cells = Vec::new();
+    if left > right || top > bottom {
+        return cells;
+    }
+    for y in top..=bottom {
+        if filled || y == y0 || y == y1 {
+            cells.extend((left..=right).map(|x| (x, y)));
+        } else {
+            let sides = if x0 == x1 { &[x0][..] } else { &[x0, x1][..] };
+            cells.extend(sides.iter().filter(|&&x| (left..=right).contains(&x)).map(|&x| (x, y)));
+        }
+    }
+    cells
+}
+
+/// Cells to draw, as a mask covering only the area drawn on, placed with
+/// its top-left corner at `origin` on the grid
+#[derive(Clone)]
+pub struct Mask {
+    pub origin: (usize, usize),
+    pub cells: BitGrid,
+}
+
+impl Mask {
+    pub fn new(origin: (usize, usize), cells: BitGrid) -> Self {
+        Self { origin, cells }
+    }
+
+    /// Set every cell of the mask on `grid` to `alive` (whatever falls
+    /// past the grid edges is dropped)
+    pub fn paint_on(&self, grid: &mut BitGrid, alive: bool) {
+        grid.paint_mask_at(&self.cells, self.origin, alive);
+    }
+}
+
+/// The cells connected to (x, y) through edge-adjacent cells in the same
+/// state, as a grid of the same size. Regions are bounded by the grid
+/// edges rather than wrapping around.
+pub fn flood_region(grid: &BitGrid, x: usize, y: usize) -> BitGrid {
+    let (width, height) = grid.dimensions();
+    let mut region = BitGrid::new(width, height);
+    if x >= width || y >= height {
+        return region;
+    }
+
+    // Scanline fill: claim a whole run of the row, then queue one seed
+    // per run of matching cells above and below it
+    let target = grid.get(x, y);
+    let open = |region: &BitGrid, x: usize, y: usize| grid.get(x, y) == target && !region.get(x, y);
+    let mut seeds = vec![(x, y)];
+    while let Some((x, y)) = seeds.pop() {
+        if !open(&region, x, y) {
+            continue;
+        }
+        let mut left = x;
+        while left > 0 && open(&region, left - 1, y) {
+            left -= 1;
+        }
+        let mut right = x;
+        while right + 1 < width && open(&region, right + 1, y) {
+            right += 1;
+        }
+        for cx in left..=right {
+            region.set(cx, y, true);
+        }
+
+        for ny in [y.checked_sub(1), (y + 1 < height).then_some(y + 1)].into_iter().flatten() {
+            let mut in_run = false;
+            for cx in left..=right {
+                let available = open(&region, cx, ny);
+                if available && !in_run {
+                    seeds.push((cx, ny));
+                }
+                in_run = available;
+            }
+        }
+    }
+    region
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_brush_offsets() {
+        assert_eq!(BrushShape::Square.offsets(1), vec![(0, 0)]);
+        assert_eq!(BrushShape::Square.offsets(4).len(), 16);
+        // A 3-cell circle is a plus sign
+        assert_eq!(BrushShape::Circle.offsets(3), vec![(0, -1), (-1, 0), (0, 0), (1, 0), (0, 1)]);
+        assert_eq!(BrushShape::Circle.offsets(2).len(), 4);
+    }
+
+    #[test]
+    fn test_line_and_rectangle() {
+        assert_eq!(line((0, 0), (3, 1)), vec![(0, 0), (1, 0), (2, 1), (3, 1)]);
+        assert_eq!(line((2, 5), (2, 2)).len(), 4);
+        assert_eq!(line((1, 1), (1, 1)), vec![(1, 1)]);
+
+        let everywhere = ((isize::MIN, isize::MIN), (isize::MAX, isize::MAX));
+        assert_eq!(rectangle((3, 3), (0, 0), false, everywhere).len(), 12);
+        assert_eq!(rectangle((0, 0), (3, 3), true, everywhere).len(), 16);
+        // A huge drag lists only the cells inside the bounds
+        let huge = rectangle((-1_000_000, -5), (1_000_000, 1_000_000), false, ((0, 0), (9, 9)));
+        assert_eq!(huge, Vec::<(isize, isize)>::new());
+        assert_eq!(rectangle((-1_000_000, 2), (1_000_000, 1_000_000), true, ((0, 0), (9, 9))).len(), 80);
+        assert_eq!(rectangle((-5, 2), (5, 1_000_000), false, ((0, 0), (9, 9))).len(), 6 + 7);
+    }
+
+    #[test]
+    fn test_symmetry_images() {
+        assert_eq!(Symmetry::FourWay.images((1, 2), (10, 6)), vec![(1, 2), (1, 3), (8, 2), (8, 3)]);
+        assert_eq!(Symmetry::Rotational.images((0, 0), (10, 6)), vec![(0, 0), (9, 5)]);
+        // The center cell of an odd grid is its own image
+        assert_eq!(Symmetry::FourWay.images((2, 2), (5, 5)), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn test_flood_region_stops_at_walls() {
+        // A closed 5x5 box with a 3x3 hole
+        let mut grid = BitGrid::new(20, 10);
+        for (x, y) in rectangle((2, 2), (6, 6), false, ((0, 0), (19, 9))) {
+            grid.set(x as usize, y as usize, true);
+        }
+
+        let inside = flood_region(&grid, 4, 4);
+        assert_eq!(inside.count_alive(), 9);
+        assert!(inside.get(3, 3) && !inside.get(2, 2) && !inside.get(0, 0));
+
+        let outside = flood_region(&grid, 0, 0);
+        assert_eq!(outside.count_alive(), 200 - 25);
+
+        // Live regions are found the same way
+        assert_eq!(flood_region(&grid, 2, 2).count_alive(), 16);
+    }
+}
@@ -0,0 +1,84 @@
+//! Cell inspector: everything worth knowing about one cell, for the hover
+//! readout that explains why a cell lives or dies.
+
+use super::{object_from, BitGrid, Cell, Object, Rule};
+
+/// State of a cell, its neighborhood, its fate under a rule and the
+/// object it belongs to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CellInfo {
+    pub x: usize,
+    pub y: usize,
+    pub alive: bool,
+    /// Live cells among the eight around it (wrapping at the edges)
+    pub neighbors: u8,
+    /// Whether the rule keeps or makes it alive next generation
+    pub alive_next: bool,
+    /// The object the cell is part of (live cells only)
+    pub object: Option<Object>,
+}
+
+impl CellInfo {
+    /// What happens to the cell next generation
+    pub fn fate(&self) -> &'static str {
+        match (self.alive, self.alive_next) {
+            (true, true) => "survives",
+            (true, false) => "dies",
+            (false, true) => "is born",
+            (false, false) => "stays dead",
+        }
+    }
+}
+
+/// Inspect the cell at (x, y), or None if it lies outside the grid
+pub fn inspect_cell(grid: &BitGrid, rule: &dyn Rule, x: usize, y: usize) -> Option<CellInfo> {
+    let (width, height) = grid.dimensions();
+    if x >= width || y >= height {
+        return None;
+    }
+    let alive = grid.get(x, y);
+    let neighbors = grid.count_neighbors(x, y);
+    let current = if alive { Cell::Alive } else { Cell::Dead };
+    Some(CellInfo {
+        x,
+        y,
+        alive,
+        neighbors,
+        alive_next: rule.evolve(current, neighbors) == Cell::Alive,
+        object: object_from(grid, x, y),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{presets, ConwayRule, SeedsRule};
+
+    #[test]
+    fn test_inspect_blinker() {
+        // Horizontal blinker at (10..=12, 10)
+        let mut grid = BitGrid::new(32, 32);
+        for x in 10..=12 {
+            grid.set(x, 10, true);
+        }
+
+        let end = inspect_cell(&grid, &ConwayRule, 10, 10).unwrap();
+        assert_eq!((end.alive, end.neighbors, end.fate()), (true, 1, "dies"));
+        let middle = inspect_cell(&grid, &ConwayRule, 11, 10).unwrap();
+        assert_eq!((middle.neighbors, middle.fate()), (2, "survives"));
+        let above = inspect_cell(&grid, &ConwayRule, 11, 9).unwrap();
+        assert_eq!((above.alive, above.neighbors, above.fate()), (false, 3, "is born"));
+        assert_eq!(above.object, None);
+
+        let object = middle.object.expect("blinker");
+        assert_eq!((object.cells, object.origin, object.size()), (3, (10, 10), (3, 1)));
+
+        // Seeds never lets a cell survive
+        assert_eq!(inspect_cell(&grid, &SeedsRule, 11, 10).unwrap().fate(), "dies");
+        assert_eq!(inspect_cell(&grid, &ConwayRule, 32, 0), None);
+
+        let mut grid = BitGrid::new(32, 32);
+        presets::glider().place_on(&mut grid, 0, 0);
+        assert_eq!(inspect_cell(&grid, &ConwayRule, 1, 0).unwrap().object.map(|o| o.cells), Some(5));
+    }
+}
@@ -4,12 +4,26 @@ mod rules;
 mod patterns;
 mod bit_grid;
 mod algorithm;
+mod stats;
+mod cell_history;
+mod breakpoint;
+mod probe;
+mod objects;
+mod drawing;
+mod inspect;
 pub mod simd_life;
 pub mod temporal_blocking;
 
 pub use cell::Cell;
 pub use grid::Grid;
-pub use rules::{Rule, ConwayRule, HighLifeRule, SeedsRule, DayAndNightRule, all_rules, default_rule};
-pub use patterns::{Pattern, presets};
-pub use bit_grid::{Chunk64, BitGrid};
+pub use rules::{Rule, ConwayRule, HighLifeRule, SeedsRule, DayAndNightRule, LifeLikeRule, all_rules, default_rule};
+pub use patterns::{Pattern, presets, load_pattern_dir};
+pub use bit_grid::{Chunk64, BitGrid, ResizeAnchor, MAX_GRID_CELLS};
 pub use algorithm::Algorithm;
+pub use stats::{GenerationStats, StatsHistory};
+pub use cell_history::{CellHistory, HistoryKind};
+pub use breakpoint::{Breakpoint, BreakpointHit, Breakpoints, CellRect, Condition};
+pub use probe::{Probe, ProbeSet};
+pub use drawing::{BrushShape, Mask, Symmetry, line, rectangle, flood_region, MAX_BRUSH_SIZE};
+pub use inspect::{CellInfo, inspect_cell};
+pub use objects::{Object, object_from, object_near, nearest_live_cell, population_centroid, LINK_DISTANCE};
@@ -0,0 +1,240 @@
+//! Objects: groups of live cells close enough to belong together, found
+//! by a flood fill over the toroidal grid. Used to lock the camera onto
+//! spaceships and other moving patterns.
+
+use std::collections::{HashSet, VecDeque};
+
+use rayon::prelude::*;
+
+use super::BitGrid;
+
+/// Live cells within this Chebyshev distance belong to the same object.
+/// Two rather than one so spaceships whose phases briefly split apart
+/// (like the LWSS) stay a single object.
+pub const LINK_DISTANCE: i64 = 2;
+
+/// Flood fills stop after this many cells, so picking inside a large
+/// soup stays cheap
+pub const MAX_OBJECT_CELLS: usize = 4096;
+
+/// A connected group of live cells
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Object {
+    /// Number of live cells (at most `MAX_OBJECT_CELLS`)
+    pub cells: usize,
+    /// The fill stopped at `MAX_OBJECT_CELLS` with cells left over, so
+    /// `cells` and the bounding box are lower bounds
+    pub truncated: bool,
+    /// Mean cell position, wrapped into the grid
+    pub centroid: (f32, f32),
+    /// Half the object's width and height, in cells
+    pub radius: (f32, f32),
+    /// Top-left cell of the bounding box, wrapped into the grid
+    pub origin: (usize, usize),
+}
+
+impl Object {
+    /// Width and height of the bounding box, in cells
+    pub fn size(&self) -> (usize, usize) {
+        ((self.radius.0 * 2.0) as usize, (self.radius.1 * 2.0) as usize)
+    }
+}
+
+/// Live cell closest to `pos` within `radius` cells, searching outward in
+/// square rings (toroidal)
+pub fn nearest_live_cell(grid: &BitGrid, pos: (f32, f32), radius: usize) -> Option<(usize, usize)> {
+    let (width, height) = grid.dimensions();
+    let (cx, cy) = (pos.0.floor() as i64, pos.1.floor() as i64);
+    let wrap = |v: i64, size: usize| v.rem_euclid(size as i64) as usize;
+
+    for ring in 0..=radius as i64 {
+        let mut best: Option<((usize, usize), f32)> = None;
+        for dy in -ring..=ring {
+            // Only the ring's border: full rows at the top and bottom,
+            // the two end cells elsewhere
+            let step = if dy.abs() == ring { 1 } else { (2 * ring).max(1) as usize };
+            for dx in (-ring..=ring).step_by(step) {
+                let (x, y) = (wrap(cx + dx, width), wrap(cy + dy, height));
+                if !grid.get(x, y) {
+                    continue;
+                }
+                let dist = (cx as f32 + dx as f32 + 0.5 - pos.0).powi(2) + (cy as f32 + dy as f32 + 0.5 - pos.1).powi(2);
+                if best.is_none_or(|(_, d)| dist < d) {
+                    best = Some(((x, y), dist));
+                }
+            }
+        }
+        if let Some((cell, _)) = best {
+            return Some(cell);
+        }
+    }
+    None
+}
+
+/// The object containing the live cell (x, y)
+pub fn object_from(grid: &BitGrid, x: usize, y: usize) -> Option<Object> {
+    if !grid.get(x, y) {
+        return None;
+    }
+    let (width, height) = grid.dimensions();
+    let wrap = |v: i64, size: usize| v.rem_euclid(size as i64) as usize;
+
+    // Positions are tracked unwrapped so objects straddling an edge get
+    // a sensible centroid and extent
+    let mut visited = HashSet::from([(x, y)]);
+    let mut queue = VecDeque::from([(x as i64, y as i64)]);
+    let (mut sum_x, mut sum_y) = (0.0f64, 0.0f64);
+    let (mut min, mut max) = ((x as i64, y as i64), (x as i64, y as i64));
+    let mut cells = 0;
+    let mut truncated = false;
+
+    while let Some((ux, uy)) = queue.pop_front() {
+        if cells == MAX_OBJECT_CELLS {
+            truncated = true;
+            break;
+        }
+        cells += 1;
+        sum_x += ux as f64;
+        sum_y += uy as f64;
+        min = (min.0.min(ux), min.1.min(uy));
+        max = (max.0.max(ux), max.1.max(uy));
+
+        for dy in -LINK_DISTANCE..=LINK_DISTANCE {
+            for dx in -LINK_DISTANCE..=LINK_DISTANCE {
+                let (nx, ny) = (ux + dx, uy + dy);
+                let cell = (wrap(nx, width), wrap(ny, height));
+                if grid.get(cell.0, cell.1) && visited.insert(cell) {
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+    }
+
+    let centroid = (
+        (sum_x / cells as f64 + 0.5).rem_euclid(width as f64) as f32,
+        (sum_y / cells as f64 + 0.5).rem_euclid(height as f64) as f32,
+    );
+    let radius = ((max.0 - min.0 + 1) as f32 / 2.0, (max.1 - min.1 + 1) as f32 / 2.0);
+    let origin = (wrap(min.0, width), wrap(min.1, height));
+    Some(Object { cells, truncated, centroid, radius, origin })
+}
+
+/// The object with a live cell closest to `pos`, within `radius` cells
+pub fn object_near(grid: &BitGrid, pos: (f32, f32), radius: usize) -> Option<Object> {
+    let (x, y) = nearest_live_cell(grid, pos, radius)?;
+    object_from(grid, x, y)
+}
+
+/// Mean position of every live cell (cell centers), or None if the grid
+/// is empty. Not wrap-aware: a population straddling an edge averages
+/// toward the middle of the grid.
+pub fn population_centroid(grid: &BitGrid) -> Option<(f32, f32)> {
+    let chunk_width = grid.dimensions().0.div_ceil(64);
+    if chunk_width == 0 {
+        return None;
+    }
+
+    // Sum of set bit positions in a chunk from six popcounts: bit k of
+    // the position contributes 2^k for every set bit whose index has it
+    const POSITION_BITS: [u64; 6] = [
+        0xAAAA_AAAA_AAAA_AAAA,
+        0xCCCC_CCCC_CCCC_CCCC,
+        0xF0F0_F0F0_F0F0_F0F0,
+        0xFF00_FF00_FF00_FF00,
+        0xFFFF_0000_FFFF_0000,
+        0xFFFF_FFFF_0000_0000,
+    ];
+
+    let (count, sum_x, sum_y) = grid
+        .chunks()
+        .par_chunks(chunk_width)
+        .enumerate()
+        .map(|(y, row)| {
+            let (mut count, mut sum_x) = (0u64, 0u64);
+            for (chunk_x, chunk) in row.iter().enumerate() {
+                let ones = chunk.0.count_ones() as u64;
+                let offsets: u64 = POSITION_BITS
+                    .iter()
+                    .enumerate()
+                    .map(|(k, mask)| ((chunk.0 & mask).count_ones() as u64) << k)
+                    .sum();
+                count += ones;
+                sum_x += ones * (chunk_x as u64 * 64) + offsets;
+            }
+            (count, sum_x, count * y as u64)
+        })
+        .reduce(|| (0, 0, 0), |a, b| (a.0 + b.0, a.1 + b.1, a.2 + b.2));
+
+    (count > 0).then(|| {
+        (
+            (sum_x as f64 / count as f64 + 0.5) as f32,
+            (sum_y as f64 / count as f64 + 0.5) as f32,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::presets;
+
+    #[test]
+    fn test_object_from_glider() {
+        let mut grid = BitGrid::new(64, 64);
+        presets::glider().place_on(&mut grid, 10, 20);
+        // A far-away block is a separate object
+        presets::block().place_on(&mut grid, 40, 40);
+
+        let object = object_from(&grid, 11, 20).expect("glider cell");
+        assert_eq!((object.cells, object.truncated), (5, false));
+        assert_eq!(object.radius, (1.5, 1.5));
+        assert_eq!((object.origin, object.size()), ((10, 20), (3, 3)));
+        let (cx, cy) = object.centroid;
+        assert!((cx - 11.7).abs() < 0.01 && (cy - 21.9).abs() < 0.01, "{:?}", object.centroid);
+
+        assert_eq!(object_from(&grid, 0, 0), None);
+        assert_eq!(object_near(&grid, (8.0, 19.0), 3).map(|o| o.cells), Some(5));
+        assert_eq!(object_near(&grid, (30.0, 30.0), 3), None);
+    }
+
+    #[test]
+    fn test_object_across_edge() {
+        let mut grid = BitGrid::new(64, 64);
+        grid.set(63, 10, true);
+        grid.set(0, 10, true);
+        grid.set(1, 10, true);
+
+        let object = object_from(&grid, 0, 10).expect("live cell");
+        assert_eq!(object.cells, 3);
+        assert_eq!(object.radius, (1.5, 0.5));
+        assert_eq!(object.origin, (63, 10));
+        assert!((object.centroid.0 - 0.5).abs() < 0.01, "{:?}", object.centroid);
+    }
+
+    #[test]
+    fn test_large_object_is_truncated() {
+        // A 64x64 block is exactly the fill limit; one more cell is over it
+        let mut grid = BitGrid::new(128, 128);
+        for y in 0..64 {
+            for x in 0..64 {
+                grid.set(x, y, true);
+            }
+        }
+        let object = object_from(&grid, 0, 0).unwrap();
+        assert_eq!((object.cells, object.truncated), (MAX_OBJECT_CELLS, false));
+
+        grid.set(64, 0, true);
+        let object = object_from(&grid, 0, 0).unwrap();
+        assert_eq!((object.cells, object.truncated), (MAX_OBJECT_CELLS, true));
+    }
+
+    #[test]
+    fn test_population_centroid() {
+        let mut grid = BitGrid::new(200, 10);
+        assert_eq!(population_centroid(&grid), None);
+
+        grid.set(3, 2, true);
+        grid.set(131, 6, true);
+        assert_eq!(population_centroid(&grid), Some((67.5, 4.5)));
+    }
+}
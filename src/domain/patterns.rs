@@ -1,10 +1,16 @@
 // use super::{Cell, Grid};
 
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::MAX_GRID_CELLS;
+
 /// Represents a pattern that can be placed on the grid
 #[derive(Clone)]
 pub struct Pattern {
-    pub name: &'static str,
-    pub description: &'static str,
+    pub name: String,
+    pub description: String,
     pub width: usize,
     pub height: usize,
     pub cells: Vec<(usize, usize)>,  // Relative coordinates of alive cells
@@ -12,10 +18,10 @@ pub struct Pattern {
 
 impl Pattern {
     /// Create a new pattern from alive cell coordinates
-    pub fn new(name: &'static str, description: &'static str, cells: Vec<(usize, usize)>) -> Self {
+    pub fn new(name: impl Into<String>, description: impl Into<String>, cells: Vec<(usize, usize)>) -> Self {
         let width = cells.iter().map(|(x, _)| *x).max().unwrap_or(0) + 1;
         let height = cells.iter().map(|(_, y)| *y).max().unwrap_or(0) + 1;
-        Self { name, description, width, height, cells }
+        Self { name: name.into(), description: description.into(), width, height, cells }
     }
     
     /// Place pattern on grid at specified position
@@ -24,6 +30,93 @@ impl Pattern {
             grid.set(x + dx, y + dy, true);
         }
     }
+    
+    /// Read a pattern in run-length encoded (RLE) format. `#N` names it
+    /// (else `name` is used) and the first `#C` line describes it; the
+    /// size and rule header is skipped. Patterns larger than
+    /// `MAX_GRID_CELLS` are refused.
+    pub fn parse_rle(text: &str, name: &str) -> Result<Self, String> {
+        let mut name = name.to_string();
+        let mut description = String::new();
+        let mut cells = Vec::new();
+        let (mut x, mut y) = (0usize, 0usize);
+        let too_large = || format!("pattern is larger than {} cells", MAX_GRID_CELLS);
+        
+        for line in text.lines().map(str::trim) {
+            if let Some(comment) = line.strip_prefix('#') {
+                let text = comment.get(1..).unwrap_or_default().trim();
+                match comment.chars().next() {
+                    Some('N') if !text.is_empty() => name = text.to_string(),
+                    Some('C' | 'c') if description.is_empty() => description = text.to_string(),
+                    _ => {}
+                }
+                continue;
+            }
+            if line.is_empty() || line.starts_with('x') {
+                continue;
+            }
+            
+            let mut count = 0usize;
+            for c in line.chars() {
+                let run = count.max(1);
+                match c {
+                    '0'..='9' => {
+                        count = count.checked_mul(10)
+                            .and_then(|count| count.checked_add(c.to_digit(10).unwrap_or(0) as usize))
+                            .ok_or_else(too_large)?;
+                        continue;
+                    }
+                    'b' | '.' => x = x.checked_add(run).ok_or_else(too_large)?,
+                    'o' | 'A' => {
+                        // Checked before allocating, so a huge run can't
+                        // exhaust memory
+                        let end = x.checked_add(run).ok_or_else(too_large)?;
+                        if end.saturating_mul(y.saturating_add(1)) > MAX_GRID_CELLS {
+                            return Err(too_large());
+                        }
+                        cells.extend((x..end).map(|cx| (cx, y)));
+                        x = end;
+                    }
+                    '$' => {
+                        y = y.checked_add(run).ok_or_else(too_large)?;
+                        x = 0;
+                    }
+                    '!' => return Self::from_rle_cells(name, description, cells),
+                    c if c.is_whitespace() => {}
+                    c => return Err(format!("unexpected '{}' in RLE data", c)),
+                }
+                count = 0;
+            }
+        }
+        Self::from_rle_cells(name, description, cells)
+    }
+    
+    fn from_rle_cells(name: String, description: String, cells: Vec<(usize, usize)>) -> Result<Self, String> {
+        if cells.is_empty() {
+            return Err("pattern has no live cells".to_string());
+        }
+        Ok(Self::new(name, description, cells))
+    }
+}
+
+/// Load every `.rle` file in `dir`, sorted by file name. Files that fail
+/// to parse are skipped and reported as "file: error" messages.
+pub fn load_pattern_dir(dir: &Path) -> io::Result<(Vec<Pattern>, Vec<String>)> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rle")))
+        .collect();
+    paths.sort();
+    
+    let (mut patterns, mut errors) = (Vec::new(), Vec::new());
+    for path in paths {
+        let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        match fs::read_to_string(&path).map_err(|e| e.to_string()).and_then(|text| Pattern::parse_rle(&text, &stem)) {
+            Ok(pattern) => patterns.push(pattern),
+            Err(err) => errors.push(format!("{}: {}", path.display(), err)),
+        }
+    }
+    Ok((patterns, errors))
 }
 
 /// Classic Game of Life patterns library
@@ -206,3 +299,33 @@ pub mod presets {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rle() {
+        let text = "#N Glider\n#C The smallest spaceship\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!";
+        let pattern = Pattern::parse_rle(text, "fallback").unwrap();
+        assert_eq!(pattern.name, "Glider");
+        assert_eq!(pattern.description, "The smallest spaceship");
+        assert_eq!(pattern.cells, presets::glider().cells);
+
+        // Runs span lines, blank rows use a counted '$'
+        let pattern = Pattern::parse_rle("x = 2, y = 3\n2o2$\n2o!", "domino").unwrap();
+        assert_eq!(pattern.name, "domino");
+        assert_eq!(pattern.cells, vec![(0, 0), (1, 0), (0, 2), (1, 2)]);
+        assert_eq!((pattern.width, pattern.height), (2, 3));
+
+        assert!(Pattern::parse_rle("x = 1, y = 1\nb!", "empty").is_err());
+        assert!(Pattern::parse_rle("3q!", "bad").is_err());
+
+        // Oversized runs are refused before anything is allocated
+        let too_large = Err(format!("pattern is larger than {} cells", MAX_GRID_CELLS));
+        assert_eq!(Pattern::parse_rle("99999999999999999999999o!", "overflow").map(|p| p.width), too_large);
+        assert_eq!(Pattern::parse_rle("200000000o!", "wide").map(|p| p.width), too_large);
+        assert_eq!(Pattern::parse_rle("20000$20000o!", "tall").map(|p| p.width), too_large);
+        assert_eq!(Pattern::parse_rle("100000$100o!", "sparse").map(|p| p.height), Ok(100_001));
+    }
+}
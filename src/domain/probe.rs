@@ -0,0 +1,390 @@
+//! Named cell probes for watching signals in circuits.
+//!
+//! Each probe samples one cell after every generation, including those
+//! inside a batch of several generations evolved at once. Samples
+//! are keyed by generation number, so replaying a generation overwrites
+//! its sample instead of appending a duplicate. The recorded timeline can
+//! be exported as CSV (one column per probe) or saved and loaded together
+//! with the probe positions, so probes carry over to the next session.
+
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::BitGrid;
+
+/// Default number of generations kept per probe
+pub const DEFAULT_PROBE_CAPACITY: usize = 100_000;
+
+/// A named cell whose state is recorded every generation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Probe {
+    pub name: String,
+    pub x: usize,
+    pub y: usize,
+    samples: BTreeMap<u64, bool>,
+}
+
+impl Probe {
+    pub fn new(name: impl Into<String>, x: usize, y: usize) -> Self {
+        Self { name: name.into(), x, y, samples: BTreeMap::new() }
+    }
+
+    /// Recorded state at `generation`, if sampled
+    pub fn sample(&self, generation: u64) -> Option<bool> {
+        self.samples.get(&generation).copied()
+    }
+
+    /// State at `generation`, holding the last sample taken before it
+    /// (a batch that ran while probes were being changed is only sampled
+    /// where it ends)
+    pub fn state_at(&self, generation: u64) -> Option<bool> {
+        self.samples.range(..=generation).next_back().map(|(_, &alive)| alive)
+    }
+
+    /// Samples from `start` to `end` inclusive, oldest first
+    pub fn samples_between(&self, start: u64, end: u64) -> impl Iterator<Item = (u64, bool)> + '_ {
+        self.samples.range(start..=end).map(|(&generation, &alive)| (generation, alive))
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+}
+
+/// All probes placed on the grid
+#[derive(Clone, Debug)]
+pub struct ProbeSet {
+    probes: Vec<Probe>,
+    capacity: usize,
+}
+
+impl ProbeSet {
+    /// Create an empty set keeping at most `capacity` samples per probe
+    pub fn new(capacity: usize) -> Self {
+        Self { probes: Vec::new(), capacity: capacity.max(1) }
+    }
+
+    /// Place a probe and take its first sample from `grid`.
+    /// A probe already on (x, y) is renamed instead of duplicated.
+    pub fn add(&mut self, name: impl Into<String>, x: usize, y: usize, grid: &BitGrid, generation: u64) {
+        let name = name.into();
+        if let Some(index) = self.index_at(x, y) {
+            self.probes[index].name = name;
+            return;
+        }
+        let mut probe = Probe::new(name, x, y);
+        probe.samples.insert(generation, grid.get(x, y));
+        self.probes.push(probe);
+    }
+
+    /// Remove the probe on (x, y); returns whether one was there
+    pub fn remove_at(&mut self, x: usize, y: usize) -> bool {
+        match self.index_at(x, y) {
+            Some(index) => {
+                self.probes.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn rename(&mut self, index: usize, name: impl Into<String>) {
+        if let Some(probe) = self.probes.get_mut(index) {
+            probe.name = name.into();
+        }
+    }
+
+    /// Index of the probe on (x, y)
+    pub fn index_at(&self, x: usize, y: usize) -> Option<usize> {
+        self.probes.iter().position(|p| p.x == x && p.y == y)
+    }
+
+    /// Name not used by any probe yet ("P1", "P2", ...)
+    pub fn next_name(&self) -> String {
+        (1..)
+            .map(|n| format!("P{}", n))
+            .find(|name| self.probes.iter().all(|p| &p.name != name))
+            .expect("unbounded names")
+    }
+
+    /// Remove all probes
+    pub fn clear(&mut self) {
+        self.probes.clear();
+    }
+
+    /// Forget recorded samples but keep the probes
+    pub fn clear_samples(&mut self) {
+        for probe in &mut self.probes {
+            probe.samples.clear();
+        }
+    }
+
+    /// Move every probe by `offset` along with the grid contents after a
+    /// resize to `size`, dropping probes whose cell was cropped
+    pub fn shift(&mut self, offset: (isize, isize), size: (usize, usize)) {
+        self.probes.retain_mut(|probe| {
+            let x = probe.x as isize + offset.0;
+            let y = probe.y as isize + offset.1;
+            if x < 0 || y < 0 || x >= size.0 as isize || y >= size.1 as isize {
+                return false;
+            }
+            (probe.x, probe.y) = (x as usize, y as usize);
+            true
+        });
+    }
+
+    /// Sample every probe from `grid`
+    pub fn record(&mut self, grid: &BitGrid, generation: u64) {
+        for probe in &mut self.probes {
+            probe.samples.insert(generation, grid.get(probe.x, probe.y));
+            while probe.samples.len() > self.capacity {
+                probe.samples.pop_first();
+            }
+        }
+    }
+
+    /// Record states sampled elsewhere (e.g. inside a batch evolved in the
+    /// background), one per probe in order
+    pub fn record_states(&mut self, generation: u64, states: &[bool]) {
+        for (probe, &alive) in self.probes.iter_mut().zip(states) {
+            probe.samples.insert(generation, alive);
+            while probe.samples.len() > self.capacity {
+                probe.samples.pop_first();
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Probe> {
+        self.probes.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.probes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.probes.is_empty()
+    }
+
+    /// Every generation sampled by at least one probe, ascending
+    fn generations(&self) -> Vec<u64> {
+        let mut generations: Vec<u64> = self.probes
+            .iter()
+            .flat_map(|p| p.samples.keys().copied())
+            .collect();
+        generations.sort_unstable();
+        generations.dedup();
+        generations
+    }
+
+    /// Write the timeline as CSV: one row per generation, one 0/1 column
+    /// per probe (empty where a probe has no sample)
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        let names: Vec<String> = self.probes.iter().map(|p| csv_field(&p.name)).collect();
+        writeln!(out, "generation,{}", names.join(","))?;
+
+        for generation in self.generations() {
+            let row: Vec<&str> = self.probes
+                .iter()
+                .map(|p| match p.sample(generation) {
+                    Some(true) => "1",
+                    Some(false) => "0",
+                    None => "",
+                })
+                .collect();
+            writeln!(out, "{},{}", generation, row.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Export the timeline as CSV
+    pub fn export_csv(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_csv(&mut out)?;
+        out.flush()
+    }
+
+    /// Write probes and their samples in a line-based format readable by
+    /// `parse`:
+    /// `probe <x> <y> <name>` followed by `<generation> <0|1>` lines
+    pub fn write_to<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for probe in &self.probes {
+            writeln!(out, "probe {} {} {}", probe.x, probe.y, probe.name)?;
+            for (generation, alive) in &probe.samples {
+                writeln!(out, "{} {}", generation, u8::from(*alive))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read a set written by `write_to`
+    pub fn parse(text: &str, capacity: usize) -> Result<Self, String> {
+        let mut set = Self::new(capacity);
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |what: &str| format!("line {}: {}", number + 1, what);
+
+            if let Some(rest) = line.strip_prefix("probe ") {
+                let mut parts = rest.splitn(3, ' ');
+                let x = parts.next().and_then(|v| v.parse().ok()).ok_or_else(|| error("bad x"))?;
+                let y = parts.next().and_then(|v| v.parse().ok()).ok_or_else(|| error("bad y"))?;
+                let name = parts.next().unwrap_or_default();
+                set.probes.push(Probe::new(name, x, y));
+                continue;
+            }
+
+            let capacity = set.capacity;
+            let probe = set.probes.last_mut().ok_or_else(|| error("sample before any probe"))?;
+            let (generation, state) = line.split_once(' ').ok_or_else(|| error("expected '<generation> <0|1>'"))?;
+            let generation = generation.parse().map_err(|_| error("bad generation"))?;
+            let alive = match state {
+                "1" => true,
+                "0" => false,
+                _ => return Err(error("state must be 0 or 1")),
+            };
+            probe.samples.insert(generation, alive);
+            while probe.samples.len() > capacity {
+                probe.samples.pop_first();
+            }
+        }
+
+        Ok(set)
+    }
+
+    /// Save probes and samples to a file
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write_to(&mut out)?;
+        out.flush()
+    }
+
+    /// Load probes and samples saved with `save`
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text, DEFAULT_PROBE_CAPACITY)
+            .map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+}
+
+impl Default for ProbeSet {
+    fn default() -> Self {
+        Self::new(DEFAULT_PROBE_CAPACITY)
+    }
+}
+
+/// Quote a CSV field if it contains separators or quotes
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ConwayRule;
+
+    fn blinker() -> BitGrid {
+        let mut grid = BitGrid::new(10, 10);
+        grid.set(4, 5, true);
+        grid.set(5, 5, true);
+        grid.set(6, 5, true);
+        grid
+    }
+
+    #[test]
+    fn test_shift_moves_and_crops_probes() {
+        let grid = blinker();
+        let mut probes = ProbeSet::default();
+        probes.add("a", 1, 1, &grid, 0);
+        probes.add("b", 8, 8, &grid, 0);
+
+        // "a" moves off the left edge, "b" stays inside
+        probes.shift((-2, 1), (10, 10));
+        assert_eq!(probes.len(), 1);
+        let b = probes.iter().next().unwrap();
+        assert_eq!((b.name.as_str(), b.x, b.y), ("b", 6, 9));
+        assert_eq!(b.sample(0), Some(false));
+    }
+
+    #[test]
+    fn test_probe_records_waveform() {
+        let mut grid = blinker();
+        let mut probes = ProbeSet::default();
+        probes.add("end", 4, 5, &grid, 0);
+        probes.add("center", 5, 5, &grid, 0);
+
+        for generation in 1..=4 {
+            grid = grid.evolve(&ConwayRule);
+            probes.record(&grid, generation);
+        }
+
+        let end: Vec<bool> = probes.iter().next().unwrap().samples_between(0, 4).map(|(_, s)| s).collect();
+        assert_eq!(end, vec![true, false, true, false, true]);
+        assert!((0..=4).all(|g| probes.iter().nth(1).unwrap().sample(g) == Some(true)));
+    }
+
+    #[test]
+    fn test_replayed_generation_overwrites_sample() {
+        let grid = blinker();
+        let mut probes = ProbeSet::default();
+        probes.add("a", 4, 5, &grid, 0);
+        probes.record(&grid, 1);
+        probes.record(&BitGrid::new(10, 10), 1);
+
+        let probe = probes.iter().next().unwrap();
+        assert_eq!(probe.len(), 2);
+        assert_eq!(probe.sample(1), Some(false));
+        assert_eq!(probe.state_at(7), Some(false));
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let grid = blinker();
+        let mut probes = ProbeSet::default();
+        probes.add("a", 4, 5, &grid, 0);
+        probes.record(&BitGrid::new(10, 10), 1);
+        probes.add("b,c", 0, 0, &grid, 1);
+
+        let mut out = Vec::new();
+        probes.write_csv(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(text, "generation,a,\"b,c\"\n0,1,\n1,0,0\n");
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let grid = blinker();
+        let mut probes = ProbeSet::default();
+        probes.add("clock out", 5, 5, &grid, 3);
+        probes.record(&BitGrid::new(10, 10), 4);
+        probes.add("idle", 0, 0, &grid, 4);
+
+        let path = std::env::temp_dir().join(format!("probes_round_trip_{}.probes", std::process::id()));
+        probes.save(&path).unwrap();
+        let loaded = ProbeSet::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+
+        let saved: Vec<_> = probes.iter().collect();
+        let restored: Vec<_> = loaded.iter().collect();
+        assert_eq!(saved, restored);
+        assert_eq!(restored[0].name, "clock out");
+        assert_eq!(restored[0].sample(4), Some(false));
+
+        assert!(ProbeSet::parse("0 1", 10).is_err());
+        assert_eq!(ProbeSet::parse("probe 1 1 a\n7 2", 10).unwrap_err(), "line 2: state must be 0 or 1");
+    }
+}
@@ -4,10 +4,10 @@ use super::Cell;
 /// Enables different rulesets beyond Conway's Game of Life
 pub trait Rule: Send + Sync {
     /// Name of the rule
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
     
     /// Short description
-    fn description(&self) -> &'static str;
+    fn description(&self) -> &str;
     
     /// Apply rule to compute next cell state
     fn evolve(&self, current: Cell, neighbors: u8) -> Cell;
@@ -19,11 +19,11 @@ pub trait Rule: Send + Sync {
 pub struct ConwayRule;
 
 impl Rule for ConwayRule {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "Conway"
     }
     
-    fn description(&self) -> &'static str {
+    fn description(&self) -> &str {
         "B3/S23 - Classic"
     }
     
@@ -43,11 +43,11 @@ impl Rule for ConwayRule {
 pub struct HighLifeRule;
 
 impl Rule for HighLifeRule {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "HighLife"
     }
     
-    fn description(&self) -> &'static str {
+    fn description(&self) -> &str {
         "B36/S23 - Replicators"
     }
     
@@ -67,11 +67,11 @@ impl Rule for HighLifeRule {
 pub struct SeedsRule;
 
 impl Rule for SeedsRule {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "Seeds"
     }
     
-    fn description(&self) -> &'static str {
+    fn description(&self) -> &str {
         "B2/S - Exploding"
     }
     
@@ -89,11 +89,11 @@ impl Rule for SeedsRule {
 pub struct DayAndNightRule;
 
 impl Rule for DayAndNightRule {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "Day&Night"
     }
     
-    fn description(&self) -> &'static str {
+    fn description(&self) -> &str {
         "B3678/S34678"
     }
     
@@ -106,6 +106,131 @@ impl Rule for DayAndNightRule {
     }
 }
 
+/// Any life-like rule, given by the neighbor counts that cause a birth
+/// and those that let a live cell survive, e.g. "B36/S23".
+/// Bit n of each mask stands for n live neighbors (0-8).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifeLikeRule {
+    birth: u16,
+    survival: u16,
+    notation: String,
+}
+
+impl LifeLikeRule {
+    /// All nine neighbor counts
+    pub const MASK: u16 = 0x1FF;
+
+    pub fn new(birth: u16, survival: u16) -> Self {
+        let (birth, survival) = (birth & Self::MASK, survival & Self::MASK);
+        Self { birth, survival, notation: notation(birth, survival) }
+    }
+
+    /// The masks `rule` actually computes, found by evaluating it for
+    /// every neighbor count
+    pub fn from_rule(rule: &dyn Rule) -> Self {
+        let mask = |current: Cell| {
+            (0..=8u8)
+                .filter(|&n| rule.evolve(current, n) == Cell::Alive)
+                .fold(0u16, |mask, n| mask | 1 << n)
+        };
+        Self::new(mask(Cell::Dead), mask(Cell::Alive))
+    }
+
+    /// Parse "B3/S23"-style notation. Case-insensitive; the slash is
+    /// optional and the parts may come in either order ("S23/B3").
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let text = text.trim().to_ascii_uppercase();
+        let mut masks = [None, None];
+
+        for part in text.split('/').filter(|part| !part.is_empty()).flat_map(split_at_letters) {
+            let mut chars = part.chars();
+            let (slot, letter) = match chars.next() {
+                Some('B') => (0, 'B'),
+                Some('S') => (1, 'S'),
+                _ => return Err(format!("expected B.../S... notation, got '{}'", text)),
+            };
+            let digits = chars.as_str();
+            if masks[slot].is_some() {
+                return Err(format!("'{}' appears twice in '{}'", letter, text));
+            }
+            let mut mask = 0u16;
+            for c in digits.chars() {
+                match c.to_digit(10) {
+                    Some(n) if n <= 8 => mask |= 1 << n,
+                    _ => return Err(format!("'{}' is not a neighbor count (0-8)", c)),
+                }
+            }
+            masks[slot] = Some(mask);
+        }
+
+        match masks {
+            [Some(birth), Some(survival)] => Ok(Self::new(birth, survival)),
+            _ => Err(format!("'{}' needs both a B and an S part", text)),
+        }
+    }
+
+    pub fn birth(&self) -> u16 {
+        self.birth
+    }
+
+    pub fn survival(&self) -> u16 {
+        self.survival
+    }
+
+    /// The rule with birth on `neighbors` switched on or off
+    pub fn with_birth(&self, neighbors: usize, on: bool) -> Self {
+        Self::new(set_bit(self.birth, neighbors, on), self.survival)
+    }
+
+    /// The rule with survival on `neighbors` switched on or off
+    pub fn with_survival(&self, neighbors: usize, on: bool) -> Self {
+        Self::new(self.birth, set_bit(self.survival, neighbors, on))
+    }
+}
+
+/// "B36/S23" for the given masks
+fn notation(birth: u16, survival: u16) -> String {
+    let digits = |mask: u16| (0..=8).filter(|n| mask & (1 << n) != 0).map(|n| n.to_string()).collect::<String>();
+    format!("B{}/S{}", digits(birth), digits(survival))
+}
+
+fn set_bit(mask: u16, bit: usize, on: bool) -> u16 {
+    if on { mask | 1 << bit } else { mask & !(1 << bit) }
+}
+
+/// "B3S23" -> ["B3", "S23"]
+fn split_at_letters(text: &str) -> Vec<&str> {
+    let starts: Vec<usize> = text.char_indices().filter(|(_, c)| c.is_ascii_alphabetic()).map(|(i, _)| i).collect();
+    if starts.first() != Some(&0) {
+        return vec![text];
+    }
+    starts.iter().enumerate()
+        .map(|(i, &start)| &text[start..starts.get(i + 1).copied().unwrap_or(text.len())])
+        .collect()
+}
+
+impl Rule for LifeLikeRule {
+    fn name(&self) -> &str {
+        &self.notation
+    }
+    
+    fn description(&self) -> &str {
+        "Custom life-like rule"
+    }
+    
+    fn evolve(&self, current: Cell, neighbors: u8) -> Cell {
+        let mask = match current {
+            Cell::Alive => self.survival,
+            Cell::Dead => self.birth,
+        };
+        if neighbors <= 8 && mask & (1 << neighbors) != 0 {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
 /// Get all available rules
 pub fn all_rules() -> Vec<(&'static str, Box<dyn Rule>)> {
     vec![
@@ -166,4 +291,36 @@ mod tests {
         assert_eq!(rule.evolve(Cell::Dead, 2), Cell::Alive);
         assert_eq!(rule.evolve(Cell::Dead, 3), Cell::Dead);
     }
+
+    #[test]
+    fn test_life_like_parse_and_notation() {
+        let rule = LifeLikeRule::parse("b36/s23").unwrap();
+        assert_eq!(rule.name(), "B36/S23");
+        assert_eq!(rule, LifeLikeRule::from_rule(&HighLifeRule));
+        assert_eq!(LifeLikeRule::parse("S23B3").unwrap(), LifeLikeRule::from_rule(&ConwayRule));
+        assert_eq!(LifeLikeRule::parse("B2/S").unwrap().name(), "B2/S");
+
+        assert!(LifeLikeRule::parse("B39/S23").is_err());
+        assert!(LifeLikeRule::parse("B3").is_err());
+        assert!(LifeLikeRule::parse("B3/B4").is_err());
+        assert!(LifeLikeRule::parse("23/3").is_err());
+        assert!(LifeLikeRule::parse("é/").is_err());
+    }
+
+    #[test]
+    fn test_life_like_matches_builtin_rules() {
+        for (_, builtin) in all_rules() {
+            let rule = LifeLikeRule::from_rule(builtin.as_ref());
+            assert_eq!(LifeLikeRule::parse(rule.name()).unwrap(), rule);
+            for neighbors in 0..=8 {
+                for current in [Cell::Dead, Cell::Alive] {
+                    assert_eq!(rule.evolve(current, neighbors), builtin.evolve(current, neighbors));
+                }
+            }
+        }
+        assert_eq!(LifeLikeRule::from_rule(&DayAndNightRule).name(), "B3678/S34678");
+
+        let rule = LifeLikeRule::from_rule(&ConwayRule).with_birth(6, true).with_survival(3, false);
+        assert_eq!(rule.name(), "B36/S2");
+    }
 }
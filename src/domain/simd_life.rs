@@ -26,6 +26,7 @@ pub fn build_rule_lookup(rule: &dyn Rule) -> [bool; 32] {
 /// Compute neighbor counts for a chunk and return the 4-bit count per cell position.
 /// Returns (bit0, bit1, bit2, bit3) where count = bit3*8 + bit2*4 + bit1*2 + bit0
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn compute_neighbor_counts(
     above: u64,
     current: u64,
@@ -97,6 +98,7 @@ fn apply_rule_lookup(current: u64, bit0: u64, bit1: u64, bit2: u64, bit3: u64, l
 /// Compute next chunk using Conway's rules (optimized bitwise version)
 /// Kept for backwards compatibility and maximum performance when using Conway's rules.
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn compute_next_chunk_conway(
     above: u64,
     current: u64,
@@ -126,6 +128,7 @@ pub fn compute_next_chunk_conway(
 
 /// Compute next chunk using arbitrary rules via lookup table
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn compute_next_chunk_with_rule(
     above: u64,
     current: u64,
@@ -150,6 +153,7 @@ pub fn compute_next_chunk_with_rule(
 
 /// Legacy function name for backwards compatibility
 #[inline]
+#[allow(clippy::too_many_arguments)]
 pub fn compute_next_chunk(
     above: u64,
     current: u64,
@@ -183,63 +187,77 @@ fn half_adder(a: u64, b: u64) -> (u64, u64) {
     (a ^ b, a & b)
 }
 
-/// Helper to get edge bits for a chunk with toroidal wrapping
+/// Evolve row `y` of `grid` into `out` (one chunk per entry), wrapping
+/// around the grid edges. Chunks are read whole and each chunk's edge
+/// neighbors are shifted out of the chunks next to it; only the row ends
+/// need the wrapped-around cells, which are read once per row.
 #[inline]
-fn get_edge_bits(grid: &BitGrid, chunk_x: usize, y: usize, chunk_width: usize, height: usize) -> (bool, bool, bool, bool, bool, bool) {
-    // Toroidal wrapping for y coordinates
+fn evolve_row(grid: &BitGrid, y: usize, lookup: &[bool; 32], out: &mut [u64]) {
+    let (width, height) = grid.dimensions();
+    let Some(last) = out.len().checked_sub(1) else {
+        return;
+    };
+    
+    // Toroidal wrapping for above/below rows
     let ya = if y > 0 { y - 1 } else { height - 1 };
     let yb = if y + 1 < height { y + 1 } else { 0 };
-    
-    // Left neighbors come from bit 0 of the next chunk (chunk_x + 1), with wrapping
-    let next_chunk_x = if chunk_x + 1 < chunk_width { chunk_x + 1 } else { 0 };
-    let left_above = (grid.get_chunk(next_chunk_x, ya) & 1) != 0;
-    let left_current = (grid.get_chunk(next_chunk_x, y) & 1) != 0;
-    let left_below = (grid.get_chunk(next_chunk_x, yb) & 1) != 0;
-    
-    // Right neighbors come from bit 63 of the previous chunk (chunk_x - 1), with wrapping
-    let prev_chunk_x = if chunk_x > 0 { chunk_x - 1 } else { chunk_width - 1 };
-    let right_above = (grid.get_chunk(prev_chunk_x, ya) >> 63) != 0;
-    let right_current = (grid.get_chunk(prev_chunk_x, y) >> 63) != 0;
-    let right_below = (grid.get_chunk(prev_chunk_x, yb) >> 63) != 0;
-    
-    (left_above, right_above, left_current, right_current, left_below, right_below)
+    let rows = [ya, y, yb];
+    
+    // Cells used in the row's last chunk, and the first and last cell of
+    // each row, which are each other's wrapped-around neighbors
+    let used = width - last * 64;
+    let first_cells = rows.map(|row| grid.get_chunk(0, row) & 1 != 0);
+    let last_cells = rows.map(|row| (grid.get_chunk(last, row) >> (used - 1)) & 1 != 0);
+    
+    let mut current = rows.map(|row| grid.get_chunk(0, row));
+    // Right neighbors are shifted into bit 0 and come from the cell just
+    // before the chunk, which for the first chunk is the row's last cell
+    let mut right = last_cells;
+    for (chunk_x, out) in out.iter_mut().enumerate() {
+        let next = if chunk_x < last { rows.map(|row| grid.get_chunk(chunk_x + 1, row)) } else { [0; 3] };
+        
+        // Left neighbors are shifted into bit 63 and come from the cell
+        // just past the chunk. A partly used last chunk instead gets the
+        // row's first cell copied into the padding bit past its last cell,
+        // so shifting brings it next to the last cell.
+        let (chunks, left) = if chunk_x < last {
+            (current, next.map(|chunk| chunk & 1 != 0))
+        } else if used == 64 {
+            (current, first_cells)
+        } else {
+            let wrapped = [0, 1, 2].map(|i| current[i] | (u64::from(first_cells[i]) << used));
+            (wrapped, [false; 3])
+        };
+        
+        *out = compute_next_chunk_with_rule(
+            chunks[0], chunks[1], chunks[2],
+            left[0], right[0],
+            left[1], right[1],
+            left[2], right[2],
+            lookup,
+        ) & grid.chunk_mask(chunk_x);
+        
+        right = current.map(|chunk| chunk >> 63 != 0);
+        current = next;
+    }
 }
 
 /// Evolve a BitGrid using SIMD-optimized bit operations with specified rule (toroidal)
 pub fn evolve_simd(grid: &BitGrid, rule: &dyn Rule) -> BitGrid {
     let (width, height) = grid.dimensions();
-    let mut next = BitGrid::new(width, height);
-    let chunk_width = (width + 63) / 64;
+    let chunk_width = width.div_ceil(64);
     
     // Build lookup table for this rule
     let lookup = build_rule_lookup(rule);
     
-    for y in 0..height {
-        for chunk_x in 0..chunk_width {
-            // Toroidal wrapping for above/below rows
-            let ya = if y > 0 { y - 1 } else { height - 1 };
-            let yb = if y + 1 < height { y + 1 } else { 0 };
-            
-            let above = grid.get_chunk(chunk_x, ya);
-            let current = grid.get_chunk(chunk_x, y);
-            let below = grid.get_chunk(chunk_x, yb);
-            
-            let (left_above, right_above, left_current, right_current, left_below, right_below) = 
-                get_edge_bits(grid, chunk_x, y, chunk_width, height);
-            
-            let next_chunk = compute_next_chunk_with_rule(
-                above, current, below,
-                left_above, right_above,
-                left_current, right_current,
-                left_below, right_below,
-                &lookup,
-            );
-            
-            next.set_chunk(chunk_x, y, next_chunk);
+    let mut output_chunks: Vec<u64> = vec![0u64; height * chunk_width];
+    if chunk_width > 0 {
+        for (y, row_output) in output_chunks.chunks_mut(chunk_width).enumerate() {
+            evolve_row(grid, y, &lookup, row_output);
         }
     }
     
-    next
+    BitGrid::from_chunks(width, height, output_chunks)
 }
 
 /// Parallel SIMD evolution using rayon with specified rule (toroidal)
@@ -248,13 +266,16 @@ pub fn evolve_simd_parallel(grid: &BitGrid, rule: &(dyn Rule + Sync)) -> BitGrid
     use rayon::prelude::*;
     
     let (width, height) = grid.dimensions();
-    let chunk_width = (width + 63) / 64;
+    let chunk_width = width.div_ceil(64);
     
     // Build lookup table for this rule
     let lookup = build_rule_lookup(rule);
     
     // Pre-allocate output chunks as contiguous buffer
     let mut output_chunks: Vec<u64> = vec![0u64; height * chunk_width];
+    if chunk_width == 0 {
+        return BitGrid::from_chunks(width, height, output_chunks);
+    }
     
     // Batch multiple rows per task to reduce rayon scheduling overhead
     // Target: ~16-32 tasks per thread for good load balancing
@@ -266,28 +287,7 @@ pub fn evolve_simd_parallel(grid: &BitGrid, rule: &(dyn Rule + Sync)) -> BitGrid
         .par_chunks_mut(chunk_width)
         .enumerate()
         .with_min_len(min_rows_per_task)
-        .for_each(|(y, row_output)| {
-            // Toroidal wrapping
-            let ya = if y > 0 { y - 1 } else { height - 1 };
-            let yb = if y + 1 < height { y + 1 } else { 0 };
-            
-            for chunk_x in 0..chunk_width {
-                let above = grid.get_chunk(chunk_x, ya);
-                let current = grid.get_chunk(chunk_x, y);
-                let below = grid.get_chunk(chunk_x, yb);
-                
-                let (left_above, right_above, left_current, right_current, left_below, right_below) = 
-                    get_edge_bits(grid, chunk_x, y, chunk_width, height);
-                
-                row_output[chunk_x] = compute_next_chunk_with_rule(
-                    above, current, below,
-                    left_above, right_above,
-                    left_current, right_current,
-                    left_below, right_below,
-                    &lookup,
-                );
-            }
-        });
+        .for_each(|(y, row_output)| evolve_row(grid, y, &lookup, row_output));
     
     BitGrid::from_chunks(width, height, output_chunks)
 }
@@ -446,6 +446,19 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_simd_wraps_at_every_width() {
+        // Row ends in a partly used chunk, a full chunk and a single chunk
+        for width in [1, 3, 63, 64, 65, 127, 128, 130] {
+            let mut grid = BitGrid::new(width, 7);
+            grid.randomize();
+            let naive = grid.evolve(&ConwayRule);
+            let simd = evolve_simd(&grid, &ConwayRule);
+            assert_eq!(simd.chunks(), naive.chunks(), "width {}", width);
+            assert_eq!(evolve_simd_parallel(&grid, &ConwayRule).chunks(), naive.chunks(), "width {}", width);
+        }
+    }
+    
     #[test]
     fn test_simd_parallel_matches_serial() {
         let rule = ConwayRule;
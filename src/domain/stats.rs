@@ -0,0 +1,259 @@
+//! Population statistics recorded per generation.
+//!
+//! Each step of the simulation produces a `GenerationStats` sample.
+//! Samples are kept in a fixed-capacity ring buffer (`StatsHistory`)
+//! that can be plotted live or exported as CSV / JSON Lines.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::BitGrid;
+
+/// Default number of samples kept before the oldest are dropped
+pub const DEFAULT_HISTORY_CAPACITY: usize = 100_000;
+
+/// Population metrics for a single generation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenerationStats {
+    pub generation: u64,
+    pub population: usize,
+    pub births: usize,
+    pub deaths: usize,
+    /// Inclusive (min_x, min_y, max_x, max_y) of live cells, None if empty
+    pub bounding_box: Option<(usize, usize, usize, usize)>,
+    /// Live cells divided by total cells
+    pub density: f64,
+}
+
+impl GenerationStats {
+    /// Sample a grid on its own (no births or deaths known)
+    pub fn from_grid(grid: &BitGrid, generation: u64) -> Self {
+        Self::build(grid, generation, 0, 0)
+    }
+
+    /// Sample `current`, counting births and deaths relative to `previous`
+    pub fn between(previous: &BitGrid, current: &BitGrid, generation: u64) -> Self {
+        let (births, deaths) = current.count_changes(previous);
+        Self::build(current, generation, births, deaths)
+    }
+
+    fn build(grid: &BitGrid, generation: u64, births: usize, deaths: usize) -> Self {
+        let (width, height) = grid.dimensions();
+        let population = grid.count_alive();
+        let total = width * height;
+
+        Self {
+            generation,
+            population,
+            births,
+            deaths,
+            bounding_box: grid.bounding_box(),
+            density: if total > 0 { population as f64 / total as f64 } else { 0.0 },
+        }
+    }
+
+    /// Format as a CSV row matching `CSV_HEADER`
+    fn to_csv_row(self) -> String {
+        let (min_x, min_y, max_x, max_y) = match self.bounding_box {
+            Some((a, b, c, d)) => (a.to_string(), b.to_string(), c.to_string(), d.to_string()),
+            None => Default::default(),
+        };
+        format!(
+            "{},{},{},{},{},{},{},{},{:.6}",
+            self.generation, self.population, self.births, self.deaths,
+            min_x, min_y, max_x, max_y, self.density
+        )
+    }
+
+    /// Format as a single-line JSON object
+    fn to_json(self) -> String {
+        let bbox = match self.bounding_box {
+            Some((min_x, min_y, max_x, max_y)) => format!(
+                "{{\"min_x\":{},\"min_y\":{},\"max_x\":{},\"max_y\":{}}}",
+                min_x, min_y, max_x, max_y
+            ),
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"generation\":{},\"population\":{},\"births\":{},\"deaths\":{},\"bounding_box\":{},\"density\":{:.6}}}",
+            self.generation, self.population, self.births, self.deaths, bbox, self.density
+        )
+    }
+}
+
+/// Column names for CSV export
+const CSV_HEADER: &str = "generation,population,births,deaths,min_x,min_y,max_x,max_y,density";
+
+/// Ring buffer of per-generation samples
+#[derive(Clone, Debug)]
+pub struct StatsHistory {
+    samples: VecDeque<GenerationStats>,
+    capacity: usize,
+}
+
+impl StatsHistory {
+    /// Create an empty history holding at most `capacity` samples
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append a sample, dropping the oldest one when full
+    pub fn record(&mut self, sample: GenerationStats) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Drop all samples
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Most recent sample
+    pub fn latest(&self) -> Option<&GenerationStats> {
+        self.samples.back()
+    }
+
+    /// Iterate samples from oldest to newest
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &GenerationStats> + ExactSizeIterator + '_ {
+        self.samples.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Write all samples as CSV with a header row
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{}", CSV_HEADER)?;
+        for sample in &self.samples {
+            writeln!(out, "{}", sample.to_csv_row())?;
+        }
+        Ok(())
+    }
+
+    /// Write all samples as JSON Lines (one object per line)
+    pub fn write_jsonl<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for sample in &self.samples {
+            writeln!(out, "{}", sample.to_json())?;
+        }
+        Ok(())
+    }
+
+    /// Export to a file, choosing the format from the extension
+    /// (`.jsonl` / `.json` for JSON Lines, anything else for CSV)
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut out = BufWriter::new(File::create(path)?);
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("jsonl" | "json") => self.write_jsonl(&mut out)?,
+            _ => self.write_csv(&mut out)?,
+        }
+        out.flush()
+    }
+}
+
+impl Default for StatsHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blinker() -> BitGrid {
+        let mut grid = BitGrid::new(10, 10);
+        grid.set(4, 5, true);
+        grid.set(5, 5, true);
+        grid.set(6, 5, true);
+        grid
+    }
+
+    #[test]
+    fn test_stats_from_grid() {
+        let stats = GenerationStats::from_grid(&blinker(), 0);
+
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.births, 0);
+        assert_eq!(stats.deaths, 0);
+        assert_eq!(stats.bounding_box, Some((4, 5, 6, 5)));
+        assert!((stats.density - 0.03).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_between_generations() {
+        let before = blinker();
+        let after = before.evolve(&crate::domain::ConwayRule);
+        let stats = GenerationStats::between(&before, &after, 1);
+
+        // Blinker flips: two ends die, two cells are born above and below
+        assert_eq!(stats.population, 3);
+        assert_eq!(stats.births, 2);
+        assert_eq!(stats.deaths, 2);
+        assert_eq!(stats.bounding_box, Some((5, 4, 5, 6)));
+    }
+
+    #[test]
+    fn test_history_drops_oldest() {
+        let grid = blinker();
+        let mut history = StatsHistory::new(3);
+
+        for generation in 0..5 {
+            history.record(GenerationStats::from_grid(&grid, generation));
+        }
+
+        assert_eq!(history.len(), 3);
+        let generations: Vec<u64> = history.iter().map(|s| s.generation).collect();
+        assert_eq!(generations, vec![2, 3, 4]);
+        assert_eq!(history.latest().map(|s| s.generation), Some(4));
+    }
+
+    #[test]
+    fn test_csv_export() {
+        let mut history = StatsHistory::new(10);
+        history.record(GenerationStats::from_grid(&blinker(), 7));
+        history.record(GenerationStats::from_grid(&BitGrid::new(10, 10), 8));
+
+        let mut out = Vec::new();
+        history.write_csv(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(lines[1], "7,3,0,0,4,5,6,5,0.030000");
+        assert_eq!(lines[2], "8,0,0,0,,,,,0.000000");
+    }
+
+    #[test]
+    fn test_jsonl_export() {
+        let mut history = StatsHistory::new(10);
+        history.record(GenerationStats::from_grid(&blinker(), 1));
+
+        let mut out = Vec::new();
+        history.write_jsonl(&mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+
+        assert_eq!(
+            text.trim_end(),
+            "{\"generation\":1,\"population\":3,\"births\":0,\"deaths\":0,\
+             \"bounding_box\":{\"min_x\":4,\"min_y\":5,\"max_x\":6,\"max_y\":5},\"density\":0.030000}"
+        );
+    }
+}
@@ -14,8 +14,9 @@ use std::mem;
 /// Tile size (must be multiple of 64 for chunk alignment)
 const TILE_SIZE: usize = 256;
 
-/// Number of generations to process per tile
-const GENERATIONS_PER_TILE: usize = 4;
+/// Maximum number of generations processed per tile pass.
+/// Callers wanting more generations run several passes.
+pub const GENERATIONS_PER_TILE: usize = 4;
 
 /// Halo size (must equal GENERATIONS_PER_TILE for correctness)
 const HALO_SIZE: usize = GENERATIONS_PER_TILE;
@@ -37,7 +38,7 @@ struct LocalTile {
 impl LocalTile {
     /// Create a new local tile buffer
     fn new(width: usize, height: usize) -> Self {
-        let chunk_width = (width + 63) / 64;
+        let chunk_width = width.div_ceil(64);
         Self {
             width,
             height,
@@ -104,25 +105,27 @@ impl LocalTile {
                 let current = self.get_chunk(chunk_x, y);
                 let below = if y + 1 < self.height { self.get_chunk(chunk_x, yb) } else { 0 };
 
-                // Left neighbors (check bound and fetch chunk - 1)
-                let left_chunk_idx = if chunk_x > 0 { chunk_x - 1 } else { usize::MAX };
-                let (left_above, left_current, left_below) = if left_chunk_idx != usize::MAX {
+                // As in simd_life, "left" bits are shifted into bit 63 and so
+                // come from bit 0 of the next chunk (chunk_x + 1)
+                let next_chunk_x = chunk_x + 1;
+                let (left_above, left_current, left_below) = if next_chunk_x < self.chunk_width {
                     (
-                        (self.get_chunk(left_chunk_idx, ya) >> 63) != 0 && y > 0,
-                        (self.get_chunk(left_chunk_idx, y) >> 63) != 0,
-                        (self.get_chunk(left_chunk_idx, yb) >> 63) != 0 && y + 1 < self.height,
+                        (self.get_chunk(next_chunk_x, ya) & 1) != 0 && y > 0,
+                        (self.get_chunk(next_chunk_x, y) & 1) != 0,
+                        (self.get_chunk(next_chunk_x, yb) & 1) != 0 && y + 1 < self.height,
                     )
                 } else {
                     (false, false, false)
                 };
 
-                // Right neighbors (check bound and fetch chunk + 1)
-                let right_chunk_idx = chunk_x + 1;
-                let (right_above, right_current, right_below) = if right_chunk_idx < self.chunk_width {
+                // "Right" bits are shifted into bit 0 and come from bit 63 of
+                // the previous chunk (chunk_x - 1)
+                let (right_above, right_current, right_below) = if chunk_x > 0 {
+                    let prev_chunk_x = chunk_x - 1;
                     (
-                        (self.get_chunk(right_chunk_idx, ya) & 1) != 0 && y > 0,
-                        (self.get_chunk(right_chunk_idx, y) & 1) != 0,
-                        (self.get_chunk(right_chunk_idx, yb) & 1) != 0 && y + 1 < self.height,
+                        (self.get_chunk(prev_chunk_x, ya) >> 63) != 0 && y > 0,
+                        (self.get_chunk(prev_chunk_x, y) >> 63) != 0,
+                        (self.get_chunk(prev_chunk_x, yb) >> 63) != 0 && y + 1 < self.height,
                     )
                 } else {
                     (false, false, false)
@@ -382,6 +385,13 @@ fn get_tile_u64_unaligned(tile: &LocalTile, x: usize, y: usize) -> u64 {
     low | high
 }
 
+/// Grid coordinate where a tile's halo starts, `HALO_SIZE` cells before
+/// `tile_start` with toroidal wrapping (grids may be narrower than the halo)
+#[inline]
+fn halo_start(tile_start: usize, size: usize) -> usize {
+    (tile_start + size - HALO_SIZE % size) % size
+}
+
 /// Evolve a BitGrid using temporal blocking (serial version)
 pub fn evolve_temporal_blocking(grid: &BitGrid, rule: &dyn Rule, generations: usize) -> BitGrid {
     let (width, height) = grid.dimensions();
@@ -390,8 +400,8 @@ pub fn evolve_temporal_blocking(grid: &BitGrid, rule: &dyn Rule, generations: us
     let lookup = build_rule_lookup(rule);
     let tile_stride = TILE_SIZE;
     
-    for tile_y_idx in 0..(height + tile_stride - 1) / tile_stride {
-        for tile_x_idx in 0..(width + tile_stride - 1) / tile_stride {
+    for tile_y_idx in 0..height.div_ceil(tile_stride) {
+        for tile_x_idx in 0..width.div_ceil(tile_stride) {
             let tile_x = tile_x_idx * tile_stride;
             let tile_y = tile_y_idx * tile_stride;
             
@@ -400,8 +410,8 @@ pub fn evolve_temporal_blocking(grid: &BitGrid, rule: &dyn Rule, generations: us
             let local_width = actual_width + 2 * HALO_SIZE;
             let local_height = actual_height + 2 * HALO_SIZE;
             
-            let start_x = (tile_x + width - HALO_SIZE) % width;
-            let start_y = (tile_y + height - HALO_SIZE) % height;
+            let start_x = halo_start(tile_x, width);
+            let start_y = halo_start(tile_y, height);
             
             let mut local = copy_to_local_tile(grid, start_x, start_y, local_width, local_height);
             
@@ -420,8 +430,8 @@ pub fn evolve_temporal_blocking_parallel(grid: &BitGrid, rule: &(dyn Rule + Sync
     let lookup = build_rule_lookup(rule);
     
     let tile_stride = TILE_SIZE;
-    let num_tiles_x = (width + tile_stride - 1) / tile_stride;
-    let num_tiles_y = (height + tile_stride - 1) / tile_stride;
+    let num_tiles_x = width.div_ceil(tile_stride);
+    let num_tiles_y = height.div_ceil(tile_stride);
     let total_tiles = num_tiles_x * num_tiles_y;
     
     // Process tiles in parallel
@@ -438,8 +448,8 @@ pub fn evolve_temporal_blocking_parallel(grid: &BitGrid, rule: &(dyn Rule + Sync
             let local_width = actual_width + 2 * HALO_SIZE;
             let local_height = actual_height + 2 * HALO_SIZE;
             
-            let start_x = (tile_x + width - HALO_SIZE) % width;
-            let start_y = (tile_y + height - HALO_SIZE) % height;
+            let start_x = halo_start(tile_x, width);
+            let start_y = halo_start(tile_y, height);
             
             let mut local = copy_to_local_tile(grid, start_x, start_y, local_width, local_height);
             
@@ -538,4 +548,25 @@ mod tests {
             }
         }
     }
+    
+    #[test]
+    fn test_temporal_blocking_across_chunk_boundaries() {
+        let rule = ConwayRule;
+        let mut grid = BitGrid::new(512, 256);
+        grid.randomize();
+        
+        // Cells next to local tile chunk boundaries depend on the
+        // neighbouring chunk's edge bits
+        for generations in 1..=GENERATIONS_PER_TILE {
+            let mut reference = grid.clone();
+            for _ in 0..generations {
+                reference = crate::domain::simd_life::evolve_simd(&reference, &rule);
+            }
+            
+            let serial = evolve_temporal_blocking(&grid, &rule, generations);
+            let parallel = evolve_temporal_blocking_parallel(&grid, &rule, generations);
+            assert_eq!(serial.chunks(), reference.chunks(), "{} generations", generations);
+            assert_eq!(parallel.chunks(), reference.chunks(), "{} generations", generations);
+        }
+    }
 }
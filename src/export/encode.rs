@@ -0,0 +1,102 @@
+//! PNG and animated PNG (APNG) encoding for rasterized images.
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::RgbaImage;
+
+/// Encode a single image as PNG
+pub fn write_png<W: Write>(image: &RgbaImage, out: W) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(out, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&image.pixels)?;
+    writer.finish()?;
+    Ok(())
+}
+
+/// Encode frames as an endlessly looping APNG, showing each frame for
+/// `delay_ms` milliseconds. All frames must have the same size.
+pub fn write_apng<W: Write>(frames: &[RgbaImage], delay_ms: u16, out: W) -> io::Result<()> {
+    let Some(first) = frames.first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "no frames to encode"));
+    };
+    if frames.iter().any(|f| (f.width, f.height) != (first.width, first.height)) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "frames differ in size"));
+    }
+
+    let mut encoder = png::Encoder::new(out, first.width as u32, first.height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_animated(frames.len() as u32, 0)?;
+    encoder.set_frame_delay(delay_ms, 1000)?;
+
+    let mut writer = encoder.write_header()?;
+    for frame in frames {
+        writer.write_image_data(&frame.pixels)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Save a PNG snapshot
+pub fn save_png(image: &RgbaImage, path: impl AsRef<Path>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_png(image, &mut out)?;
+    out.flush()
+}
+
+/// Save an animated PNG
+pub fn save_apng(frames: &[RgbaImage], delay_ms: u16, path: impl AsRef<Path>) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_apng(frames, delay_ms, &mut out)?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BitGrid;
+    use crate::export::{render_region, Theme};
+
+    #[test]
+    fn test_png_round_trip() {
+        let mut grid = BitGrid::new(8, 8);
+        grid.set(2, 3, true);
+        let image = render_region(&grid, (0, 0, 7, 7), 2, &Theme::default()).unwrap();
+
+        let mut bytes = Vec::new();
+        write_png(&image, &mut bytes).unwrap();
+
+        let decoder = png::Decoder::new(bytes.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut decoded).unwrap();
+        assert_eq!((info.width, info.height), (16, 16));
+        assert_eq!(&decoded[..info.buffer_size()], image.pixels.as_slice());
+    }
+
+    #[test]
+    fn test_apng_has_all_frames() {
+        let grid = BitGrid::new(4, 4);
+        let frame = render_region(&grid, (0, 0, 3, 3), 1, &Theme::default()).unwrap();
+        let frames = vec![frame.clone(), frame.clone(), frame];
+
+        let mut bytes = Vec::new();
+        write_apng(&frames, 100, &mut bytes).unwrap();
+
+        let reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+        let animation = reader.info().animation_control.expect("animated");
+        assert_eq!(animation.num_frames, 3);
+        assert_eq!(animation.num_plays, 0);
+
+        let mismatched = vec![
+            render_region(&grid, (0, 0, 3, 3), 1, &Theme::default()).unwrap(),
+            render_region(&grid, (0, 0, 3, 3), 2, &Theme::default()).unwrap(),
+        ];
+        assert!(write_apng(&mismatched, 100, Vec::new()).is_err());
+    }
+}
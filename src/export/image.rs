@@ -0,0 +1,222 @@
+//! Software rasterizer producing RGBA images of grid regions.
+//!
+//! Independent of the window and GPU, so it runs headless.
+
+use rayon::prelude::*;
+
+use crate::domain::{BitGrid, CellRect};
+
+/// Colors used when rasterizing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub alive: [u8; 4],
+    pub dead: [u8; 4],
+    /// Drawn between cells when the scale is large enough
+    pub grid_lines: Option<[u8; 4]>,
+}
+
+impl Theme {
+    /// Built-in themes
+    pub fn all() -> [Theme; 3] {
+        [
+            Theme {
+                name: "dark",
+                alive: [0, 255, 150, 255],
+                dead: [15, 15, 15, 255],
+                grid_lines: Some([40, 40, 40, 255]),
+            },
+            Theme {
+                name: "light",
+                alive: [20, 90, 200, 255],
+                dead: [250, 250, 245, 255],
+                grid_lines: Some([220, 220, 215, 255]),
+            },
+            Theme {
+                name: "print",
+                alive: [0, 0, 0, 255],
+                dead: [255, 255, 255, 255],
+                grid_lines: None,
+            },
+        ]
+    }
+
+    /// Look up a built-in theme by name (case-insensitive)
+    pub fn by_name(name: &str) -> Option<Theme> {
+        Self::all().into_iter().find(|t| t.name.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::all()[0]
+    }
+}
+
+/// Smallest scale at which grid lines are drawn
+const GRID_LINE_MIN_SCALE: usize = 4;
+
+/// Largest image `render_region` makes, in pixels (64 MB of RGBA)
+pub const MAX_IMAGE_PIXELS: usize = 4096 * 4096;
+
+/// An RGBA image, row-major
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Color at pixel (x, y)
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * self.width + x) * 4;
+        self.pixels[offset..offset + 4].try_into().expect("4 bytes")
+    }
+}
+
+/// Rasterize the inclusive cell rectangle `rect` with each cell drawn as a
+/// `scale` x `scale` block. Cells outside the grid are drawn dead. Images
+/// over `MAX_IMAGE_PIXELS` are refused (see `fit_region`).
+pub fn render_region(grid: &BitGrid, rect: CellRect, scale: usize, theme: &Theme) -> Result<RgbaImage, String> {
+    let scale = scale.max(1);
+    let (min_x, min_y, max_x, max_y) = rect;
+    let cells_wide = max_x.saturating_sub(min_x).saturating_add(1);
+    let cells_high = max_y.saturating_sub(min_y).saturating_add(1);
+    let (width, height) = cells_wide.checked_mul(scale)
+        .zip(cells_high.checked_mul(scale))
+        .filter(|&(width, height)| width.checked_mul(height).is_some_and(|pixels| pixels <= MAX_IMAGE_PIXELS))
+        .ok_or_else(|| format!(
+            "{}x{} cells at scale {} is more than {} pixels",
+            cells_wide, cells_high, scale, MAX_IMAGE_PIXELS
+        ))?;
+    let lines = theme.grid_lines.filter(|_| scale >= GRID_LINE_MIN_SCALE);
+
+    let mut pixels = vec![0u8; width * height * 4];
+    pixels
+        .par_chunks_mut(width * 4)
+        .enumerate()
+        .for_each(|(py, row)| {
+            let y = min_y + py / scale;
+            let on_row_line = py % scale == 0;
+
+            for (px, pixel) in row.chunks_exact_mut(4).enumerate() {
+                let x = min_x + px / scale;
+                let color = match lines {
+                    Some(line) if on_row_line || px % scale == 0 => line,
+                    _ if grid.get(x, y) => theme.alive,
+                    _ => theme.dead,
+                };
+                pixel.copy_from_slice(&color);
+            }
+        });
+
+    Ok(RgbaImage { width, height, pixels })
+}
+
+/// Shrink a snapshot of `rect` at `scale` pixels per cell so it has at
+/// most `max_pixels` pixels: the scale drops first, then the rectangle is
+/// cropped around its middle at one pixel per cell
+pub fn fit_region(rect: CellRect, scale: usize, max_pixels: usize) -> (CellRect, usize) {
+    let (min_x, min_y, max_x, max_y) = rect;
+    let (wide, high) = (max_x.saturating_sub(min_x).saturating_add(1), max_y.saturating_sub(min_y).saturating_add(1));
+    let cells = wide.saturating_mul(high);
+    if cells <= max_pixels {
+        // Largest scale s with s * s * cells <= max_pixels
+        let fitting = ((max_pixels / cells) as f64).sqrt() as usize;
+        return (rect, scale.max(1).min(fitting.max(1)));
+    }
+
+    let shrink = (max_pixels as f64 / (wide as f64 * high as f64)).sqrt();
+    let new_wide = ((wide as f64 * shrink) as usize).clamp(1, max_pixels);
+    let new_high = ((high as f64 * shrink) as usize).clamp(1, (max_pixels / new_wide).max(1));
+    let x0 = min_x + (wide - new_wide) / 2;
+    let y0 = min_y + (high - new_high) / 2;
+    ((x0, y0, x0 + new_wide - 1, y0 + new_high - 1), 1)
+}
+
+/// Live-cell bounding box grown by `margin` cells, clamped to the grid;
+/// the whole grid if it is empty
+pub fn content_rect(grid: &BitGrid, margin: usize) -> CellRect {
+    let (width, height) = grid.dimensions();
+    match grid.bounding_box() {
+        Some((min_x, min_y, max_x, max_y)) => (
+            min_x.saturating_sub(margin),
+            min_y.saturating_sub(margin),
+            (max_x + margin).min(width - 1),
+            (max_y + margin).min(height - 1),
+        ),
+        None => (0, 0, width - 1, height - 1),
+    }
+}
+
+/// Smallest rectangle containing both
+pub fn union_rect(a: CellRect, b: CellRect) -> CellRect {
+    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_region_lowers_scale_then_crops() {
+        // Fits as is
+        assert_eq!(fit_region((0, 0, 9, 9), 4, 10_000), ((0, 0, 9, 9), 4));
+        // 100 cells at scale 4 is 1600 pixels; scale 3 gives 900
+        assert_eq!(fit_region((0, 0, 9, 9), 4, 1000), ((0, 0, 9, 9), 3));
+        // 10 000 x 100 cells crop to the middle at one pixel per cell
+        let (rect, scale) = fit_region((0, 0, 9_999, 99), 2, 10_000);
+        let (x0, y0, x1, y1) = rect;
+        assert_eq!(scale, 1);
+        assert!((x1 - x0 + 1) * (y1 - y0 + 1) <= 10_000);
+        assert_eq!((x0 + x1) / 2, 4_999);
+        assert_eq!((y0 + y1) / 2, 49);
+        // A rectangle whose cell count overflows still fits
+        let (rect, _) = fit_region((0, 0, usize::MAX - 1, usize::MAX - 1), 8, 10_000);
+        assert!(render_region(&BitGrid::new(4, 4), rect, 1, &Theme::default()).is_ok());
+    }
+
+    #[test]
+    fn test_render_region_scales_cells() {
+        let mut grid = BitGrid::new(10, 10);
+        grid.set(3, 4, true);
+        let theme = Theme::by_name("print").unwrap();
+
+        let image = render_region(&grid, (2, 4, 4, 5), 3, &theme).unwrap();
+        assert_eq!((image.width, image.height), (9, 6));
+        // Cell (3, 4) covers pixels 3..6 x 0..3
+        assert_eq!(image.pixel(3, 0), theme.alive);
+        assert_eq!(image.pixel(5, 2), theme.alive);
+        assert_eq!(image.pixel(6, 0), theme.dead);
+        assert_eq!(image.pixel(4, 3), theme.dead);
+
+        // Oversized images are refused instead of allocated
+        assert!(render_region(&grid, (0, 0, 99_999, 99_999), 8, &theme).is_err());
+        assert!(render_region(&grid, (0, 0, usize::MAX, usize::MAX), 8, &theme).is_err());
+    }
+
+    #[test]
+    fn test_grid_lines_only_at_large_scale() {
+        let mut grid = BitGrid::new(4, 4);
+        grid.set(0, 0, true);
+        let theme = Theme::default();
+
+        let small = render_region(&grid, (0, 0, 3, 3), 2, &theme).unwrap();
+        assert_eq!(small.pixel(0, 0), theme.alive);
+
+        let large = render_region(&grid, (0, 0, 3, 3), 8, &theme).unwrap();
+        assert_eq!(large.pixel(0, 0), theme.grid_lines.unwrap());
+        assert_eq!(large.pixel(1, 1), theme.alive);
+    }
+
+    #[test]
+    fn test_content_rect_adds_margin() {
+        let mut grid = BitGrid::new(20, 20);
+        grid.set(5, 6, true);
+        grid.set(18, 7, true);
+
+        assert_eq!(content_rect(&grid, 2), (3, 4, 19, 9));
+        assert_eq!(content_rect(&BitGrid::new(8, 4), 2), (0, 0, 7, 3));
+    }
+}
@@ -0,0 +1,9 @@
+//! Image export: software rasterization of grid regions and PNG / APNG
+//! encoding. Nothing here needs a window, so it is shared by the GUI
+//! screenshot key and the headless `export` binary.
+
+mod image;
+mod encode;
+
+pub use image::{RgbaImage, Theme, render_region, content_rect, fit_region, union_rect, MAX_IMAGE_PIXELS};
+pub use encode::{write_png, write_apng, save_png, save_apng};
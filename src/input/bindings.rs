@@ -0,0 +1,287 @@
+//! Rebindable keyboard shortcuts. Each action has a list of keys (any of
+//! them triggers it); modifier variants such as Shift+E follow the key
+//! bound to the action. A key triggers at most one action, and the keys
+//! with a fixed meaning can't be bound. Key names are what the
+//! preferences file uses.
+
+use macroquad::prelude::*;
+
+/// Something a single key press does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    TogglePlay,
+    Step,
+    Clear,
+    Randomize,
+    SpeedUp,
+    SlowDown,
+    DoubleStep,
+    HalveStep,
+    MaxSpeed,
+    StatsGraph,
+    Inspector,
+    RenderMode,
+    ResetView,
+    FitView,
+    Follow,
+    Compare,
+    Breakpoint,
+    Probe,
+    Export,
+    Help,
+}
+
+impl Action {
+    pub fn all() -> [Action; 20] {
+        use Action::*;
+        [
+            TogglePlay, Step, Clear, Randomize, SpeedUp, SlowDown, DoubleStep, HalveStep, MaxSpeed,
+            StatsGraph, Inspector, RenderMode, ResetView, FitView, Follow, Compare, Breakpoint, Probe,
+            Export, Help,
+        ]
+    }
+
+    /// Name in the `[keys]` section of the preferences file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::TogglePlay => "play",
+            Action::Step => "step",
+            Action::Clear => "clear",
+            Action::Randomize => "randomize",
+            Action::SpeedUp => "speed_up",
+            Action::SlowDown => "slow_down",
+            Action::DoubleStep => "double_step",
+            Action::HalveStep => "halve_step",
+            Action::MaxSpeed => "max_speed",
+            Action::StatsGraph => "stats_graph",
+            Action::Inspector => "inspector",
+            Action::RenderMode => "render_mode",
+            Action::ResetView => "reset_view",
+            Action::FitView => "fit_view",
+            Action::Follow => "follow",
+            Action::Compare => "compare",
+            Action::Breakpoint => "breakpoint",
+            Action::Probe => "probe",
+            Action::Export => "export",
+            Action::Help => "help",
+        }
+    }
+
+    /// What the action does, for the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::TogglePlay => "Start or pause",
+            Action::Step => "Advance one generation",
+            Action::Clear => "Kill every cell",
+            Action::Randomize => "Random fill",
+            Action::SpeedUp => "More updates per second",
+            Action::SlowDown => "Fewer updates per second",
+            Action::DoubleStep => "Double the generations per update",
+            Action::HalveStep => "Halve the generations per update",
+            Action::MaxSpeed => "Run as fast as possible",
+            Action::StatsGraph => "Population graph",
+            Action::Inspector => "Cell inspector on hover",
+            Action::RenderMode => "Next render mode (Shift: next colors)",
+            Action::ResetView => "Reset the view",
+            Action::FitView => "Fit the view to the live cells",
+            Action::Follow => "Follow the whole population",
+            Action::Compare => "Compare rules side by side (Shift: diff)",
+            Action::Breakpoint => "Break on hovered cell (Shift: stable, Ctrl: clear)",
+            Action::Probe => "Probe the hovered cell (Shift: export)",
+            Action::Export => "Export population CSV (Shift: JSON Lines)",
+            Action::Help => "Show or hide this help",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<KeyCode> {
+        match self {
+            Action::TogglePlay => vec![KeyCode::Space],
+            Action::Step => vec![KeyCode::N],
+            Action::Clear => vec![KeyCode::C],
+            Action::Randomize => vec![KeyCode::R],
+            Action::SpeedUp => vec![KeyCode::Equal, KeyCode::KpAdd],
+            Action::SlowDown => vec![KeyCode::Minus, KeyCode::KpSubtract],
+            Action::DoubleStep => vec![KeyCode::RightBracket],
+            Action::HalveStep => vec![KeyCode::LeftBracket],
+            Action::MaxSpeed => vec![KeyCode::X],
+            Action::StatsGraph => vec![KeyCode::G],
+            Action::Inspector => vec![KeyCode::I],
+            Action::RenderMode => vec![KeyCode::M],
+            Action::ResetView => vec![KeyCode::H],
+            Action::FitView => vec![KeyCode::F],
+            Action::Follow => vec![KeyCode::T],
+            Action::Compare => vec![KeyCode::V],
+            Action::Breakpoint => vec![KeyCode::B],
+            Action::Probe => vec![KeyCode::P],
+            Action::Export => vec![KeyCode::E],
+            Action::Help => vec![KeyCode::F1],
+        }
+    }
+
+    /// Look up an action by its preferences name
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::all().into_iter().find(|action| action.name() == name)
+    }
+}
+
+/// Shortcuts that cannot be rebound, as (keys, description) for the help
+/// overlay
+pub const FIXED_BINDINGS: &[(&str, &str)] = &[
+    ("LMB / RMB", "Draw / erase with the selected tool"),
+    ("Ctrl+LMB", "Follow the object under the cursor"),
+    ("Wheel / MMB drag", "Zoom / pan"),
+    ("WASD / arrows", "Pan (Shift: faster)"),
+    ("1-9 / Ctrl+1-9", "Recall / save and name a view"),
+    ("Ctrl+G", "Go to a coordinate"),
+    ("Ctrl+S", "Save a PNG snapshot"),
+];
+
+/// Keys with a fixed meaning that no action can take, with what they do
+const RESERVED_KEYS: &[(KeyCode, &str)] = &[
+    (KeyCode::W, "panning"), (KeyCode::A, "panning"), (KeyCode::S, "panning"), (KeyCode::D, "panning"),
+    (KeyCode::Up, "panning"), (KeyCode::Down, "panning"), (KeyCode::Left, "panning"), (KeyCode::Right, "panning"),
+    (KeyCode::Key1, "views"), (KeyCode::Key2, "views"), (KeyCode::Key3, "views"),
+    (KeyCode::Key4, "views"), (KeyCode::Key5, "views"), (KeyCode::Key6, "views"),
+    (KeyCode::Key7, "views"), (KeyCode::Key8, "views"), (KeyCode::Key9, "views"),
+    (KeyCode::Escape, "cancelling"),
+];
+
+/// Names accepted for keys, the first one for each key being how it is
+/// shown
+const KEY_NAMES: &[(&str, KeyCode)] = &[
+    ("Space", KeyCode::Space), ("Enter", KeyCode::Enter), ("Escape", KeyCode::Escape),
+    ("Tab", KeyCode::Tab), ("Backspace", KeyCode::Backspace), ("Delete", KeyCode::Delete),
+    ("Insert", KeyCode::Insert), ("Home", KeyCode::Home), ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp), ("PageDown", KeyCode::PageDown),
+    ("Up", KeyCode::Up), ("Down", KeyCode::Down), ("Left", KeyCode::Left), ("Right", KeyCode::Right),
+    ("=", KeyCode::Equal), ("-", KeyCode::Minus), ("[", KeyCode::LeftBracket), ("]", KeyCode::RightBracket),
+    (";", KeyCode::Semicolon), ("'", KeyCode::Apostrophe), (",", KeyCode::Comma), (".", KeyCode::Period),
+    ("/", KeyCode::Slash), ("\\", KeyCode::Backslash), ("`", KeyCode::GraveAccent),
+    ("Kp+", KeyCode::KpAdd), ("Kp-", KeyCode::KpSubtract), ("Kp*", KeyCode::KpMultiply),
+    ("Kp/", KeyCode::KpDivide), ("KpEnter", KeyCode::KpEnter),
+    ("A", KeyCode::A), ("B", KeyCode::B), ("C", KeyCode::C), ("D", KeyCode::D), ("E", KeyCode::E),
+    ("F", KeyCode::F), ("G", KeyCode::G), ("H", KeyCode::H), ("I", KeyCode::I), ("J", KeyCode::J),
+    ("K", KeyCode::K), ("L", KeyCode::L), ("M", KeyCode::M), ("N", KeyCode::N), ("O", KeyCode::O),
+    ("P", KeyCode::P), ("Q", KeyCode::Q), ("R", KeyCode::R), ("S", KeyCode::S), ("T", KeyCode::T),
+    ("U", KeyCode::U), ("V", KeyCode::V), ("W", KeyCode::W), ("X", KeyCode::X), ("Y", KeyCode::Y),
+    ("Z", KeyCode::Z),
+    ("0", KeyCode::Key0), ("1", KeyCode::Key1), ("2", KeyCode::Key2), ("3", KeyCode::Key3),
+    ("4", KeyCode::Key4), ("5", KeyCode::Key5), ("6", KeyCode::Key6), ("7", KeyCode::Key7),
+    ("8", KeyCode::Key8), ("9", KeyCode::Key9),
+    ("F1", KeyCode::F1), ("F2", KeyCode::F2), ("F3", KeyCode::F3), ("F4", KeyCode::F4),
+    ("F5", KeyCode::F5), ("F6", KeyCode::F6), ("F7", KeyCode::F7), ("F8", KeyCode::F8),
+    ("F9", KeyCode::F9), ("F10", KeyCode::F10), ("F11", KeyCode::F11), ("F12", KeyCode::F12),
+    // Spelled-out aliases
+    ("Equal", KeyCode::Equal), ("Minus", KeyCode::Minus),
+    ("LeftBracket", KeyCode::LeftBracket), ("RightBracket", KeyCode::RightBracket),
+    ("KpAdd", KeyCode::KpAdd), ("KpSubtract", KeyCode::KpSubtract), ("Esc", KeyCode::Escape),
+];
+
+/// Key for a name such as "Space", "F1", "q" or "[" (case-insensitive)
+pub fn parse_key(name: &str) -> Option<KeyCode> {
+    KEY_NAMES.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|&(_, key)| key)
+}
+
+/// Display name of a key
+pub fn key_name(key: KeyCode) -> String {
+    match KEY_NAMES.iter().find(|&&(_, k)| k == key) {
+        Some((name, _)) => name.to_string(),
+        None => format!("{:?}", key),
+    }
+}
+
+/// Keys from a comma-separated list of names ("none" for no keys).
+/// Reserved keys, such as WASD for panning, are refused.
+pub fn parse_keys(text: &str) -> Result<Vec<KeyCode>, String> {
+    if text.trim().eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    text.split(',')
+        .map(str::trim)
+        .map(|name| {
+            let key = parse_key(name).ok_or_else(|| format!("unknown key '{}'", name))?;
+            match RESERVED_KEYS.iter().find(|&&(k, _)| k == key) {
+                Some((_, purpose)) => Err(format!("{} is reserved for {}", key_name(key), purpose)),
+                None => Ok(key),
+            }
+        })
+        .collect()
+}
+
+/// The keys bound to every action
+#[derive(Clone, Debug, PartialEq)]
+pub struct KeyBindings {
+    keys: Vec<(Action, Vec<KeyCode>)>,
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> &[KeyCode] {
+        self.keys.iter()
+            .find(|(a, _)| *a == action)
+            .map_or(&[], |(_, keys)| keys.as_slice())
+    }
+
+    pub fn set(&mut self, action: Action, keys: Vec<KeyCode>) {
+        match self.keys.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, slot)) => *slot = keys,
+            None => self.keys.push((action, keys)),
+        }
+    }
+
+    /// A key bound to two different actions, if any
+    pub fn clash(&self) -> Option<(KeyCode, Action, Action)> {
+        self.keys.iter().enumerate().find_map(|(i, (action, keys))| {
+            keys.iter().find_map(|key| {
+                self.keys[i + 1..].iter()
+                    .find(|(_, other)| other.contains(key))
+                    .map(|&(other, _)| (*key, *action, other))
+            })
+        })
+    }
+
+    /// Whether a key bound to `action` was pressed this frame
+    pub fn pressed(&self, action: Action) -> bool {
+        self.keys(action).iter().any(|&key| is_key_pressed(key))
+    }
+
+    /// The bound keys as shown in help, e.g. "=/Kp+" ("-" if unbound)
+    pub fn label(&self, action: Action) -> String {
+        let names: Vec<String> = self.keys(action).iter().map(|&key| key_name(key)).collect();
+        if names.is_empty() { "-".to_string() } else { names.join("/") }
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: Action::all().into_iter().map(|action| (action, action.default_keys())).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys_and_labels() {
+        assert_eq!(parse_keys("space, KpAdd , q"), Ok(vec![KeyCode::Space, KeyCode::KpAdd, KeyCode::Q]));
+        assert_eq!(parse_keys("None"), Ok(Vec::new()));
+        assert!(parse_keys("Space, Hyper").is_err());
+        assert_eq!(parse_keys("Q, w"), Err("W is reserved for panning".to_string()));
+
+        let mut keys = KeyBindings::default();
+        assert_eq!(keys.label(Action::SpeedUp), "=/Kp+");
+        keys.set(Action::TogglePlay, vec![KeyCode::P, KeyCode::F5]);
+        assert_eq!(keys.label(Action::TogglePlay), "P/F5");
+        keys.set(Action::Help, Vec::new());
+        assert_eq!(keys.label(Action::Help), "-");
+        assert!(Action::all().iter().all(|action| Action::from_name(action.name()) == Some(*action)));
+
+        // P is also the probe key
+        assert_eq!(keys.clash(), Some((KeyCode::P, Action::TogglePlay, Action::Probe)));
+        keys.set(Action::Probe, vec![KeyCode::O]);
+        assert_eq!(keys.clash(), None);
+        assert_eq!(KeyBindings::default().clash(), None);
+    }
+}
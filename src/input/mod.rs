@@ -1,103 +1,736 @@
+mod bindings;
+
+use std::sync::Arc;
+
 use macroquad::prelude::*;
 // use crate::domain::Cell;
-use crate::application::{GameState, Camera};
-use crate::ui::{grid_area_width, CELL_SIZE};
+use crate::application::{GameState, Camera, Command, Bookmarks, Comparison, Follow, FollowTarget, RuleEditor, DrawingTools, Tool, MAX_STEP_SIZE};
+use crate::domain::{all_rules, inspect_cell, BrushShape, CellInfo, Condition, LifeLikeRule, Mask, ResizeAnchor, Rule, Symmetry, MAX_BRUSH_SIZE};
+use crate::export;
+use crate::rendering::HeatmapPalette;
+pub use bindings::{parse_key, parse_keys, key_name, Action, KeyBindings, FIXED_BINDINGS};
+
+use crate::ui::{grid_area_width, grid_area_height, parse_grid_size, DrawingEvent, Panel, RuleEditorEvent, StatusLine, CELL_SIZE};
+
+/// Input state carried between frames
+#[derive(Default)]
+pub struct InputState {
+    /// Mouse position on the previous frame of a middle-button drag
+    last_pan_pos: Option<(f32, f32)>,
+    /// Text prompt over the grid (None = closed)
+    pub prompt: Option<Prompt>,
+    /// Saved camera views (Ctrl+1-9 saves, 1-9 recalls)
+    pub bookmarks: Bookmarks,
+    /// Active keyboard shortcuts (from the preferences file)
+    pub keys: KeyBindings,
+    /// Whether the key binding overlay is open
+    pub show_help: bool,
+    /// Last cell inspection with the cell and grid version it was made
+    /// for, reused while neither changes (finding the object is a fill)
+    inspected: Option<(InspectedAt, CellInfo)>,
+}
+
+/// A cell and the `GameState::grid_version` it was inspected at
+type InspectedAt = ((usize, usize), (u64, u64, usize));
+
+impl InputState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inspect `cell`, reusing the last result while the cell and the
+    /// grid are unchanged
+    fn inspect(&mut self, state: &GameState, cell: (usize, usize)) -> Option<CellInfo> {
+        let version = state.grid_version();
+        if let Some((at, info)) = self.inspected
+            && at == (cell, version)
+        {
+            return Some(info);
+        }
+        let info = inspect_cell(&state.grid, state.rule.as_ref(), cell.0, cell.1)?;
+        self.inspected = Some(((cell, version), info));
+        Some(info)
+    }
+}
+
+/// What the text prompt over the grid asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PromptKind {
+    /// A coordinate to center the view on (Ctrl+G)
+    GoTo,
+    /// A name for the view just saved in this bookmark slot (Ctrl+1-9)
+    BookmarkName(usize),
+}
+
+/// One-line text entry drawn over the grid
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub text: String,
+}
+
+impl Prompt {
+    /// Longest bookmark name
+    const MAX_NAME_LEN: usize = 24;
+
+    /// Open an empty prompt, dropping characters typed so far (e.g. the
+    /// shortcut)
+    fn open(kind: PromptKind) -> Self {
+        while get_char_pressed().is_some() {}
+        Self { kind, text: String::new() }
+    }
+
+    /// Question shown above the text
+    pub fn title(&self) -> String {
+        match self.kind {
+            PromptKind::GoTo => "Go to x,y (Enter / Esc):".to_string(),
+            PromptKind::BookmarkName(slot) => format!("Name view {} (Enter / Esc):", slot + 1),
+        }
+    }
+
+    fn accepts(&self, c: char) -> bool {
+        match self.kind {
+            PromptKind::GoTo => c.is_ascii_digit() || c == ',' || c == ' ',
+            PromptKind::BookmarkName(_) => !c.is_control() && self.text.chars().count() < Self::MAX_NAME_LEN,
+        }
+    }
+}
 
-/// Handle zoom with mouse wheel
-pub fn handle_zoom(camera: &mut Camera) {
+/// Keyboard pan speed in screen pixels per second (Shift triples it)
+const KEYBOARD_PAN_SPEED: f32 = 600.0;
+
+/// Handle zoom with mouse wheel, keeping the cell under the cursor fixed
+pub fn handle_zoom(camera: &mut Camera, mouse_pos: (f32, f32)) {
     let wheel = mouse_wheel().1;
-    if wheel > 0.0 {
-        camera.zoom_in(1.1);
-    } else if wheel < 0.0 {
-        camera.zoom_out(1.1);
+    if wheel == 0.0 {
+        return;
     }
+    
+    // Outside the grid area zoom around the center of the view
+    let anchor = if mouse_pos.0 < grid_area_width() {
+        mouse_pos
+    } else {
+        (grid_area_width() / 2.0, grid_area_height() / 2.0)
+    };
+    let factor = if wheel > 0.0 { 1.1 } else { 1.0 / 1.1 };
+    camera.zoom_at(factor, anchor);
 }
 
 /// Handle pan with middle mouse button drag
-pub fn handle_pan(camera: &mut Camera, mouse_pos: (f32, f32)) {
-    // Use static for tracking last position
-    static mut LAST_POS: Option<(f32, f32)> = None;
-    
-    unsafe {
-        if is_mouse_button_down(MouseButton::Middle) {
-            if let Some(last) = LAST_POS {
-                let dx = mouse_pos.0 - last.0;
-                let dy = mouse_pos.1 - last.1;
-                camera.pan(dx, dy);
-            }
-            LAST_POS = Some(mouse_pos);
-        } else {
-            LAST_POS = None;
+pub fn handle_pan(input: &mut InputState, camera: &mut Camera, mouse_pos: (f32, f32)) {
+    if is_mouse_button_down(MouseButton::Middle) {
+        if let Some(last) = input.last_pan_pos {
+            camera.pan(mouse_pos.0 - last.0, mouse_pos.1 - last.1);
         }
+        input.last_pan_pos = Some(mouse_pos);
+    } else {
+        input.last_pan_pos = None;
     }
 }
 
-/// Handle mouse painting on the grid (with camera support)
-pub fn handle_mouse_paint(state: &mut GameState, camera: &Camera, mouse_pos: (f32, f32)) {
-    if state.is_running || mouse_pos.0 >= grid_area_width() {
+/// Pan with WASD or the arrow keys
+pub fn handle_keyboard_pan(camera: &mut Camera, delta_time: f32) {
+    // Ctrl+key combinations are shortcuts, not panning
+    if is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl) {
         return;
     }
+    let held = |keys: [KeyCode; 2]| keys.iter().any(|k| is_key_down(*k));
+    let mut direction = (0.0, 0.0);
+    if held([KeyCode::A, KeyCode::Left]) { direction.0 += 1.0; }
+    if held([KeyCode::D, KeyCode::Right]) { direction.0 -= 1.0; }
+    if held([KeyCode::W, KeyCode::Up]) { direction.1 += 1.0; }
+    if held([KeyCode::S, KeyCode::Down]) { direction.1 -= 1.0; }
     
-    // Convert screen coordinates to grid coordinates using camera
-    let (grid_x, grid_y) = camera.screen_to_grid(mouse_pos.0, mouse_pos.1, CELL_SIZE);
+    let fast = if held([KeyCode::LeftShift, KeyCode::RightShift]) { 3.0 } else { 1.0 };
+    let step = KEYBOARD_PAN_SPEED * fast * delta_time;
+    camera.pan(direction.0 * step, direction.1 * step);
+}
+
+/// F fits the view to the live cells, Ctrl+G opens the go-to prompt,
+/// 1-9 recall camera bookmarks and Ctrl+1-9 save them, then ask for a name
+pub fn handle_navigation_keys(input: &mut InputState, state: &GameState, camera: &mut Camera, status: &mut StatusLine) {
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    let viewport = (grid_area_width(), grid_area_height());
     
-    // Check if within grid bounds
-    let (grid_width, grid_height) = state.grid.dimensions();
-    if grid_x < 0 || grid_y < 0 || grid_x >= grid_width as i32 || grid_y >= grid_height as i32 {
-        return;
+    if input.keys.pressed(Action::FitView) && !ctrl {
+        // An empty grid frames the whole grid instead
+        let (width, height) = state.grid.dimensions();
+        let rect = state.grid.bounding_box().unwrap_or((0, 0, width - 1, height - 1));
+        camera.fit_rect(rect, viewport, CELL_SIZE);
     }
     
-    let (gx, gy) = (grid_x as usize, grid_y as usize);
+    if ctrl && is_key_pressed(KeyCode::G) {
+        input.prompt = Some(Prompt::open(PromptKind::GoTo));
+    }
     
-    if is_mouse_button_down(MouseButton::Left) {
-        state.grid.set(gx, gy, true);
-    } else if is_mouse_button_down(MouseButton::Right) {
-        state.grid.set(gx, gy, false);
+    let number_keys = [
+        KeyCode::Key1, KeyCode::Key2, KeyCode::Key3,
+        KeyCode::Key4, KeyCode::Key5, KeyCode::Key6,
+        KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    ];
+    for (slot, key) in number_keys.into_iter().enumerate() {
+        if !is_key_pressed(key) {
+            continue;
+        }
+        if ctrl {
+            // Saved right away; the prompt can replace the default name
+            let name = input.bookmarks.get(slot).map_or_else(|| format!("View {}", slot + 1), |b| b.name.clone());
+            input.bookmarks.save(slot, camera.bookmark(name));
+            input.prompt = Some(Prompt::open(PromptKind::BookmarkName(slot)));
+        } else if let Some(bookmark) = input.bookmarks.get(slot) {
+            camera.jump_to(bookmark);
+            status.info(format!("View {}: {}", slot + 1, bookmark.name));
+        }
     }
 }
 
-/// Process keyboard input functionally
-pub fn process_keyboard_input(state: GameState, camera: &mut Camera) -> GameState {
-    type KeyAction = (KeyCode, fn(GameState) -> GameState);
-    
-    let actions: [KeyAction; 5] = [
-        (KeyCode::Space, GameState::toggle_running),
-        (KeyCode::C, GameState::clear),
-        (KeyCode::R, GameState::randomize),
-        (KeyCode::Up, |s| s.adjust_speed(1.0)),
-        (KeyCode::Down, |s| s.adjust_speed(-1.0)),
+/// Feed typed characters to the open prompt. Enter centers the view on
+/// "x,y" or names the bookmark, Escape cancels (an unnamed bookmark keeps
+/// its current name). Returns true while a prompt has focus so other keyboard
+/// shortcuts can be skipped.
+pub fn handle_prompt(input: &mut InputState, camera: &mut Camera, status: &mut StatusLine) -> bool {
+    let Some(prompt) = input.prompt.as_mut() else {
+        return false;
+    };
+    
+    while let Some(c) = get_char_pressed() {
+        if prompt.accepts(c) {
+            prompt.text.push(c);
+        }
+    }
+    if is_key_pressed(KeyCode::Backspace) {
+        prompt.text.pop();
+    }
+    
+    if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+        let text = prompt.text.trim();
+        match prompt.kind {
+            PromptKind::GoTo => match parse_coordinate(text) {
+                Some((x, y)) => camera.center_on(x as f32, y as f32, (grid_area_width(), grid_area_height()), CELL_SIZE),
+                None if text.is_empty() => {}
+                None => status.error(format!("Not a coordinate: '{}'", text)),
+            },
+            PromptKind::BookmarkName(slot) => {
+                if !text.is_empty() {
+                    input.bookmarks.rename(slot, text);
+                }
+                if let Some(bookmark) = input.bookmarks.get(slot) {
+                    status.info(format!("Saved view {} as {}", slot + 1, bookmark.name));
+                }
+            }
+        }
+        input.prompt = None;
+    } else if is_key_pressed(KeyCode::Escape) {
+        input.prompt = None;
+    }
+    true
+}
+
+/// Parse "x,y" or "x y" into a cell coordinate
+fn parse_coordinate(text: &str) -> Option<(usize, usize)> {
+    let mut parts = text
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty());
+    let x = parts.next()?.parse().ok()?;
+    let y = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((x, y))
+}
+
+/// Handle mouse drawing on the grid (with camera support): the left
+/// button draws live cells and the right button dead ones, with the
+/// selected tool. Works while running too; edits land between generations.
+pub fn handle_mouse_paint(state: GameState, tools: &mut DrawingTools, camera: &Camera, mouse_pos: (f32, f32)) -> GameState {
+    // Ctrl+click picks an object to follow instead
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    if ctrl {
+        tools.cancel();
+        return state;
+    }
+    
+    // Convert screen coordinates to grid coordinates using camera;
+    // strokes may leave the grid and are clipped by the tools
+    let (grid_x, grid_y) = camera.screen_to_grid(mouse_pos.0, mouse_pos.1, CELL_SIZE);
+    let cell = (grid_x as isize, grid_y as isize);
+    let grid_size = state.grid.dimensions();
+    
+    let commands = if tools.is_drawing() {
+        if is_mouse_button_down(MouseButton::Left) || is_mouse_button_down(MouseButton::Right) {
+            tools.drag(cell, grid_size)
+        } else {
+            tools.release(cell, grid_size)
+        }
+    } else if mouse_pos.0 >= grid_area_width() {
+        return state;
+    } else if is_mouse_button_pressed(MouseButton::Left) {
+        tools.press(cell, true, &state.grid)
+    } else if is_mouse_button_pressed(MouseButton::Right) {
+        tools.press(cell, false, &state.grid)
+    } else {
+        return state;
+    };
+    state.apply_all(commands)
+}
+
+/// Masks of the cells the drawing tool would change with the mouse at
+/// `mouse_pos`, for the preview overlay (none outside the grid area)
+pub fn stroke_preview(tools: &DrawingTools, state: &GameState, camera: &Camera, mouse_pos: (f32, f32)) -> Option<Vec<Mask>> {
+    if mouse_pos.0 >= grid_area_width() && !tools.is_drawing() {
+        return None;
+    }
+    let (grid_x, grid_y) = camera.screen_to_grid(mouse_pos.0, mouse_pos.1, CELL_SIZE);
+    Some(tools.preview((grid_x as isize, grid_y as isize), state.grid.dimensions()))
+}
+
+/// The cell under the mouse, for the cell inspector
+pub fn inspect_hovered(input: &mut InputState, state: &GameState, camera: &Camera, mouse_pos: (f32, f32)) -> Option<CellInfo> {
+    let cell = hovered_cell(state, camera, mouse_pos)?;
+    input.inspect(state, cell)
+}
+
+/// Apply clicks in the drawing section to `tools` and show their settings
+pub fn update_drawing_tools(panel: &mut Panel, tools: &mut DrawingTools, mouse_pos: (f32, f32)) {
+    fn next<T: PartialEq + Copy>(all: &[T], current: T) -> T {
+        let index = all.iter().position(|&item| item == current).unwrap_or(0);
+        all[(index + 1) % all.len()]
+    }
+    
+    for event in panel.drawing_tools.update(mouse_pos) {
+        match event {
+            DrawingEvent::NextTool => {
+                tools.cancel();
+                tools.tool = next(&Tool::all(), tools.tool);
+            }
+            DrawingEvent::NextBrushShape => tools.brush_shape = next(&BrushShape::all(), tools.brush_shape),
+            DrawingEvent::NextSymmetry => tools.symmetry = next(&Symmetry::all(), tools.symmetry),
+            DrawingEvent::BrushSize(size) => tools.brush_size = size.clamp(1, MAX_BRUSH_SIZE),
+        }
+    }
+    panel.drawing_tools.set_values(tools.tool.name(), tools.brush_shape.name(), tools.symmetry.name(), tools.brush_size);
+}
+
+/// Commands for the single-key bindings pressed this frame
+fn pressed_commands(keys: &KeyBindings) -> Vec<Command> {
+    let bindings: [(Action, Command); 11] = [
+        (Action::TogglePlay, Command::TogglePlay),
+        (Action::Step, Command::Step),
+        (Action::Clear, Command::Clear),
+        (Action::Randomize, Command::Randomize),
+        (Action::StatsGraph, Command::ToggleStatsGraph),
+        (Action::Inspector, Command::ToggleInspector),
+        (Action::SpeedUp, Command::AdjustSpeed(1.0)),
+        (Action::SlowDown, Command::AdjustSpeed(-1.0)),
+        (Action::DoubleStep, Command::DoubleStepSize),
+        (Action::HalveStep, Command::HalveStepSize),
+        (Action::MaxSpeed, Command::ToggleMaxSpeed),
     ];
     
-    let new_state = actions.iter().fold(state, |s, (key, action)| {
-        if is_key_pressed(*key) { action(s) } else { s }
-    });
+    // Ctrl combinations belong to other handlers (Ctrl+G, Ctrl+B, ...)
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    bindings.into_iter()
+        .filter(|(action, _)| !ctrl && keys.pressed(*action))
+        .map(|(_, command)| command)
+        .collect()
+}
+
+/// Process keyboard input functionally
+pub fn process_keyboard_input(state: GameState, camera: &mut Camera, keys: &KeyBindings, status: &mut StatusLine) -> GameState {
+    let new_state = state.apply_all(pressed_commands(keys));
     
     // Reset camera with 'H' (home)
-    if is_key_pressed(KeyCode::H) {
+    if keys.pressed(Action::ResetView) {
         camera.reset();
     }
     
+    // Export population series with 'E' (CSV) or Shift+E (JSON Lines)
+    if keys.pressed(Action::Export) {
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+        status.report(export_stats(&new_state, if shift { "jsonl" } else { "csv" }));
+    }
+    
     new_state
 }
 
-/// Process button clicks functionally
-pub fn process_button_clicks(
+/// V opens side-by-side comparison of the current grid (the right side
+/// starts on the next rule) or closes it; Shift+V toggles the diff
+/// overlay. While comparing, the usual single-key bindings drive both
+/// sides in lockstep.
+pub fn handle_comparison_keys(comparison: Option<Comparison>, state: &GameState, camera: &mut Camera, keys: &KeyBindings) -> Option<Comparison> {
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    let v_pressed = keys.pressed(Action::Compare) && !ctrl;
+    
+    let Some(comparison) = comparison else {
+        if !v_pressed || shift {
+            return None;
+        }
+        let rules = all_rules();
+        let current = rules.iter().position(|(_, rule)| rule.name() == state.rule.name()).unwrap_or(0);
+        let next = (current + 1) % rules.len();
+        let (_, rule) = rules.into_iter().nth(next).expect("rule index");
+        return Some(Comparison::new(state, Arc::from(rule), state.algorithm));
+    };
+    
+    match (v_pressed, shift) {
+        (true, false) => return None,
+        (true, true) => return Some(comparison.toggle_diff()),
+        _ => {}
+    }
+    if keys.pressed(Action::ResetView) {
+        camera.reset();
+    }
+    Some(pressed_commands(keys).into_iter().fold(comparison, Comparison::apply))
+}
+
+/// Ctrl+click locks the camera onto the object under the cursor (or
+/// stops following when nothing is there); T toggles following the
+/// whole population
+pub fn handle_follow_input(follow: Option<Follow>, state: &GameState, camera: &Camera, mouse_pos: (f32, f32), keys: &KeyBindings) -> Option<Follow> {
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    
+    if ctrl && is_mouse_button_pressed(MouseButton::Left) && mouse_pos.0 < grid_area_width() {
+        let (grid_x, grid_y) = camera.screen_to_grid(mouse_pos.0, mouse_pos.1, CELL_SIZE);
+        let pos = (grid_x as f32 + 0.5, grid_y as f32 + 0.5);
+        // Small spaceships are hard to hit exactly, so allow a few cells
+        // of slack (at least a few pixels when zoomed out)
+        let slack = (3.0 / camera.zoom).ceil().max(3.0) as usize;
+        return Follow::object(&state.grid, pos, slack, state.generation);
+    }
+    
+    if keys.pressed(Action::Follow) && !ctrl {
+        return match follow {
+            Some(f) if f.target == FollowTarget::Population => None,
+            _ => Follow::population(&state.grid, state.generation),
+        };
+    }
+    follow
+}
+
+/// Cycle render mode with 'M', or the current mode's gradient with Shift+M
+pub fn handle_render_mode_keys(state: GameState, palette: &mut HeatmapPalette, keys: &KeyBindings) -> GameState {
+    if !keys.pressed(Action::RenderMode) {
+        return state;
+    }
+    
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    match (shift, state.cell_history.as_ref()) {
+        (true, Some(history)) => {
+            palette.cycle_gradient(history.kind());
+            state
+        }
+        (true, None) => state,
+        (false, _) => state.apply(Command::CycleHistoryKind),
+    }
+}
+
+/// Grid cell under the mouse, if the cursor is over the grid
+fn hovered_cell(state: &GameState, camera: &Camera, mouse_pos: (f32, f32)) -> Option<(usize, usize)> {
+    if mouse_pos.0 >= grid_area_width() {
+        return None;
+    }
+    let (grid_x, grid_y) = camera.screen_to_grid(mouse_pos.0, mouse_pos.1, CELL_SIZE);
+    let (grid_width, grid_height) = state.grid.dimensions();
+    if grid_x < 0 || grid_y < 0 || grid_x >= grid_width as i32 || grid_y >= grid_height as i32 {
+        return None;
+    }
+    Some((grid_x as usize, grid_y as usize))
+}
+
+/// Breakpoint keys: B breaks when the hovered cell comes alive,
+/// Shift+B runs until the pattern stabilizes, Ctrl+B clears all breakpoints
+pub fn handle_breakpoint_keys(state: GameState, camera: &Camera, mouse_pos: (f32, f32), keys: &KeyBindings) -> GameState {
+    if !keys.pressed(Action::Breakpoint) {
+        return state;
+    }
+    
+    let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    
+    if ctrl {
+        return state.apply(Command::ClearBreakpoints);
+    }
+    if shift {
+        return state.apply(Command::RunUntil(Condition::Stabilized));
+    }
+    
+    match hovered_cell(&state, camera, mouse_pos) {
+        Some((x, y)) => state.apply(Command::AddBreakpoint(Condition::CellAlive { x, y })),
+        None => state,
+    }
+}
+
+/// P toggles a probe on the hovered cell, Shift+P exports the probe timeline
+pub fn handle_probe_keys(
     state: GameState,
-    buttons: &[crate::ui::Button],
-    mouse_pos: (f32, f32)
+    camera: &Camera,
+    mouse_pos: (f32, f32),
+    keys: &KeyBindings,
+    status: &mut StatusLine,
 ) -> GameState {
+    if !keys.pressed(Action::Probe) {
+        return state;
+    }
+    
+    if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) {
+        let path = format!("probes_{}.csv", unix_timestamp());
+        match state.probes.export_csv(&path) {
+            Ok(()) => status.info(format!("Exported {} probes to {}", state.probes.len(), path)),
+            Err(err) => status.error(format!("Failed to export probes to {}: {}", path, err)),
+        }
+        return state;
+    }
+    
+    let Some((x, y)) = hovered_cell(&state, camera, mouse_pos) else {
+        return state;
+    };
+    if state.probes.index_at(x, y).is_some() {
+        state.apply(Command::RemoveProbeAt { x, y })
+    } else {
+        let name = state.probes.next_name();
+        state.apply(Command::AddProbe { x, y, name })
+    }
+}
+
+/// F1 opens or closes the key binding overlay, Escape closes it
+pub fn handle_help_key(input: &mut InputState) {
+    if input.keys.pressed(Action::Help) {
+        input.show_help = !input.show_help;
+    } else if is_key_pressed(KeyCode::Escape) {
+        input.show_help = false;
+    }
+}
+
+/// Ctrl+S saves the visible part of the grid as a PNG, one cell per
+/// on-screen cell size (at least one pixel), in the color theme. Views too
+/// big for `export::MAX_IMAGE_PIXELS` are saved smaller, or cropped.
+pub fn handle_snapshot_keys(state: &GameState, camera: &Camera, theme: &export::Theme, status: &mut StatusLine) {
+    let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+    if !ctrl || !is_key_pressed(KeyCode::S) {
+        return;
+    }
+    
+    let (width, height) = state.grid.dimensions();
+    let (min_x, min_y, max_x, max_y) = camera.visible_bounds(grid_area_width(), grid_area_height(), CELL_SIZE);
+    let clamp = |v: i32, limit: usize| v.clamp(0, limit as i32 - 1) as usize;
+    let rect = (clamp(min_x, width), clamp(min_y, height), clamp(max_x, width), clamp(max_y, height));
+    let scale = (CELL_SIZE * camera.zoom).round().max(1.0) as usize;
+    let (fitted, scale) = export::fit_region(rect, scale, export::MAX_IMAGE_PIXELS);
+    let cropped = if fitted != rect { " (cropped to the middle of the view)" } else { "" };
+    
+    let path = format!("snapshot_{}.png", unix_timestamp());
+    let result = export::render_region(&state.grid, fitted, scale, theme).and_then(|image| {
+        export::save_png(&image, &path)
+            .map(|()| format!("Saved {}x{} snapshot to {}{}", image.width, image.height, path, cropped))
+            .map_err(|err| err.to_string())
+    });
+    status.report(result.map_err(|err| format!("Failed to save snapshot to {}: {}", path, err)));
+}
+
+/// Seconds since the Unix epoch, used to name exported files
+fn unix_timestamp() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Write the statistics history next to the working directory
+fn export_stats(state: &GameState, extension: &str) -> Result<String, String> {
+    let path = format!("population_{}.{}", unix_timestamp(), extension);
+    
+    match state.stats.save(&path) {
+        Ok(()) => Ok(format!("Exported {} generations to {}", state.stats.len(), path)),
+        Err(err) => Err(format!("Failed to export statistics to {}: {}", path, err)),
+    }
+}
+
+/// Commands for the panel buttons clicked this frame
+pub fn clicked_commands(buttons: &[crate::ui::Button], mouse_pos: (f32, f32)) -> Vec<Command> {
     buttons
         .iter()
         .enumerate()
-        .fold(state, |s, (idx, btn)| {
-            if !btn.is_clicked(mouse_pos) {
-                return s;
-            }
-            match idx {
-                0 => s.toggle_running(),
-                1 => s.clear(),
-                2 => s.randomize(),
-                _ => s,
-            }
+        .filter(|(_, btn)| btn.is_clicked(mouse_pos))
+        .filter_map(|(idx, _)| match idx {
+            0 => Some(Command::TogglePlay),
+            1 => Some(Command::Clear),
+            2 => Some(Command::Randomize),
+            _ => None,
         })
+        .collect()
+}
+
+/// Commands from the panel's slider and checkbox. The widgets are synced
+/// to `state` first so keyboard changes show up in the panel.
+pub fn panel_commands(panel: &mut Panel, state: &GameState, mouse_pos: (f32, f32)) -> Vec<Command> {
+    let mut commands = Vec::new();
+    let layout = panel.layout().clone();
+    
+    panel.speed.set_value(state.updates_per_second);
+    if layout.speed.is_some() && panel.speed.update(mouse_pos) {
+        commands.push(Command::AdjustSpeed(panel.speed.value() - state.updates_per_second));
+    }
+    
+    if !panel.step_size.is_focused() {
+        panel.step_size.set_text(state.step_size.to_string());
+    }
+    if layout.step_size.is_some() && panel.step_size.update(mouse_pos) {
+        match parse_step_size(panel.step_size.text()) {
+            Ok(generations) => commands.push(Command::SetStepSize(generations)),
+            Err(err) => panel.step_size.reject(err),
+        }
+    }
+    
+    panel.stats_graph.set_checked(state.show_stats_graph);
+    if layout.stats_graph.is_some() && panel.stats_graph.update(mouse_pos) {
+        commands.push(Command::ToggleStatsGraph);
+    }
+    
+    panel.inspector.set_checked(state.show_inspector);
+    if layout.inspector.is_some() && panel.inspector.update(mouse_pos) {
+        commands.push(Command::ToggleInspector);
+    }
+    
+    if layout.run_until.is_some() && panel.run_until.update(mouse_pos) {
+        match Condition::parse(panel.run_until.text()) {
+            Ok(condition) => commands.push(Command::RunUntil(condition)),
+            Err(err) => panel.run_until.reject(err),
+        }
+    }
+    
+    panel.auto_expand.set_checked(state.auto_expand);
+    if layout.auto_expand.is_some() && panel.auto_expand.update(mouse_pos) {
+        commands.push(Command::ToggleAutoExpand);
+    }
+    if layout.resize_centered.is_some() {
+        panel.resize_centered.update(mouse_pos);
+    }
+    commands
+}
+
+/// Generations per update typed in the panel, from 1 to `MAX_STEP_SIZE`
+fn parse_step_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    match text.parse::<u64>() {
+        Ok(generations) if (1..=MAX_STEP_SIZE).contains(&generations) => Ok(generations),
+        Ok(_) => Err(format!("step size must be 1 to {}", MAX_STEP_SIZE)),
+        Err(_) => Err(format!("'{}' is not a number", text)),
+    }
+}
+
+/// Anchor for resizes chosen in the panel
+pub fn resize_anchor(panel: &Panel) -> ResizeAnchor {
+    if panel.resize_centered.is_checked() {
+        ResizeAnchor::Center
+    } else {
+        ResizeAnchor::TopLeft
+    }
+}
+
+/// A size entered in the panel's WxH field this frame, as a `Resize`
+/// command. The field shows the grid's size while not being edited; an
+/// unparseable size is flagged on the field and leaves the grid unchanged.
+pub fn grid_size_command(panel: &mut Panel, state: &GameState, mouse_pos: (f32, f32)) -> Option<Command> {
+    let (width, height) = state.grid.dimensions();
+    if !panel.grid_size.is_focused() {
+        panel.grid_size.set_text(format!("{}x{}", width, height));
+    }
+    if panel.layout().grid_size.is_none() || !panel.grid_size.update(mouse_pos) {
+        return None;
+    }
+    
+    match parse_grid_size(panel.grid_size.text()) {
+        Ok(size) if size == (width, height) => None,
+        Ok((width, height)) => Some(Command::Resize { width, height, anchor: resize_anchor(panel) }),
+        Err(err) => {
+            panel.grid_size.reject(err);
+            None
+        }
+    }
+}
+
+/// The rule chosen in the rule editor this frame, as a `SetRule`
+/// command. The editor is synced to `rule` first; a rejected B/S string
+/// is flagged on the notation field and leaves the rule unchanged.
+pub fn rule_editor_command(panel: &mut Panel, editor: &mut RuleEditor, rule: &dyn Rule, mouse_pos: (f32, f32)) -> Option<Command> {
+    editor.observe(rule);
+    let current = LifeLikeRule::from_rule(rule);
+    let controls = &mut panel.rule_editor;
+    controls.set_rule(current.birth(), current.survival(), current.name());
+    controls.set_recent(editor.recent().iter().map(|r| r.name().to_string()).collect());
+    
+    let mut rng = ::rand::rng();
+    let mut next = None;
+    for event in controls.update(mouse_pos) {
+        next = match event {
+            RuleEditorEvent::Toggled { birth: true, neighbors, on } => Some(current.with_birth(neighbors, on)),
+            RuleEditorEvent::Toggled { birth: false, neighbors, on } => Some(current.with_survival(neighbors, on)),
+            RuleEditorEvent::Typed(text) => match LifeLikeRule::parse(&text) {
+                Ok(rule) => Some(rule),
+                Err(err) => {
+                    controls.reject(err);
+                    None
+                }
+            },
+            RuleEditorEvent::Randomize => Some(RuleEditor::random_rule(&mut rng)),
+            RuleEditorEvent::Mutate => Some(RuleEditor::mutate(&current, &mut rng)),
+            RuleEditorEvent::Recall(index) => editor.recent().get(index).cloned(),
+        };
+    }
+    
+    next.filter(|rule| *rule != current).map(|rule| Command::SetRule(Arc::new(rule)))
+}
+
+/// Process button clicks functionally
+pub fn process_button_clicks(
+    state: GameState,
+    buttons: &[crate::ui::Button],
+    mouse_pos: (f32, f32)
+) -> GameState {
+    state.apply_all(clicked_commands(buttons, mouse_pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_filters_by_kind() {
+        let goto = Prompt { kind: PromptKind::GoTo, text: String::new() };
+        assert!(goto.accepts('4') && goto.accepts(','));
+        assert!(!goto.accepts('a'));
+
+        let name = Prompt { kind: PromptKind::BookmarkName(2), text: "gun".to_string() };
+        assert!(name.accepts('a') && name.accepts(' '));
+        assert!(!name.accepts('\n'));
+        assert_eq!(name.title(), "Name view 3 (Enter / Esc):");
+        let full = Prompt { text: "x".repeat(Prompt::MAX_NAME_LEN), ..name };
+        assert!(!full.accepts('a'));
+    }
+
+    #[test]
+    fn test_parse_step_size() {
+        assert_eq!(parse_step_size("12"), Ok(12));
+        assert_eq!(parse_step_size(" 1048576 "), Ok(MAX_STEP_SIZE));
+        assert!(parse_step_size("0").is_err());
+        assert!(parse_step_size("1048577").is_err());
+        assert!(parse_step_size("").is_err());
+    }
+
+    #[test]
+    fn test_inspection_reused_until_grid_changes() {
+        let mut input = InputState::new();
+        let mut state = GameState::new(20, 20).apply(Command::Paint { x: 5, y: 5, alive: true });
+        assert!(input.inspect(&state, (5, 5)).unwrap().alive);
+
+        // Same cell and grid version: the cached answer comes back
+        state.grid.set(5, 5, false);
+        assert!(input.inspect(&state, (5, 5)).unwrap().alive);
+
+        let state = state.apply(Command::Paint { x: 6, y: 5, alive: true });
+        let info = input.inspect(&state, (5, 5)).unwrap();
+        assert!(!info.alive && info.neighbors == 1);
+        assert_eq!(input.inspect(&state, (20, 0)), None);
+    }
 }
@@ -9,8 +9,17 @@ pub mod ui;
 pub mod rendering;
 pub mod input;
 
+// Image export (headless)
+pub mod export;
+
+// Preferences file (key bindings, colors, startup settings)
+pub mod preferences;
+
+// Terminal frontend
+pub mod tui;
+
 // Re-exports for convenience
 pub use domain::{Cell, Grid, Pattern, presets, Algorithm};
-pub use application::{GameState, Camera};
+pub use application::{GameState, Camera, Command};
 pub use ui::Button;
 
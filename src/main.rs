@@ -1,8 +1,14 @@
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
 use macroquad::prelude::*;
 use game_of_life::{
-    GameState, Camera, presets, Algorithm,
-    domain::all_rules,
-    ui::{self, Dropdown, GRID_SIZES, ALGORITHMS},
+    GameState, Camera, Command, Pattern, presets, Algorithm,
+    application::{Comparison, DrawingTools, Follow, RuleEditor, Side},
+    domain::{all_rules, load_pattern_dir, HistoryKind, ProbeSet, ResizeAnchor, Rule},
+    preferences::{probes_path, Preferences, PreferencesWatcher, DEFAULT_PREFERENCES_PATH},
+    ui::{self, Dropdown, StatusLine, GRID_SIZES, ALGORITHMS},
     rendering, input,
 };
 
@@ -16,15 +22,148 @@ fn window_conf() -> Conf {
     }
 }
 
+/// Command line options
+struct Args {
+    /// `--size WxH`, overriding the preferred grid size
+    size: Option<(usize, usize)>,
+    /// `--prefs PATH`
+    prefs: PathBuf,
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let usage = || "Usage: game_of_life [--size WxH] [--prefs PATH]".to_string();
+    let mut parsed = Args { size: None, prefs: PathBuf::from(DEFAULT_PREFERENCES_PATH) };
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        let value = args.next().ok_or_else(usage)?;
+        match flag.as_str() {
+            "--size" => parsed.size = Some(ui::parse_grid_size(value).map_err(|err| format!("--size: {}", err))?),
+            "--prefs" => parsed.prefs = PathBuf::from(value),
+            _ => return Err(usage()),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Preferences from `path`; defaults if there is no such file or it is
+/// invalid (reported in the status line)
+fn load_preferences(path: &Path, status: &mut StatusLine) -> Preferences {
+    match Preferences::load(path) {
+        Ok(prefs) => prefs,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Preferences::default(),
+        Err(err) => {
+            status.error(format!("Ignoring {}: {}", path.display(), err));
+            Preferences::default()
+        }
+    }
+}
+
+/// Probes saved by the last session at `path`, if any (a file that fails
+/// to load is reported in the status line)
+fn load_probes(path: &Path, status: &mut StatusLine) -> Option<ProbeSet> {
+    match ProbeSet::load(path) {
+        Ok(probes) => Some(probes),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            status.error(format!("Ignoring {}: {}", path.display(), err));
+            None
+        }
+    }
+}
+
+/// The built-in patterns followed by those in `dir`. Files that fail to
+/// load are reported in the status line.
+fn pattern_library(dir: Option<&Path>, status: &mut StatusLine) -> Vec<Pattern> {
+    let mut patterns = presets::all_patterns();
+    if let Some(dir) = dir {
+        match load_pattern_dir(dir) {
+            Ok((loaded, errors)) => {
+                if let Some(first) = errors.first() {
+                    status.error(format!("Skipped {} pattern file(s): {}", errors.len(), first));
+                }
+                patterns.extend(loaded);
+            }
+            Err(err) => status.error(format!("Cannot read pattern directory {}: {}", dir.display(), err)),
+        }
+    }
+    patterns
+}
+
+fn build_pattern_dropdown(patterns: &[Pattern]) -> Dropdown {
+    let items = patterns.iter().map(|p| p.name.clone()).collect();
+    Dropdown::new(ui::panel_x(), 170.0, ui::PANEL_WIDTH, "Pattern", items)
+}
+
+/// Colors and key bindings take effect as soon as preferences load
+fn apply_preferences(prefs: &Preferences, palette: &mut rendering::HeatmapPalette, input_state: &mut input::InputState) {
+    palette.theme = prefs.theme;
+    palette.background = prefs.background;
+    input_state.keys = prefs.keys.clone();
+}
+
+fn algorithm_index(algorithm: Algorithm) -> usize {
+    Algorithm::all().iter().position(|&a| a == algorithm).unwrap_or(0)
+}
+
+/// Position of `rule` in the rule dropdown (None for rules not listed)
+fn rule_index(rule: &dyn Rule) -> Option<usize> {
+    all_rules().iter().position(|(name, _)| *name == rule.name())
+}
+
+/// Keep the view and the followed target on the same cells after a
+/// resize moved the grid contents by `offset`
+fn track_resize(camera: &mut Camera, follow: &mut Option<Follow>, offset: (isize, isize), grid_size: (usize, usize)) {
+    camera.shift_cells(offset, ui::CELL_SIZE);
+    if let Some(target) = follow.as_mut() {
+        target.shift(offset, grid_size);
+    }
+}
+
 #[macroquad::main(window_conf)]
 async fn main() {
-    // Initialize with medium grid
-    let mut state = GameState::new(100, 100);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(2);
+    });
+    let mut panel = ui::Panel::new();
+    let mut prefs = load_preferences(&args.prefs, &mut panel.status_line);
+    let mut prefs_watcher = PreferencesWatcher::new(&args.prefs);
+    let (width, height) = args.size.unwrap_or(prefs.grid_size);
+    let mut state = GameState::new(width, height).with_background_worker();
+    let speed_delta = prefs.speed - state.updates_per_second;
+    state = state.apply_all([
+        Command::SetRule(prefs.rule()),
+        Command::SetAlgorithm(prefs.algorithm),
+        Command::AdjustSpeed(speed_delta),
+    ]);
+    let probes_file = probes_path(&args.prefs);
+    if let Some(probes) = load_probes(&probes_file, &mut panel.status_line) {
+        state = state.apply(Command::SetProbes(probes));
+    }
+    // Closing the window ends the loop below, which saves the probes
+    prevent_quit();
     let mut camera = Camera::new();
+    let mut palette = rendering::HeatmapPalette::default();
+    let mut grid_renderer = rendering::GridRenderer::new();
+    let mut preview_renderers = Vec::new();
+    let mut input_state = input::InputState::new();
+    apply_preferences(&prefs, &mut palette, &mut input_state);
+    let mut minimap = rendering::Minimap::new();
+    // Side-by-side comparison (None = normal single view)
+    let mut comparison: Option<Comparison> = None;
+    let mut comparison_renderer = rendering::ComparisonRenderer::new();
+    // Object or population the camera is locked onto
+    let mut follow: Option<Follow> = None;
+    let mut rule_editor = RuleEditor::new();
+    let mut drawing = DrawingTools::new();
     
     // Create dropdowns - simple vertical stack at top
     let px = ui::panel_x();
-    let grid_size_items: Vec<String> = GRID_SIZES.iter().map(|(_, name)| name.to_string()).collect();
+    let grid_size_items: Vec<String> = GRID_SIZES.iter()
+        .map(|(_, name)| name.to_string())
+        .chain([ui::CUSTOM_GRID_SIZE.to_string()])
+        .collect();
     let mut grid_size_dropdown = Dropdown::new(
         px, 
         20.0,
@@ -32,7 +171,7 @@ async fn main() {
         "Grid Size",
         grid_size_items
     );
-    grid_size_dropdown.set_selected(1); // Start with 100x100
+    grid_size_dropdown.set_selected(ui::grid_size_index((width, height)));
     
     // Rules dropdown
     let rules = all_rules();
@@ -54,39 +193,88 @@ async fn main() {
         "Algorithm",
         algorithm_items
     );
-    algorithm_dropdown.set_selected(5); // SIMD+Par scales best for huge grids
+    algorithm_dropdown.set_selected(algorithm_index(state.algorithm));
+    if let Some(index) = rule_index(state.rule.as_ref()) {
+        rule_dropdown.set_selected(index);
+    }
     
     // Pattern dropdown - moved down
-    let patterns = presets::all_patterns();
-    let pattern_items: Vec<String> = patterns.iter()
-        .map(|p| p.name.to_string())
-        .collect();
-    let mut pattern_dropdown = Dropdown::new(
-        px,
-        170.0,
-        ui::PANEL_WIDTH,
-        "Pattern",
-        pattern_items
-    );
+    let mut patterns = pattern_library(prefs.pattern_dir.as_deref(), &mut panel.status_line);
+    let mut pattern_dropdown = build_pattern_dropdown(&patterns);
     
     loop {
+        if is_quit_requested() {
+            // An empty set is still saved over one left by an earlier session
+            let save = !state.probes.is_empty() || probes_file.exists();
+            if save && let Err(err) = state.probes.save(&probes_file) {
+                eprintln!("Failed to save probes to {}: {}", probes_file.display(), err);
+            }
+            break;
+        }
         let mouse_pos = mouse_position();
         
-        // Update UI positions for responsiveness
-        let px = ui::panel_x();
-        grid_size_dropdown.set_position(px, 20.0);
-        rule_dropdown.set_position(px, 70.0);
-        algorithm_dropdown.set_position(px, 120.0);
-        pattern_dropdown.set_position(px, 170.0);
+        // Apply edits to the preferences file as they are saved
+        if let Some(result) = prefs_watcher.poll(get_frame_time()) {
+            match result {
+                Ok(newer) => {
+                    let size_before = state.grid.dimensions();
+                    let commands = prefs.changes(&newer, &state);
+                    state = state.apply_all(commands);
+                    if state.grid.dimensions() != size_before {
+                        let offset = ResizeAnchor::Center.offset(size_before, state.grid.dimensions());
+                        track_resize(&mut camera, &mut follow, offset, state.grid.dimensions());
+                        panel.grid_size.blur();
+                    }
+                    // Show the reloaded settings in the panel
+                    grid_size_dropdown.set_selected(ui::grid_size_index(state.grid.dimensions()));
+                    algorithm_dropdown.set_selected(algorithm_index(state.algorithm));
+                    if newer.rule != prefs.rule {
+                        if let Some(index) = rule_index(state.rule.as_ref()) {
+                            rule_dropdown.set_selected(index);
+                        }
+                        panel.rule_editor.stop_typing();
+                    }
+                    panel.status_line.info(format!("Reloaded preferences from {}", prefs_watcher.path().display()));
+                    if newer.pattern_dir != prefs.pattern_dir {
+                        state = state.apply(Command::CancelPlacement);
+                        patterns = pattern_library(newer.pattern_dir.as_deref(), &mut panel.status_line);
+                        pattern_dropdown = build_pattern_dropdown(&patterns);
+                    }
+                    apply_preferences(&newer, &mut palette, &mut input_state);
+                    prefs = newer;
+                }
+                Err(err) => panel.status_line.error(format!("Ignoring {}: {}", prefs_watcher.path().display(), err)),
+            }
+        }
         
-        // Recreate buttons with current panel position
-        let buttons = ui::create_buttons();
+        // Lay out the panel for this frame and move the widgets into place
+        let layout = panel.arrange().clone();
+        let dropdowns_visible = layout.dropdowns.is_some();
+        let dropdowns = [&mut grid_size_dropdown, &mut rule_dropdown, &mut algorithm_dropdown, &mut pattern_dropdown];
+        let dropdown_open = dropdowns.iter().any(|dropdown| dropdown.is_open());
+        match layout.dropdowns {
+            Some(rows) => {
+                for (dropdown, row) in dropdowns.into_iter().zip(rows) {
+                    dropdown.set_position(row.x, row.y + ui::DROPDOWN_LABEL_HEIGHT);
+                }
+            }
+            None => dropdowns.into_iter().for_each(|dropdown| dropdown.close()),
+        }
+        let buttons = ui::create_buttons(&layout);
+        let panel_scrolled = panel.handle_input(mouse_pos, get_frame_time(), dropdown_open);
         
         // Update dropdowns (handle clicks) - only one can be open at a time
-        if grid_size_dropdown.update(mouse_pos) {
-            let size = GRID_SIZES[grid_size_dropdown.selected()].0;
-            state.resize_grid(size, size);
-            camera.reset();
+        let mut resize = None;
+        let mut enter_grid_size = false;
+        if dropdowns_visible && grid_size_dropdown.update(mouse_pos) {
+            match GRID_SIZES.get(grid_size_dropdown.selected()) {
+                Some(&((width, height), _)) => {
+                    resize = Some(Command::Resize { width, height, anchor: input::resize_anchor(&panel) });
+                }
+                // "Custom…" moves to the size field (once this frame's click
+                // or Enter has been handled)
+                None => enter_grid_size = true,
+            }
         }
         // Close other dropdowns when grid_size opens
         if grid_size_dropdown.is_open() {
@@ -95,10 +283,15 @@ async fn main() {
             pattern_dropdown.close();
         }
         
-        if rule_dropdown.update(mouse_pos) {
+        if dropdowns_visible && rule_dropdown.update(mouse_pos) {
             let rules = all_rules();
             let (_, rule) = rules.into_iter().nth(rule_dropdown.selected()).unwrap();
-            state.set_rule(rule);
+            let command = Command::SetRule(Arc::from(rule));
+            // While comparing, the pickers configure the right side
+            match comparison.take() {
+                Some(cmp) => comparison = Some(cmp.apply_to(Side::Right, command)),
+                None => state = state.apply(command),
+            }
         }
         // Close other dropdowns when rule opens
         if rule_dropdown.is_open() {
@@ -108,10 +301,14 @@ async fn main() {
         }
         
         // Handle algorithm selection - NEW
-        if algorithm_dropdown.update(mouse_pos) {
+        if dropdowns_visible && algorithm_dropdown.update(mouse_pos) {
             let algorithms = Algorithm::all();
             let selected_algo = algorithms[algorithm_dropdown.selected()];
-            state.set_algorithm(selected_algo);
+            let command = Command::SetAlgorithm(selected_algo);
+            match comparison.take() {
+                Some(cmp) => comparison = Some(cmp.apply_to(Side::Right, command)),
+                None => state = state.apply(command),
+            }
         }
         // Close other dropdowns when algorithm opens
         if algorithm_dropdown.is_open() {
@@ -121,9 +318,8 @@ async fn main() {
         }
         
         // When pattern selected, enter placement mode
-        if pattern_dropdown.update(mouse_pos) {
-            state.pending_pattern_index = Some(pattern_dropdown.selected());
-            state.is_running = false;
+        if dropdowns_visible && pattern_dropdown.update(mouse_pos) {
+            state = state.apply(Command::BeginPlacement(pattern_dropdown.selected()));
         }
         // Close other dropdowns when pattern opens
         if pattern_dropdown.is_open() {
@@ -137,43 +333,153 @@ async fn main() {
             let pattern = &patterns[pattern_idx];
             
             // Right-click or Escape to cancel placement
-            if is_mouse_button_pressed(MouseButton::Right) || is_key_pressed(KeyCode::Escape) {
-                state.pending_pattern_index = None;
+            if is_mouse_button_pressed(MouseButton::Right) || (is_key_pressed(KeyCode::Escape) && !dropdown_open) {
+                state = state.apply(Command::CancelPlacement);
             }
             // Left-click on grid to place pattern
-            else if is_mouse_button_pressed(MouseButton::Left) && mouse_pos.0 < ui::grid_area_width() {
+            else if is_mouse_button_pressed(MouseButton::Left)
+                && mouse_pos.0 < ui::grid_area_width()
+                && !minimap.contains(mouse_pos)
+            {
                 let (grid_x, grid_y) = camera.screen_to_grid(mouse_pos.0, mouse_pos.1, ui::CELL_SIZE);
                 
                 // Center pattern on click position
                 let x = (grid_x as isize - pattern.width as isize / 2).max(0) as usize;
                 let y = (grid_y as isize - pattern.height as isize / 2).max(0) as usize;
                 
-                pattern.place_on(&mut state.grid, x, y);
-                state.pending_pattern_index = None;
+                state = state.apply(Command::PlacePattern { pattern: pattern.clone(), x, y });
             }
         }
         
         // Process input (skip paint if in placement mode)
-        state = input::process_button_clicks(state, &buttons, mouse_pos);
-        input::handle_zoom(&mut camera);
-        input::handle_pan(&mut camera, mouse_pos);
-        if state.pending_pattern_index.is_none() {
-            input::handle_mouse_paint(&mut state, &camera, mouse_pos);
+        let panel_state = comparison.as_ref().map_or(&state, |cmp| &cmp.left);
+        let mut commands = input::clicked_commands(&buttons, mouse_pos);
+        commands.extend(input::panel_commands(&mut panel, panel_state, mouse_pos));
+        // The rule editor, like the rule dropdown, edits the right side while comparing
+        let edited_rule = comparison.as_ref().map_or(&state.rule, |cmp| &cmp.right.rule).clone();
+        input::update_drawing_tools(&mut panel, &mut drawing, mouse_pos);
+        let rule_command = input::rule_editor_command(&mut panel, &mut rule_editor, edited_rule.as_ref(), mouse_pos);
+        if let Some(command) = resize.or(input::grid_size_command(&mut panel, &state, mouse_pos)) {
+            let size_before = state.grid.dimensions();
+            let anchor = input::resize_anchor(&panel);
+            state = state.apply(command);
+            track_resize(&mut camera, &mut follow, anchor.offset(size_before, state.grid.dimensions()), state.grid.dimensions());
+        }
+        if enter_grid_size {
+            panel.grid_size.focus();
+        }
+        grid_size_dropdown.set_selected(ui::grid_size_index(state.grid.dimensions()));
+        match comparison.take() {
+            Some(cmp) => {
+                let cmp = commands.into_iter().fold(cmp, Comparison::apply);
+                comparison = Some(match rule_command {
+                    Some(command) => cmp.apply_to(Side::Right, command),
+                    None => cmp,
+                });
+            }
+            None => state = state.apply_all(commands.into_iter().chain(rule_command)),
+        }
+        let typing = dropdown_open || panel.is_typing();
+        let comparing = comparison.is_some();
+        // Both comparison halves share the camera, each from its own origin
+        let camera_pos = if comparing { rendering::comparison_local_pos(mouse_pos) } else { mouse_pos };
+        // An open dropdown scrolls with the wheel instead
+        if !panel_scrolled && !dropdown_open {
+            input::handle_zoom(&mut camera, camera_pos);
+        }
+        // Zooming keeps following; any other camera move hands control back
+        let camera_before = (camera.offset_x, camera.offset_y, camera.zoom);
+        input::handle_pan(&mut input_state, &mut camera, mouse_pos);
+        let over_minimap = !comparing && minimap.handle_input(&mut camera, mouse_pos);
+        // A stroke already under way keeps going over the minimap or panel
+        let may_start_stroke = !over_minimap && !panel.is_dragging();
+        if !comparing && state.pending_pattern_index.is_none() && (drawing.is_drawing() || may_start_stroke) {
+            state = input::handle_mouse_paint(state, &mut drawing, &camera, mouse_pos);
+        }
+        // Open dropdowns, text fields and the go-to prompt swallow typing
+        if !typing && !input::handle_prompt(&mut input_state, &mut camera, &mut panel.status_line) {
+            input::handle_keyboard_pan(&mut camera, get_frame_time());
+            input::handle_help_key(&mut input_state);
+            comparison = input::handle_comparison_keys(comparison, &state, &mut camera, &input_state.keys);
+            match &comparison {
+                Some(cmp) => input::handle_navigation_keys(&mut input_state, &cmp.left, &mut camera, &mut panel.status_line),
+                None => {
+                    input::handle_navigation_keys(&mut input_state, &state, &mut camera, &mut panel.status_line);
+                    let keys = &input_state.keys;
+                    let status = &mut panel.status_line;
+                    state = input::process_keyboard_input(state, &mut camera, keys, status);
+                    state = input::handle_render_mode_keys(state, &mut palette, keys);
+                    state = input::handle_breakpoint_keys(state, &camera, mouse_pos, keys);
+                    state = input::handle_probe_keys(state, &camera, mouse_pos, keys, status);
+                    input::handle_snapshot_keys(&state, &camera, &palette.theme, status);
+                }
+            }
+        }
+        if (camera.offset_x, camera.offset_y, camera.zoom) != camera_before {
+            follow = None;
+        }
+        if !comparing && !typing && state.pending_pattern_index.is_none() && input_state.prompt.is_none() {
+            follow = input::handle_follow_input(follow, &state, &camera, mouse_pos, &input_state.keys);
         }
-        state = input::process_keyboard_input(state, &mut camera);
         
-        // Update game state
-        state = state.tick(get_frame_time());
+        // Update game state; the single view is frozen while comparing
+        let size_before = state.grid.dimensions();
+        match comparison.take() {
+            Some(cmp) => comparison = Some(cmp.tick(get_frame_time())),
+            None => state = state.tick(get_frame_time()),
+        }
+        // Auto-expand grows the grid around its contents
+        if state.grid.dimensions() != size_before {
+            let offset = ResizeAnchor::Center.offset(size_before, state.grid.dimensions());
+            track_resize(&mut camera, &mut follow, offset, state.grid.dimensions());
+        }
+        if let Some(target) = follow.as_mut() {
+            if target.update(&state.grid, state.generation) {
+                let viewport = (ui::grid_area_width(), ui::grid_area_height());
+                target.steer(&mut camera, viewport, ui::CELL_SIZE, get_frame_time());
+            } else {
+                follow = None;
+            }
+        }
         
         // Render (with timing)
         let render_start = std::time::Instant::now();
-        clear_background(BLACK);
-        rendering::draw_grid(&state.grid, &camera);
+        let [r, g, b, a] = palette.background;
+        clear_background(Color::from_rgba(r, g, b, a));
+        if let Some(cmp) = &comparison {
+            comparison_renderer.draw(cmp, &palette, &camera);
+        } else {
+            rendering::draw_grid(&mut grid_renderer, &state.grid, state.cell_history.as_ref(), &palette, &camera);
+            minimap.update(&state.grid, palette.theme.alive);
+            minimap.draw(&camera);
+            rendering::draw_breakpoints(&state, &camera);
+            if state.cell_history.as_ref().is_some_and(|h| h.kind() == HistoryKind::Changes) {
+                rendering::draw_changes_legend(&palette);
+            }
+            rendering::draw_probes(&state.probes, &camera);
+            if let Some(target) = &follow {
+                rendering::draw_follow_marker(target, &camera);
+            }
+            rendering::draw_probe_timeline(&state.probes, state.generation);
+            if state.show_stats_graph {
+                rendering::draw_stats_graph(&state.stats);
+            }
+        }
         
         // Draw pattern ghost preview if in placement mode
-        if let Some(idx) = state.pending_pattern_index {
-            if mouse_pos.0 < ui::grid_area_width() {
-                rendering::draw_pattern_preview(&patterns[idx], &camera, mouse_pos);
+        if let Some(idx) = state.pending_pattern_index
+            && mouse_pos.0 < ui::grid_area_width()
+        {
+            rendering::draw_pattern_preview(&patterns[idx], &camera, mouse_pos);
+        } else if comparison.is_none() {
+            if let Some(masks) = input::stroke_preview(&drawing, &state, &camera, mouse_pos) {
+                rendering::draw_stroke_preview(&mut preview_renderers, &masks, &camera);
+            }
+            if state.show_inspector
+                && !drawing.is_drawing()
+                && let Some(info) = input::inspect_hovered(&mut input_state, &state, &camera, mouse_pos)
+            {
+                rendering::draw_cell_inspector(&info, &camera, mouse_pos);
             }
         }
         
@@ -183,7 +489,15 @@ async fn main() {
             algorithm_dropdown.clone(),
             pattern_dropdown.clone()
         ];
-        rendering::draw_controls(&state, &camera, &buttons, dropdowns_slice, mouse_pos);
+        let panel_state = comparison.as_ref().map_or(&state, |cmp| &cmp.left);
+        let dropdowns_slice = if dropdowns_visible { dropdowns_slice } else { &[] };
+        rendering::draw_controls(panel_state, &camera, &panel, &buttons, dropdowns_slice, &input_state.keys, mouse_pos);
+        if let Some(prompt) = &input_state.prompt {
+            rendering::draw_prompt(&prompt.title(), &prompt.text);
+        }
+        if input_state.show_help {
+            rendering::draw_help_overlay(&input_state.keys);
+        }
         state.last_render_time_ms = render_start.elapsed().as_secs_f32() * 1000.0;
         
         next_frame().await;
@@ -0,0 +1,339 @@
+//! Preferences file: key bindings, colors and startup settings as
+//! `name = value` lines, with key bindings under a `[keys]` header. Lines
+//! starting with `#` are comments. The file is watched while the program
+//! runs and edits are applied live.
+//!
+//! ```text
+//! grid_size = 200x150
+//! rule = HighLife
+//! algorithm = BitSIMD+Par
+//! speed = 20
+//! pattern_dir = patterns
+//! theme = light
+//! alive = #c83232
+//!
+//! [keys]
+//! play = Space, K
+//! speed_up = =, Kp+
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use crate::application::{Command, GameState};
+use crate::domain::{all_rules, default_rule, Algorithm, LifeLikeRule, ResizeAnchor, Rule};
+use crate::export::Theme;
+use crate::input::{key_name, parse_keys, Action, KeyBindings};
+use crate::ui::parse_grid_size;
+
+/// Read from the working directory unless `--prefs PATH` is given
+pub const DEFAULT_PREFERENCES_PATH: &str = "life.prefs";
+
+/// Probes (with their recorded samples) are kept between sessions in a
+/// file next to the preferences, e.g. `life.probes` for `life.prefs`
+pub fn probes_path(preferences_path: &Path) -> PathBuf {
+    preferences_path.with_extension("probes")
+}
+
+/// Seconds between checks of the file's modification time
+const POLL_INTERVAL: f32 = 0.5;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Preferences {
+    pub keys: KeyBindings,
+    /// Live and dead cell and grid line colors
+    pub theme: Theme,
+    /// Window background around the grid
+    pub background: [u8; 4],
+    /// Grid size at startup (`--size` overrides it)
+    pub grid_size: (usize, usize),
+    /// A rule from the rule list by name, or B/S notation
+    pub rule: String,
+    pub algorithm: Algorithm,
+    /// Updates per second
+    pub speed: f32,
+    /// Directory of `.rle` files added to the pattern list
+    pub pattern_dir: Option<PathBuf>,
+}
+
+impl Preferences {
+    /// Read preferences; settings left out keep their defaults
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut prefs = Self::default();
+        // Applied after the theme, wherever they appear
+        let mut colors = Vec::new();
+        // Line of each key binding, to point at the later of two that clash
+        let mut binding_lines = Vec::new();
+        let mut in_keys = false;
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |what: String| format!("line {}: {}", number + 1, what);
+
+            if line.starts_with('[') {
+                in_keys = match line {
+                    "[keys]" => true,
+                    "[general]" => false,
+                    _ => return Err(error(format!("unknown section {}", line))),
+                };
+                continue;
+            }
+            let (name, value) = line.split_once('=').ok_or_else(|| error("expected 'name = value'".to_string()))?;
+            let (name, value) = (name.trim(), value.trim());
+
+            if in_keys {
+                let action = Action::from_name(name).ok_or_else(|| error(format!("unknown action '{}'", name)))?;
+                prefs.keys.set(action, parse_keys(value).map_err(error)?);
+                binding_lines.push((action, number + 1));
+                continue;
+            }
+            match name {
+                "grid_size" => prefs.grid_size = parse_grid_size(value).map_err(error)?,
+                "rule" => {
+                    resolve_rule(value).map_err(error)?;
+                    prefs.rule = value.to_string();
+                }
+                "algorithm" => {
+                    prefs.algorithm = Algorithm::all().into_iter()
+                        .find(|a| a.name().eq_ignore_ascii_case(value))
+                        .ok_or_else(|| error(format!("unknown algorithm '{}'", value)))?;
+                }
+                "speed" => {
+                    prefs.speed = value.parse().ok()
+                        .filter(|speed| (1.0..=60.0).contains(speed))
+                        .ok_or_else(|| error("speed must be between 1 and 60".to_string()))?;
+                }
+                "pattern_dir" => prefs.pattern_dir = (!value.is_empty()).then(|| PathBuf::from(value)),
+                "theme" => prefs.theme = Theme::by_name(value).ok_or_else(|| error(format!("unknown theme '{}'", value)))?,
+                "alive" | "dead" | "grid_lines" | "background" => {
+                    let color = match value {
+                        "none" if name == "grid_lines" => None,
+                        _ => Some(parse_color(value).map_err(error)?),
+                    };
+                    colors.push((name, color));
+                }
+                _ => return Err(error(format!("unknown setting '{}'", name))),
+            }
+        }
+
+        if let Some((key, first, second)) = prefs.keys.clash() {
+            let line = binding_lines.iter()
+                .filter(|(action, _)| *action == first || *action == second)
+                .map(|&(_, line)| line)
+                .max()
+                .unwrap_or(0);
+            return Err(format!(
+                "line {}: {} is bound to both {} and {}",
+                line, key_name(key), first.name(), second.name()
+            ));
+        }
+
+        for (name, color) in colors {
+            match name {
+                "alive" => prefs.theme.alive = color.unwrap_or(prefs.theme.alive),
+                "dead" => prefs.theme.dead = color.unwrap_or(prefs.theme.dead),
+                "grid_lines" => prefs.theme.grid_lines = color,
+                _ => prefs.background = color.unwrap_or(prefs.background),
+            }
+        }
+        Ok(prefs)
+    }
+
+    /// Load preferences from a file
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|message| io::Error::new(io::ErrorKind::InvalidData, message))
+    }
+
+    /// The preferred rule
+    pub fn rule(&self) -> Arc<dyn Rule> {
+        resolve_rule(&self.rule).unwrap_or_else(|_| Arc::from(default_rule()))
+    }
+
+    /// Commands that bring `state` in line with the settings that differ
+    /// in `newer`. Key bindings, colors and the pattern directory are not
+    /// part of the game state and are left to the caller.
+    pub fn changes(&self, newer: &Preferences, state: &GameState) -> Vec<Command> {
+        let mut commands = Vec::new();
+        if newer.grid_size != self.grid_size {
+            let (width, height) = newer.grid_size;
+            commands.push(Command::Resize { width, height, anchor: ResizeAnchor::Center });
+        }
+        if newer.rule != self.rule {
+            commands.push(Command::SetRule(newer.rule()));
+        }
+        if newer.algorithm != self.algorithm {
+            commands.push(Command::SetAlgorithm(newer.algorithm));
+        }
+        if newer.speed != self.speed {
+            commands.push(Command::AdjustSpeed(newer.speed - state.updates_per_second));
+        }
+        commands
+    }
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            keys: KeyBindings::default(),
+            theme: Theme::default(),
+            background: [0, 0, 0, 255],
+            grid_size: (100, 100),
+            rule: "Conway".to_string(),
+            algorithm: Algorithm::default(),
+            speed: 10.0,
+            pattern_dir: None,
+        }
+    }
+}
+
+/// A rule from the rule list (by name, case-insensitive) or in B/S notation
+fn resolve_rule(text: &str) -> Result<Arc<dyn Rule>, String> {
+    match all_rules().into_iter().find(|(name, _)| name.eq_ignore_ascii_case(text)) {
+        Some((_, rule)) => Ok(Arc::from(rule)),
+        None => LifeLikeRule::parse(text).map(|rule| Arc::new(rule) as Arc<dyn Rule>),
+    }
+}
+
+/// Color from "#rrggbb" or "#rrggbbaa"
+fn parse_color(text: &str) -> Result<[u8; 4], String> {
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok());
+    match hex.len() {
+        6 | 8 => {
+            let alpha = if hex.len() == 8 { channel(6) } else { Some(255) };
+            match (channel(0), channel(2), channel(4), alpha) {
+                (Some(r), Some(g), Some(b), Some(a)) => Ok([r, g, b, a]),
+                _ => Err(format!("bad color '{}'", text)),
+            }
+        }
+        _ => Err(format!("color '{}' should look like #rrggbb", text)),
+    }
+}
+
+/// Reloads the preferences file when it changes on disk
+pub struct PreferencesWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: f32,
+}
+
+impl PreferencesWatcher {
+    /// Watch `path`, treating its current contents as already loaded
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let modified = modified_time(&path);
+        Self { path, modified, timer: 0.0 }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The reloaded preferences if the file changed since the last load.
+    /// Checked every `POLL_INTERVAL` seconds; a deleted file keeps the
+    /// current preferences.
+    pub fn poll(&mut self, delta_time: f32) -> Option<io::Result<Preferences>> {
+        self.timer += delta_time;
+        if self.timer < POLL_INTERVAL {
+            return None;
+        }
+        self.timer = 0.0;
+
+        let modified = modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(Preferences::load(&self.path))
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use macroquad::prelude::KeyCode;
+
+    #[test]
+    fn test_parse_preferences() {
+        let text = "\
+# comment
+alive = #c83232
+theme = light
+grid_size = 200x150
+rule = b36/s23
+algorithm = bitsimd+par
+speed = 20
+grid_lines = none
+
+[keys]
+play = Space, K
+help = none
+";
+        let prefs = Preferences::parse(text).unwrap();
+        // Colors override the theme even when they come first
+        assert_eq!(prefs.theme.alive, [200, 50, 50, 255]);
+        assert_eq!(prefs.theme.dead, Theme::by_name("light").unwrap().dead);
+        assert_eq!(prefs.theme.grid_lines, None);
+        assert_eq!(prefs.grid_size, (200, 150));
+        assert_eq!(prefs.rule().name(), "B36/S23");
+        assert_eq!(prefs.algorithm, Algorithm::SimdParallel);
+        assert_eq!(prefs.speed, 20.0);
+        assert_eq!(prefs.keys.keys(Action::TogglePlay), &[KeyCode::Space, KeyCode::K]);
+        assert!(prefs.keys.keys(Action::Help).is_empty());
+        assert_eq!(prefs.keys.keys(Action::Step), &[KeyCode::N]);
+
+        assert_eq!(Preferences::parse(""), Ok(Preferences::default()));
+        assert_eq!(Preferences::parse("speed = 0").unwrap_err(), "line 1: speed must be between 1 and 60");
+        assert!(Preferences::parse("\n[keys]\nplay = Hyper").unwrap_err().starts_with("line 3:"));
+        assert!(Preferences::parse("colour = #000000").is_err());
+        assert!(Preferences::parse("alive = #12345").is_err());
+
+        // P is the probe key unless the probe moves elsewhere
+        assert_eq!(
+            Preferences::parse("[keys]\nplay = P, Space").unwrap_err(),
+            "line 2: P is bound to both play and probe"
+        );
+        assert!(Preferences::parse("[keys]\nplay = P\nprobe = O").is_ok());
+        assert_eq!(Preferences::parse("[keys]\nstep = W").unwrap_err(), "line 2: W is reserved for panning");
+    }
+
+    #[test]
+    fn test_changes_become_commands() {
+        let state = GameState::new(100, 100);
+        let old = Preferences::default();
+        assert!(old.changes(&old.clone(), &state).is_empty());
+
+        let newer = Preferences::parse("grid_size = 120x100\nrule = HighLife\nspeed = 30\ntheme = print").unwrap();
+        let commands = old.changes(&newer, &state);
+        let state = state.apply_all(commands);
+        assert_eq!(state.grid.dimensions(), (120, 100));
+        assert_eq!(state.rule.name(), "HighLife");
+        assert_eq!(state.updates_per_second, 30.0);
+    }
+
+    #[test]
+    fn test_watcher_loads_new_file() {
+        let path = std::env::temp_dir().join(format!("life_prefs_test_{}.prefs", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut watcher = PreferencesWatcher::new(&path);
+        assert!(watcher.poll(1.0).is_none());
+
+        fs::write(&path, "speed = 42\n").unwrap();
+        assert!(watcher.poll(0.1).is_none(), "polls are spaced out");
+        let prefs = watcher.poll(1.0).expect("file appeared").unwrap();
+        assert_eq!(prefs.speed, 42.0);
+        assert!(watcher.poll(1.0).is_none());
+        fs::remove_file(&path).unwrap();
+    }
+}
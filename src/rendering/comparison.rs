@@ -0,0 +1,97 @@
+//! Split-screen view of a `Comparison`: both grids side by side through
+//! one camera, with cells that differ optionally highlighted on both.
+
+use macroquad::prelude::*;
+
+use crate::application::{Camera, Comparison};
+use crate::ui::{grid_area_width, grid_area_height};
+use super::{draw_cell_mask, draw_grid_in, draw_text_label, format_number, GridRenderer, HeatmapPalette, Viewport};
+
+/// Color of cells that differ between the two sides
+pub const DIFF_RGBA: [u8; 4] = [255, 60, 200, 220];
+
+/// Gap between the halves
+const DIVIDER_WIDTH: f32 = 4.0;
+
+/// Left and right halves of the grid area
+pub fn comparison_viewports() -> [Viewport; 2] {
+    let width = (grid_area_width() - DIVIDER_WIDTH) / 2.0;
+    let height = grid_area_height();
+    [
+        Viewport { x: 0.0, y: 0.0, width, height },
+        Viewport { x: width + DIVIDER_WIDTH, y: 0.0, width, height },
+    ]
+}
+
+/// `pos` relative to the half it is over, so zooming anchors at the same
+/// cell on either side
+pub fn comparison_local_pos(pos: (f32, f32)) -> (f32, f32) {
+    let [_, right] = comparison_viewports();
+    if pos.0 >= right.x {
+        (pos.0 - right.x, pos.1)
+    } else {
+        pos
+    }
+}
+
+/// Textures for both grids and both diff overlays, reused across frames
+pub struct ComparisonRenderer {
+    grids: [GridRenderer; 2],
+    diffs: [GridRenderer; 2],
+}
+
+impl ComparisonRenderer {
+    pub fn new() -> Self {
+        Self {
+            grids: [GridRenderer::new(), GridRenderer::new()],
+            diffs: [GridRenderer::new(), GridRenderer::new()],
+        }
+    }
+
+    pub fn draw(&mut self, comparison: &Comparison, palette: &HeatmapPalette, camera: &Camera) {
+        let viewports = comparison_viewports();
+        let sides = [&comparison.left, &comparison.right];
+        let diff = comparison.show_diff.then(|| comparison.diff());
+
+        for (i, (state, viewport)) in sides.iter().zip(viewports).enumerate() {
+            draw_grid_in(&mut self.grids[i], &state.grid, state.cell_history.as_ref(), palette, camera, viewport);
+            if let Some(diff) = &diff {
+                draw_cell_mask(&mut self.diffs[i], diff, DIFF_RGBA, camera, viewport);
+            }
+
+            let label = format!("{} / {}", state.rule.name(), state.algorithm.name());
+            draw_rectangle(viewport.x, 0.0, viewport.width, 24.0, Color::from_rgba(0, 0, 0, 180));
+            draw_text_label(&label, viewport.x + 8.0, 17.0, 18.0, WHITE);
+        }
+
+        draw_rectangle(viewports[0].width, 0.0, DIVIDER_WIDTH, grid_area_height(), Color::from_rgba(90, 90, 90, 255));
+        draw_divergence_banner(comparison, diff.as_ref().map(|d| d.count_alive()));
+    }
+}
+
+impl Default for ComparisonRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Generation and divergence status along the bottom of the grid area
+fn draw_divergence_banner(comparison: &Comparison, differing_cells: Option<usize>) {
+    let generation = comparison.left.generation;
+    let (text, color) = match comparison.divergence {
+        None => (format!("Gen {}: identical so far", generation), Color::from_rgba(120, 220, 120, 255)),
+        Some(first) => {
+            let mut text = format!("Gen {}: diverged at gen {}", generation, first);
+            if let Some(cells) = differing_cells {
+                text.push_str(&format!(", {} cells differ", format_number(cells)));
+            }
+            (text, Color::from_rgba(255, 120, 200, 255))
+        }
+    };
+
+    let y = grid_area_height() - 28.0;
+    let width = measure_text(&text, None, 18, 1.0).width + 16.0;
+    let x = (grid_area_width() - width) / 2.0;
+    draw_rectangle(x, y, width, 24.0, Color::from_rgba(0, 0, 0, 200));
+    draw_text_label(&text, x + 8.0, y + 17.0, 18.0, color);
+}
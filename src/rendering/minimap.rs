@@ -0,0 +1,251 @@
+//! Minimap overview of the whole grid.
+//!
+//! The minimap is a density-binned image of the full `BitGrid`, drawn in
+//! the top-right corner of the grid area with the camera viewport as a
+//! rectangle. It is refreshed a few rows at a time within a fixed time
+//! budget, so large grids never cost more than about a millisecond per
+//! frame; a full refresh simply spans several frames.
+
+use std::time::{Duration, Instant};
+
+use macroquad::prelude::*;
+
+use crate::application::Camera;
+use crate::domain::BitGrid;
+use crate::ui::{grid_area_width, grid_area_height, CELL_SIZE};
+use super::raster::{density_row, CellRegion};
+
+/// Longest side of the minimap in screen pixels
+const MINIMAP_SIZE: f32 = 160.0;
+
+/// Margin from the corner of the grid area
+const MINIMAP_MARGIN: f32 = 10.0;
+
+/// Time spent refreshing minimap rows per frame
+const REFRESH_BUDGET: Duration = Duration::from_micros(800);
+
+/// Downsampled overview of the grid that can be clicked to move the camera
+pub struct Minimap {
+    /// Grid dimensions the image was laid out for
+    grid_size: (usize, usize),
+    /// Cells per minimap pixel along each axis
+    bin: usize,
+    /// Image size in pixels
+    image_size: (usize, usize),
+    pixels: Vec<u8>,
+    /// Next image row to refresh
+    next_row: usize,
+    texture: Option<Texture2D>,
+    /// A drag that started on the minimap keeps steering the camera
+    dragging: bool,
+}
+
+impl Minimap {
+    pub fn new() -> Self {
+        Self {
+            grid_size: (0, 0),
+            bin: 1,
+            image_size: (0, 0),
+            pixels: Vec::new(),
+            next_row: 0,
+            texture: None,
+            dragging: false,
+        }
+    }
+
+    /// Screen rectangle (x, y, width, height) of the minimap
+    pub fn rect(&self) -> (f32, f32, f32, f32) {
+        let (width, height) = self.grid_size;
+        let scale = MINIMAP_SIZE / width.max(height).max(1) as f32;
+        let (w, h) = (width as f32 * scale, height as f32 * scale);
+        (grid_area_width() - w - MINIMAP_MARGIN, MINIMAP_MARGIN, w, h)
+    }
+
+    /// Whether `pos` is over the minimap
+    pub fn contains(&self, pos: (f32, f32)) -> bool {
+        let (x, y, w, h) = self.rect();
+        pos.0 >= x && pos.0 < x + w && pos.1 >= y && pos.1 < y + h
+    }
+
+    /// Refresh as many rows as fit in the frame budget, drawing live
+    /// cells in `color`
+    pub fn update(&mut self, grid: &BitGrid, color: [u8; 4]) {
+        if grid.dimensions() != self.grid_size {
+            self.relayout(grid.dimensions());
+        }
+        let (image_width, image_height) = self.image_size;
+        if image_height == 0 {
+            return;
+        }
+
+        let start = Instant::now();
+        self.refresh_rows(grid, color, || start.elapsed() >= REFRESH_BUDGET);
+
+        match &self.texture {
+            Some(texture) => texture.update_from_bytes(image_width as u32, image_height as u32, &self.pixels),
+            None => {
+                let texture = Texture2D::from_rgba8(image_width as u16, image_height as u16, &self.pixels);
+                texture.set_filter(FilterMode::Nearest);
+                self.texture = Some(texture);
+            }
+        }
+    }
+
+    /// Re-bin image rows from where the last refresh stopped, wrapping to
+    /// the top, until `out_of_time` says to stop or every row was done once
+    fn refresh_rows(&mut self, grid: &BitGrid, color: [u8; 4], mut out_of_time: impl FnMut() -> bool) {
+        let (image_width, image_height) = self.image_size;
+        let region = CellRegion { x: 0, y: 0, width: self.grid_size.0, height: self.grid_size.1 };
+        let row_bytes = image_width * 4;
+
+        for _ in 0..image_height {
+            let row = self.next_row;
+            let out = &mut self.pixels[row * row_bytes..(row + 1) * row_bytes];
+            density_row(grid, region, self.bin, row, color, out);
+            self.next_row = (row + 1) % image_height;
+
+            if out_of_time() {
+                break;
+            }
+        }
+    }
+
+    /// Pick bin size and image size for a grid of `size`
+    fn relayout(&mut self, size: (usize, usize)) {
+        let (bin, image_size) = minimap_layout(size);
+        self.grid_size = size;
+        self.bin = bin;
+        self.image_size = image_size;
+        self.pixels = vec![0; image_size.0 * image_size.1 * 4];
+        self.next_row = 0;
+        self.texture = None;
+    }
+
+    /// Click or drag on the minimap to center the camera there.
+    /// Returns true while the mouse is being used by the minimap.
+    pub fn handle_input(&mut self, camera: &mut Camera, mouse_pos: (f32, f32)) -> bool {
+        if is_mouse_button_pressed(MouseButton::Left) && self.contains(mouse_pos) {
+            self.dragging = true;
+        }
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+        if !self.dragging {
+            return self.contains(mouse_pos);
+        }
+
+        let (cell_x, cell_y) = minimap_to_grid(self.rect(), self.grid_size, mouse_pos);
+        camera.center_on(cell_x, cell_y, (grid_area_width(), grid_area_height()), CELL_SIZE);
+        true
+    }
+
+    /// Draw the overview and the camera viewport rectangle
+    pub fn draw(&self, camera: &Camera) {
+        let Some(texture) = &self.texture else {
+            return;
+        };
+        let (x, y, w, h) = self.rect();
+        let (grid_width, grid_height) = self.grid_size;
+
+        draw_rectangle(x, y, w, h, Color::from_rgba(0, 0, 0, 200));
+        // The last bin may reach past the grid edge; scale by whole bins
+        // so cells line up (overshoot is under one minimap pixel)
+        let cell_scale = w / grid_width as f32;
+        draw_texture_ex(
+            texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(
+                    (self.image_size.0 * self.bin) as f32 * cell_scale,
+                    (self.image_size.1 * self.bin) as f32 * cell_scale,
+                )),
+                ..Default::default()
+            },
+        );
+        draw_rectangle_lines(x, y, w, h, 1.0, Color::from_rgba(80, 80, 80, 255));
+
+        // Viewport, clipped to the minimap
+        let (min_x, min_y, max_x, max_y) =
+            camera.visible_bounds(grid_area_width(), grid_area_height(), CELL_SIZE);
+        let to_x = |gx: i32| x + (gx.clamp(0, grid_width as i32) as f32 / grid_width as f32) * w;
+        let to_y = |gy: i32| y + (gy.clamp(0, grid_height as i32) as f32 / grid_height as f32) * h;
+        let (vx0, vy0, vx1, vy1) = (to_x(min_x), to_y(min_y), to_x(max_x + 1), to_y(max_y + 1));
+        draw_rectangle_lines(
+            vx0, vy0,
+            (vx1 - vx0).max(2.0), (vy1 - vy0).max(2.0),
+            1.5,
+            Color::from_rgba(255, 255, 255, 220),
+        );
+    }
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Cells per minimap pixel and the image size for a grid of `size`, so
+/// the longest side fits in `MINIMAP_SIZE` pixels
+fn minimap_layout(size: (usize, usize)) -> (usize, (usize, usize)) {
+    let longest = size.0.max(size.1).max(1);
+    let bin = longest.div_ceil(MINIMAP_SIZE as usize).max(1);
+    (bin, (size.0.div_ceil(bin), size.1.div_ceil(bin)))
+}
+
+/// Grid position under `pos` on a minimap drawn at `rect` (x, y, width,
+/// height); positions off the minimap are clamped to the grid edge
+fn minimap_to_grid(rect: (f32, f32, f32, f32), grid_size: (usize, usize), pos: (f32, f32)) -> (f32, f32) {
+    let (x, y, w, h) = rect;
+    let fx = ((pos.0 - x) / w).clamp(0.0, 1.0);
+    let fy = ((pos.1 - y) / h).clamp(0.0, 1.0);
+    (fx * grid_size.0 as f32, fy * grid_size.1 as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_fits_longest_side() {
+        assert_eq!(minimap_layout((100, 50)), (1, (100, 50)));
+        assert_eq!(minimap_layout((160, 160)), (1, (160, 160)));
+        // 161 cells need two per pixel; the short side rounds up
+        assert_eq!(minimap_layout((161, 41)), (2, (81, 21)));
+        assert_eq!(minimap_layout((10_000, 2_500)), (63, (159, 40)));
+        assert_eq!(minimap_layout((0, 0)), (1, (0, 0)));
+    }
+
+    #[test]
+    fn test_refresh_resumes_and_wraps_around() {
+        let mut grid = BitGrid::new(4, 4);
+        grid.set(0, 3, true);
+        let color = [255, 255, 255, 255];
+        let mut minimap = Minimap::new();
+        minimap.relayout(grid.dimensions());
+
+        // Budget for three rows, then one more wraps back to the top
+        let mut rows = 0;
+        minimap.refresh_rows(&grid, color, || { rows += 1; rows == 3 });
+        assert_eq!(minimap.next_row, 3);
+        assert_eq!(&minimap.pixels[3 * 16..3 * 16 + 4], &[0, 0, 0, 0], "row 3 not refreshed yet");
+        minimap.refresh_rows(&grid, color, || true);
+        assert_eq!(minimap.next_row, 0);
+        assert_eq!(&minimap.pixels[3 * 16..3 * 16 + 4], &color);
+
+        // With time to spare a refresh covers each row once
+        minimap.refresh_rows(&grid, color, || false);
+        assert_eq!(minimap.next_row, 0);
+    }
+
+    #[test]
+    fn test_click_maps_to_grid_position() {
+        let rect = (500.0, 10.0, 160.0, 80.0);
+        assert_eq!(minimap_to_grid(rect, (1000, 500), (500.0, 10.0)), (0.0, 0.0));
+        assert_eq!(minimap_to_grid(rect, (1000, 500), (580.0, 30.0)), (500.0, 125.0));
+        // Dragging past the edge stays on the grid
+        assert_eq!(minimap_to_grid(rect, (1000, 500), (700.0, -5.0)), (1000.0, 0.0));
+    }
+}
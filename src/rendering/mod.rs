@@ -1,10 +1,27 @@
+mod palette;
+mod raster;
+mod minimap;
+mod comparison;
+
 use macroquad::prelude::*;
-use crate::domain::{BitGrid, Pattern};
-use crate::application::{GameState, Camera};
-use crate::ui::{Button, Dropdown, panel_x, grid_area_width, grid_area_height, CELL_SIZE, PANEL_WIDTH};
+use crate::domain::{BitGrid, CellHistory, CellInfo, CellRect, Condition, Mask, Pattern, ProbeSet, StatsHistory};
+use crate::application::{GameState, Camera, Follow, FollowTarget};
+use crate::input::{key_name, Action, KeyBindings, FIXED_BINDINGS};
+use crate::ui::{Button, Dropdown, Panel, Rect, panel_x, grid_area_width, grid_area_height, CELL_SIZE, PANEL_WIDTH};
+
+pub use palette::{ChangeColors, ColorGradient, HeatmapPalette};
+pub use raster::{CellRegion, GridRenderer};
+pub use minimap::Minimap;
+pub use comparison::{ComparisonRenderer, comparison_viewports, comparison_local_pos, DIFF_RGBA};
+
+/// Outline and waveform color for probes
+const PROBE_COLOR: Color = Color::new(1.0, 0.8, 0.3, 1.0);
+
+/// Tint of the cells the drawing tool would change
+const STROKE_PREVIEW_RGBA: [u8; 4] = [120, 200, 255, 90];
 
 /// Format large numbers with K/M/B suffixes
-fn format_number(n: usize) -> String {
+pub fn format_number(n: usize) -> String {
     if n >= 1_000_000_000 {
         format!("{:.1}B", n as f64 / 1_000_000_000.0)
     } else if n >= 1_000_000 {
@@ -16,68 +33,143 @@ fn format_number(n: usize) -> String {
     }
 }
 
-/// Draw the cellular automaton grid with camera support
-pub fn draw_grid(grid: &BitGrid, camera: &Camera) {
+/// Screen rectangle a grid is drawn into. The camera's screen space
+/// starts at its top-left corner.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Viewport {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Viewport {
+    /// The whole grid area left of the panel
+    pub fn grid_area() -> Self {
+        Self { x: 0.0, y: 0.0, width: grid_area_width(), height: grid_area_height() }
+    }
+
+    pub fn contains(&self, pos: (f32, f32)) -> bool {
+        pos.0 >= self.x && pos.0 < self.x + self.width && pos.1 >= self.y && pos.1 < self.y + self.height
+    }
+
+    /// Restrict drawing to this rectangle until `unclip` is called
+    fn clip(&self) {
+        let gl = unsafe { get_internal_gl() };
+        gl.quad_gl.scissor(Some((self.x as i32, self.y as i32, self.width as i32, self.height as i32)));
+    }
+
+    fn unclip() {
+        let gl = unsafe { get_internal_gl() };
+        gl.quad_gl.scissor(None);
+    }
+}
+
+/// Cells visible through `viewport`, clamped to the grid, with the bin
+/// size used at this zoom and the region's top-left corner on screen
+fn visible_region(grid: &BitGrid, camera: &Camera, viewport: Viewport) -> (CellRegion, usize, (f32, f32)) {
     let cell_size = CELL_SIZE * camera.zoom;
     let (grid_width, grid_height) = grid.dimensions();
-    let area_width = grid_area_width();
-    let area_height = grid_area_height();
     
     // Get visible bounds for culling
-    let (min_x, min_y, max_x, max_y) = camera.visible_bounds(
-        area_width,
-        area_height,
-        CELL_SIZE
-    );
+    let (min_x, min_y, max_x, max_y) = camera.visible_bounds(viewport.width, viewport.height, CELL_SIZE);
     
-    // Clamp to grid bounds
-    let start_x = min_x.max(0) as usize;
-    let start_y = min_y.max(0) as usize;
-    let end_x = (max_x + 1).min(grid_width as i32) as usize;
-    let end_y = (max_y + 1).min(grid_height as i32) as usize;
+    // Below one pixel per cell, bin cells into pixels (level of detail)
+    let cells_per_pixel = if cell_size < 1.0 { (1.0 / cell_size).ceil() as usize } else { 1 };
     
-    // Colors
-    let alive_color = Color::from_rgba(0, 255, 150, 255); // Bright green
-    let grid_line_color = Color::from_rgba(40, 40, 40, 255); // Dark gray
-    let dead_cell_color = Color::from_rgba(15, 15, 15, 255); // Very dark gray for dead cells
+    // Clamp to grid bounds; align bins to the grid so they don't shimmer while panning
+    let align = |v: i32, limit: usize| (v.clamp(0, limit as i32) as usize) / cells_per_pixel * cells_per_pixel;
+    let start_x = align(min_x, grid_width);
+    let start_y = align(min_y, grid_height);
+    let end_x = (max_x + 1).clamp(0, grid_width as i32) as usize;
+    let end_y = (max_y + 1).clamp(0, grid_height as i32) as usize;
+    let region = CellRegion {
+        x: start_x,
+        y: start_y,
+        width: end_x.saturating_sub(start_x),
+        height: end_y.saturating_sub(start_y),
+    };
     
-    // Draw grid lines when zoomed in enough (before cells so cells draw on top)
-    let draw_grid_lines = camera.zoom > 0.5 && cell_size >= 4.0;
+    let (screen_x, screen_y) = camera.grid_to_screen(start_x, start_y, CELL_SIZE);
+    (region, cells_per_pixel, (viewport.x + screen_x, viewport.y + screen_y))
+}
+
+/// Draw the cellular automaton grid with camera support.
+/// The visible region is rasterized into one texture; when a history
+/// layer is given, cells are colored through `palette`. Zoomed out past
+/// one pixel per cell, pixels show the live density of the cells they
+/// cover instead.
+pub fn draw_grid(
+    renderer: &mut GridRenderer,
+    grid: &BitGrid,
+    history: Option<&CellHistory>,
+    palette: &HeatmapPalette,
+    camera: &Camera,
+) {
+    draw_grid_in(renderer, grid, history, palette, camera, Viewport::grid_area());
+}
+
+/// `draw_grid` into `viewport` instead of the whole grid area
+pub fn draw_grid_in(
+    renderer: &mut GridRenderer,
+    grid: &BitGrid,
+    history: Option<&CellHistory>,
+    palette: &HeatmapPalette,
+    camera: &Camera,
+    viewport: Viewport,
+) {
+    let cell_size = CELL_SIZE * camera.zoom;
+    let (region, cells_per_pixel, (screen_x, screen_y)) = visible_region(grid, camera, viewport);
     
-    // Render all visible cells
-    for y in start_y..end_y {
-        for x in start_x..end_x {
-            let (screen_x, screen_y) = camera.grid_to_screen(x, y, CELL_SIZE);
-            
-            // Skip if outside viewport
-            if screen_x + cell_size < 0.0 || screen_x > area_width ||
-               screen_y + cell_size < 0.0 || screen_y > area_height {
-                continue;
-            }
-            
-            if grid.get(x, y) {
-                // Alive cell
-                draw_rectangle(screen_x, screen_y, cell_size, cell_size, alive_color);
-            } else if draw_grid_lines {
-                // Dead cell - show faint background so grid is visible
-                draw_rectangle(screen_x, screen_y, cell_size, cell_size, dead_cell_color);
-            }
-            
-            // Draw grid lines if zoomed in enough
-            if draw_grid_lines {
-                draw_rectangle_lines(
-                    screen_x,
-                    screen_y,
-                    cell_size,
-                    cell_size,
-                    1.0,
-                    grid_line_color
-                );
-            }
+    viewport.clip();
+    if cells_per_pixel > 1 {
+        let bin_size = cells_per_pixel as f32 * cell_size;
+        renderer.draw_density_region(grid, region, cells_per_pixel, palette.theme.alive, screen_x, screen_y, bin_size);
+    } else {
+        // Grid lines and a dead-cell background only when zoomed in enough
+        let zoomed_in = camera.zoom > 0.5 && cell_size >= 4.0;
+        let dead = if zoomed_in { palette.theme.dead } else { raster::TRANSPARENT_RGBA };
+        
+        renderer.draw_region(grid, history, palette, region, dead, screen_x, screen_y, cell_size);
+        
+        if zoomed_in && let Some(color) = palette.theme.grid_lines {
+            draw_grid_lines_overlay(region, screen_x, screen_y, cell_size, color);
         }
     }
+    Viewport::unclip();
 }
 
+/// Draw the live cells of `mask` in `color` over whatever is already in
+/// `viewport` (density-binned when zoomed out)
+pub fn draw_cell_mask(renderer: &mut GridRenderer, mask: &BitGrid, color: [u8; 4], camera: &Camera, viewport: Viewport) {
+    let cell_size = CELL_SIZE * camera.zoom;
+    let (region, cells_per_pixel, (screen_x, screen_y)) = visible_region(mask, camera, viewport);
+    
+    viewport.clip();
+    if cells_per_pixel > 1 {
+        let bin_size = cells_per_pixel as f32 * cell_size;
+        renderer.draw_density_region(mask, region, cells_per_pixel, color, screen_x, screen_y, bin_size);
+    } else {
+        renderer.draw_mask_region(mask, region, color, screen_x, screen_y, cell_size);
+    }
+    Viewport::unclip();
+}
+
+/// One line per visible row and column boundary
+fn draw_grid_lines_overlay(region: CellRegion, screen_x: f32, screen_y: f32, cell_size: f32, color: [u8; 4]) {
+    let grid_line_color = Color::from_rgba(color[0], color[1], color[2], color[3]);
+    let right = screen_x + region.width as f32 * cell_size;
+    let bottom = screen_y + region.height as f32 * cell_size;
+    
+    for column in 0..=region.width {
+        let x = screen_x + column as f32 * cell_size;
+        draw_line(x, screen_y, x, bottom, 1.0, grid_line_color);
+    }
+    for row in 0..=region.height {
+        let y = screen_y + row as f32 * cell_size;
+        draw_line(screen_x, y, right, y, 1.0, grid_line_color);
+    }
+}
 
 /// Draw a semi-transparent preview of a pattern at the cursor position
 pub fn draw_pattern_preview(pattern: &Pattern, camera: &Camera, mouse_pos: (f32, f32)) {
@@ -126,6 +218,76 @@ pub fn draw_pattern_preview(pattern: &Pattern, camera: &Camera, mouse_pos: (f32,
     }
 }
 
+/// Shade the cells the drawing tool would change (the brush under the
+/// cursor, or the line or rectangle being dragged out), given as masks.
+/// Each mask gets its own renderer, since textures are only drawn at the
+/// end of the frame.
+pub fn draw_stroke_preview(renderers: &mut Vec<GridRenderer>, masks: &[Mask], camera: &Camera) {
+    if renderers.len() < masks.len() {
+        renderers.resize_with(masks.len(), GridRenderer::new);
+    }
+    let scale = CELL_SIZE * camera.zoom;
+    for (renderer, mask) in renderers.iter_mut().zip(masks) {
+        // The camera as seen from the mask's top-left corner
+        let local = Camera {
+            offset_x: camera.offset_x + mask.origin.0 as f32 * scale,
+            offset_y: camera.offset_y + mask.origin.1 as f32 * scale,
+            zoom: camera.zoom,
+        };
+        draw_cell_mask(renderer, &mask.cells, STROKE_PREVIEW_RGBA, &local, Viewport::grid_area());
+    }
+}
+
+/// Draw a live graph of population, births and deaths over recent generations.
+/// Anchored to the bottom-left corner of the grid area.
+pub fn draw_stats_graph(stats: &StatsHistory) {
+    let width = (grid_area_width() - 20.0).clamp(0.0, 360.0);
+    let height = 110.0;
+    let x = 10.0;
+    let y = grid_area_height() - height - 10.0;
+    
+    if width < 60.0 || stats.is_empty() {
+        return;
+    }
+    
+    draw_rectangle(x, y, width, height, Color::from_rgba(0, 0, 0, 180));
+    draw_rectangle_lines(x, y, width, height, 1.0, Color::from_rgba(80, 80, 80, 255));
+    
+    // One sample per pixel column, most recent on the right
+    let plot_top = y + 18.0;
+    let plot_height = height - 24.0;
+    let columns = (width - 8.0) as usize;
+    let samples: Vec<_> = stats.iter().skip(stats.len().saturating_sub(columns)).collect();
+    
+    let max_population = samples.iter().map(|s| s.population).max().unwrap_or(0).max(1) as f32;
+    let max_change = samples.iter().map(|s| s.births.max(s.deaths)).max().unwrap_or(0).max(1) as f32;
+    
+    let plot = |values: &dyn Fn(usize) -> f32, scale: f32, color: Color| {
+        for i in 1..samples.len() {
+            let x0 = x + 4.0 + (i - 1) as f32;
+            let x1 = x + 4.0 + i as f32;
+            let y0 = plot_top + plot_height * (1.0 - values(i - 1) / scale);
+            let y1 = plot_top + plot_height * (1.0 - values(i) / scale);
+            draw_line(x0, y0, x1, y1, 1.0, color);
+        }
+    };
+    
+    plot(&|i| samples[i].births as f32, max_change, Color::from_rgba(100, 200, 255, 160));
+    plot(&|i| samples[i].deaths as f32, max_change, Color::from_rgba(255, 90, 90, 160));
+    plot(&|i| samples[i].population as f32, max_population, Color::from_rgba(0, 255, 150, 255));
+    
+    if let Some(latest) = stats.latest() {
+        let legend = format!(
+            "Pop {}  +{} -{}  density {:.2}%",
+            format_number(latest.population),
+            format_number(latest.births),
+            format_number(latest.deaths),
+            latest.density * 100.0
+        );
+        draw_text_label(&legend, x + 4.0, y + 13.0, 13.0, Color::from_rgba(200, 200, 200, 255));
+    }
+}
+
 /// Draw control panel background
 fn draw_panel_background() {
     draw_rectangle(
@@ -137,45 +299,337 @@ fn draw_panel_background() {
     );
 }
 
+/// Outline armed cell/region breakpoints and the one that last fired
+pub fn draw_breakpoints(state: &GameState, camera: &Camera) {
+    let cell_size = CELL_SIZE * camera.zoom;
+    let outline = |(x0, y0, x1, y1): CellRect, thickness: f32, color: Color| {
+        let (sx, sy) = camera.grid_to_screen(x0.min(x1), y0.min(y1), CELL_SIZE);
+        let w = (x1.abs_diff(x0) + 1) as f32 * cell_size;
+        let h = (y1.abs_diff(y0) + 1) as f32 * cell_size;
+        draw_rectangle_lines(sx, sy, w.max(2.0), h.max(2.0), thickness, color);
+    };
+    
+    for breakpoint in state.breakpoints.iter() {
+        let rect = match breakpoint.condition {
+            Condition::CellAlive { x, y } => (x, y, x, y),
+            Condition::RegionChanged(rect) => rect,
+            _ => continue,
+        };
+        outline(rect, 1.5, Color::from_rgba(255, 200, 0, 160));
+    }
+    
+    let Some(hit) = state.breakpoint_hit else {
+        return;
+    };
+    if let Some(rect) = hit.highlight {
+        outline(rect, 3.0, Color::from_rgba(255, 60, 60, 255));
+    }
+    draw_rectangle(8.0, 8.0, 320.0, 24.0, Color::from_rgba(0, 0, 0, 180));
+    draw_text_label(
+        &format!("Break: {} (gen {})", hit.condition, hit.generation),
+        14.0, 25.0, 16.0,
+        Color::from_rgba(255, 90, 90, 255)
+    );
+}
+
+/// Color key for the changes render mode (top-left, below any
+/// breakpoint banner)
+pub fn draw_changes_legend(palette: &HeatmapPalette) {
+    let colors = &palette.changes;
+    let entries = [("Born", colors.born), ("Survived", colors.survived), ("Died", colors.died)];
+    let (x, y) = (8.0, 36.0);
+    
+    draw_rectangle(x, y, 230.0, 22.0, Color::from_rgba(0, 0, 0, 180));
+    let mut cursor = x + 8.0;
+    for (label, color) in entries {
+        draw_rectangle(cursor, y + 6.0, 10.0, 10.0, color);
+        draw_text_label(label, cursor + 14.0, y + 16.0, 15.0, WHITE);
+        cursor += 14.0 + measure_text(label, None, 15, 1.0).width + 12.0;
+    }
+}
+
+/// Outline the followed object (or mark the population centroid) and
+/// label the view as following
+pub fn draw_follow_marker(follow: &Follow, camera: &Camera) {
+    let color = Color::from_rgba(120, 200, 255, 220);
+    let cell_size = CELL_SIZE * camera.zoom;
+    let to_screen = |x: f32, y: f32| (x * cell_size + camera.offset_x, y * cell_size + camera.offset_y);
+    let (x, y) = follow.position;
+    
+    let label = match follow.target {
+        FollowTarget::Object => {
+            // One cell of margin around the object's extent
+            let (rx, ry) = (follow.radius.0 + 1.0, follow.radius.1 + 1.0);
+            let (sx, sy) = to_screen(x - rx, y - ry);
+            let (w, h) = (2.0 * rx * cell_size, 2.0 * ry * cell_size);
+            draw_rectangle_lines(sx, sy, w.max(6.0), h.max(6.0), 1.5, color);
+            "Following object (Ctrl+click empty space to stop)"
+        }
+        FollowTarget::Population => {
+            let (sx, sy) = to_screen(x, y);
+            draw_line(sx - 8.0, sy, sx + 8.0, sy, 1.5, color);
+            draw_line(sx, sy - 8.0, sx, sy + 8.0, 1.5, color);
+            "Following population (T to stop)"
+        }
+    };
+    
+    let width = measure_text(label, None, 15, 1.0).width + 16.0;
+    let x = (grid_area_width() - width) / 2.0;
+    draw_rectangle(x, 8.0, width, 22.0, Color::from_rgba(0, 0, 0, 180));
+    draw_text_label(label, x + 8.0, 24.0, 15.0, color);
+}
+
+/// Cell inspector: outline the hovered cell and its object, and list
+/// the cell's state, neighbors and fate next to the cursor
+pub fn draw_cell_inspector(info: &CellInfo, camera: &Camera, mouse_pos: (f32, f32)) {
+    let color = Color::from_rgba(255, 220, 120, 230);
+    let cell_size = CELL_SIZE * camera.zoom;
+    let (sx, sy) = camera.grid_to_screen(info.x, info.y, CELL_SIZE);
+    draw_rectangle_lines(sx, sy, cell_size.max(2.0), cell_size.max(2.0), 1.5, color);
+    
+    let mut lines = vec![
+        format!("({}, {}) {}", info.x, info.y, if info.alive { "alive" } else { "dead" }),
+        format!("{} neighbors: {}", info.neighbors, info.fate()),
+    ];
+    if let Some(object) = &info.object {
+        let (width, height) = object.size();
+        let (ox, oy) = camera.grid_to_screen(object.origin.0, object.origin.1, CELL_SIZE);
+        draw_rectangle_lines(ox, oy, width as f32 * cell_size, height as f32 * cell_size, 1.0, color);
+        lines.push(format!("Object: {}×{} at ({}, {})", width, height, object.origin.0, object.origin.1));
+        let more = if object.truncated { "+" } else { "" };
+        lines.push(format!("Population: {}{}", format_number(object.cells), more));
+    }
+    
+    let font_size = 14.0;
+    let width = lines.iter()
+        .map(|line| measure_text(line, None, font_size as u16, 1.0).width)
+        .fold(0.0, f32::max) + 12.0;
+    let height = lines.len() as f32 * 16.0 + 6.0;
+    // Below and right of the cursor, flipped where it would leave the grid area
+    let mut x = mouse_pos.0 + 14.0;
+    let mut y = mouse_pos.1 + 18.0;
+    if x + width > grid_area_width() {
+        x = (mouse_pos.0 - width - 6.0).max(0.0);
+    }
+    if y + height > grid_area_height() {
+        y = (mouse_pos.1 - height - 6.0).max(0.0);
+    }
+    draw_rectangle(x, y, width, height, Color::from_rgba(20, 20, 20, 230));
+    draw_rectangle_lines(x, y, width, height, 1.0, Color::from_rgba(120, 120, 120, 255));
+    for (i, line) in lines.iter().enumerate() {
+        draw_text_label(line, x + 6.0, y + 16.0 + i as f32 * 16.0, font_size, WHITE);
+    }
+}
+
+/// Mark probed cells with their names
+pub fn draw_probes(probes: &ProbeSet, camera: &Camera) {
+    let cell_size = CELL_SIZE * camera.zoom;
+    for probe in probes.iter() {
+        let (sx, sy) = camera.grid_to_screen(probe.x, probe.y, CELL_SIZE);
+        draw_rectangle_lines(sx, sy, cell_size.max(2.0), cell_size.max(2.0), 2.0, PROBE_COLOR);
+        draw_text_label(&probe.name, sx + cell_size + 2.0, sy, 13.0, PROBE_COLOR);
+    }
+}
+
+/// Logic-analyzer view: one waveform row per probe over recent generations
+pub fn draw_probe_timeline(probes: &ProbeSet, generation: u64) {
+    if probes.is_empty() {
+        return;
+    }
+    
+    let row_height = 18.0;
+    let label_width = 50.0;
+    let width = (grid_area_width() - 20.0).clamp(0.0, 320.0);
+    let height = probes.len() as f32 * row_height + 20.0;
+    let x = grid_area_width() - width - 10.0;
+    let y = grid_area_height() - height - 10.0;
+    if width < label_width + 40.0 {
+        return;
+    }
+    
+    draw_rectangle(x, y, width, height, Color::from_rgba(0, 0, 0, 180));
+    draw_rectangle_lines(x, y, width, height, 1.0, Color::from_rgba(80, 80, 80, 255));
+    
+    // Two pixels per generation, most recent on the right
+    let plot_x = x + label_width;
+    let columns = ((width - label_width - 6.0) / 2.0) as u64;
+    let start = generation.saturating_sub(columns);
+    let column_x = |g: u64| plot_x + (g - start) as f32 * 2.0;
+    
+    for (row, probe) in probes.iter().enumerate() {
+        let top = y + 6.0 + row as f32 * row_height;
+        let high = top + 2.0;
+        let low = top + row_height - 4.0;
+        draw_text_label(&probe.name, x + 4.0, low, 13.0, PROBE_COLOR);
+        
+        let mut level = probe.state_at(start);
+        let mut from = start;
+        for (g, alive) in probe.samples_between(start, generation).chain([(generation + 1, false)]) {
+            if let Some(was_alive) = level {
+                let line_y = if was_alive { high } else { low };
+                draw_line(column_x(from), line_y, column_x(g), line_y, 1.5, PROBE_COLOR);
+                if g <= generation && alive != was_alive {
+                    draw_line(column_x(g), high, column_x(g), low, 1.0, PROBE_COLOR);
+                }
+            }
+            level = Some(alive);
+            from = g;
+        }
+    }
+    
+    draw_text_label(
+        &format!("gen {}..{}", start, generation),
+        plot_x, y + height - 4.0, 12.0, GRAY
+    );
+}
+
+/// Zoom as a factor, or as cells per pixel once cells are smaller than a pixel
+fn format_zoom(zoom: f32) -> String {
+    let cell_size = CELL_SIZE * zoom;
+    if cell_size < 1.0 {
+        format!("{:.2}x ({:.0} cells/px)", zoom, 1.0 / cell_size)
+    } else {
+        format!("{:.1}x", zoom)
+    }
+}
+
+/// Centered text prompt (go to coordinate, bookmark name)
+pub fn draw_prompt(title: &str, text: &str) {
+    let (width, height) = (300.0, 56.0);
+    let x = (grid_area_width() - width) / 2.0;
+    let y = (grid_area_height() - height) / 2.0;
+    
+    draw_rectangle(x, y, width, height, Color::from_rgba(20, 20, 20, 235));
+    draw_rectangle_lines(x, y, width, height, 1.5, Color::from_rgba(0, 255, 150, 255));
+    draw_text_label(title, x + 10.0, y + 20.0, 16.0, GRAY);
+    draw_text_label(&format!("{}_", text), x + 10.0, y + 44.0, 20.0, WHITE);
+}
+
+/// Update rate and step size, e.g. "10 x 64 gen/s"
+pub fn speed_label(state: &GameState) -> String {
+    if state.max_speed {
+        format!("Max ({}/update)", format_number(state.generations_per_update() as usize))
+    } else if state.step_size > 1 {
+        format!("{:.0} x {} gen/s", state.updates_per_second, format_number(state.step_size as usize))
+    } else {
+        format!("{:.0} gen/s", state.updates_per_second)
+    }
+}
+
 /// Helper to draw text labels
 fn draw_text_label(text: &str, x: f32, y: f32, size: f32, color: Color) {
     draw_text(text, x, y, size, color);
 }
 
-/// Draw the control panel with buttons, dropdowns, and info
+/// Draw the control panel: section headers, buttons and the panel's own
+/// widgets, the text blocks the layout has room for, then dropdowns on top
 pub fn draw_controls(
     state: &GameState,
     camera: &Camera,
+    panel: &Panel,
     buttons: &[Button],
     dropdowns: &[Dropdown],
+    keys: &KeyBindings,
     mouse_pos: (f32, f32)
 ) {
     draw_panel_background();
-    
-    // Draw all buttons FIRST
+    panel.draw(mouse_pos);
     buttons.iter().for_each(|btn| btn.draw(mouse_pos));
     
-    let px = panel_x();
+    let layout = panel.layout();
+    if let Some(rect) = layout.status {
+        draw_status(state, camera, rect);
+    }
+    if let Some(rect) = layout.info {
+        draw_info(state, rect);
+    }
+    if let Some(rect) = layout.help {
+        draw_help(rect, keys);
+    }
     
-    // Controls help - positioned below dropdowns (after pattern at ~170+50)
-    let controls = [
-        ("Controls:", px, 240.0, 14.0, WHITE),
-        ("LMB: Paint", px, 255.0, 12.0, GRAY),
-        ("RMB: Erase", px, 268.0, 12.0, GRAY),
-        ("Space: Play", px, 281.0, 12.0, GRAY),
-        ("Wheel: Zoom", px, 294.0, 12.0, GRAY),
-        ("Mid-drag: Pan", px, 307.0, 12.0, GRAY),
+    // Draw dropdowns LAST so they appear on top of everything
+    // Draw closed dropdowns first, then open one on top
+    let mut open_dropdown: Option<&Dropdown> = None;
+    for dropdown in dropdowns.iter() {
+        if dropdown.is_open() {
+            open_dropdown = Some(dropdown);
+        } else {
+            dropdown.draw(mouse_pos);
+        }
+    }
+    panel.draw_overlay();
+    // Draw open dropdown last so it's on top
+    if let Some(dd) = open_dropdown {
+        dd.draw(mouse_pos);
+    }
+}
+
+/// Key binding cheat sheet (first key of each action)
+fn draw_help(rect: Rect, keys: &KeyBindings) {
+    let key = |action| keys.keys(action).first().map_or("-".to_string(), |&k| key_name(k));
+    let lines = [
+        "LMB:Draw RMB:Erase ^LMB:Follow".to_string(),
+        format!("{}:Play {}:Step {}:Compare", key(Action::TogglePlay), key(Action::Step), key(Action::Compare)),
+        format!("Wheel:Zoom WASD:Pan {}:Follow all", key(Action::Follow)),
+        format!("{}:Fit ^G:Go 1-9:View ^S:PNG", key(Action::FitView)),
+        format!(
+            "{}/{}:Speed {} {}:Skip {}:Max",
+            key(Action::SpeedUp), key(Action::SlowDown), key(Action::HalveStep), key(Action::DoubleStep), key(Action::MaxSpeed)
+        ),
+        format!(
+            "{}:Graph {}:Export {}:Brk {}:Probe",
+            key(Action::StatsGraph), key(Action::Export), key(Action::Breakpoint), key(Action::Probe)
+        ),
+        format!("{}:All keys {}:Inspect", key(Action::Help), key(Action::Inspector)),
     ];
+    for (i, line) in lines.iter().enumerate() {
+        draw_text_label(line, rect.x, rect.y + 11.0 + i as f32 * 13.0, 12.0, GRAY);
+    }
+}
+
+/// Every key binding, rebindable ones first, in a box over the grid area
+pub fn draw_help_overlay(keys: &KeyBindings) {
+    let rows: Vec<(String, &str)> = Action::all().iter()
+        .map(|&action| (keys.label(action), action.description()))
+        .chain(FIXED_BINDINGS.iter().map(|&(keys, description)| (keys.to_string(), description)))
+        .collect();
     
-    controls.iter().for_each(|(text, x, y, size, color)| {
-        draw_text_label(text, *x, *y, *size, *color);
-    });
+    let (font_size, line_height) = (15.0, 19.0);
+    let key_width = rows.iter().map(|(keys, _)| measure_text(keys, None, font_size as u16, 1.0).width).fold(0.0, f32::max);
+    let text_width = rows.iter().map(|(_, text)| measure_text(text, None, font_size as u16, 1.0).width).fold(0.0, f32::max);
+    let width = key_width + text_width + 48.0;
+    let height = rows.len() as f32 * line_height + 48.0;
+    let x = ((grid_area_width() - width) / 2.0).max(0.0);
+    let y = ((grid_area_height() - height) / 2.0).max(0.0);
+    
+    draw_rectangle(x, y, width, height, Color::from_rgba(10, 10, 10, 235));
+    draw_rectangle_lines(x, y, width, height, 1.0, Color::from_rgba(120, 120, 120, 255));
+    let title = format!("Key bindings ({} or Esc to close)", keys.label(Action::Help));
+    draw_text_label(&title, x + 16.0, y + 24.0, 17.0, WHITE);
+    for (i, (keys, text)) in rows.iter().enumerate() {
+        let row_y = y + 44.0 + (i as f32 + 0.5) * line_height;
+        // Rebindable keys are highlighted; fixed ones are not
+        let color = if i < Action::all().len() { Color::from_rgba(255, 220, 120, 255) } else { LIGHTGRAY };
+        draw_text_label(keys, x + 16.0, row_y, font_size, color);
+        draw_text_label(text, x + 32.0 + key_width, row_y, font_size, LIGHTGRAY);
+    }
+}
+
+/// Grid size, population and performance figures
+fn draw_info(state: &GameState, rect: Rect) {
+    let (px, top) = (rect.x, rect.y);
     
     // Grid info
     let (gw, gh) = state.grid.dimensions();
     let cells = gw * gh;
-    let grid_info = format!("Grid: {}×{}\nCells: {}", gw, gh, format_number(cells));
-    draw_text_label(&grid_info, px, 335.0, 12.0, Color::from_rgba(150, 150, 150, 255));
+    let info_color = Color::from_rgba(150, 150, 150, 255);
+    draw_text_label(&format!("Grid: {}×{}", gw, gh), px, top + 11.0, 12.0, info_color);
+    draw_text_label(&format!("Cells: {}", format_number(cells)), px, top + 24.0, 12.0, info_color);
+    draw_text_label(
+        &format!("Population: {}", format_number(state.population())),
+        px, top + 40.0, 13.0,
+        Color::from_rgba(0, 255, 150, 255)
+    );
     
     // Performance metrics with algorithm name
     let evolve_ms = state.last_evolution_time_ms;
@@ -205,39 +659,54 @@ pub fn draw_controls(
         Color::from_rgba(255, 0, 0, 255)
     };
     
-    draw_text_label(&format!("Evolve: {:.1}ms", evolve_ms), px, 370.0, 13.0, perf_color);
-    draw_text_label(&format!("Render: {:.1}ms", render_ms), px, 385.0, 13.0, render_color);
-    draw_text_label(&format!("{} | FPS: {:.0}", algo_name, fps), px, 400.0, 12.0, GRAY);
+    draw_text_label(&format!("Evolve: {:.1}ms", evolve_ms), px, top + 58.0, 13.0, perf_color);
+    draw_text_label(&format!("Render: {:.1}ms", render_ms), px, top + 73.0, 13.0, render_color);
+    draw_text_label(&format!("{} | FPS: {:.0}", algo_name, fps), px, top + 88.0, 12.0, GRAY);
     
     // Cells per second (throughput)
-    if evolve_ms > 0.0 && state.is_running {
-        let cells_per_sec = (cells as f64) / (evolve_ms as f64 / 1000.0);
+    let ms_per_generation = state.ms_per_generation();
+    if ms_per_generation > 0.0 && state.is_running {
+        let cells_per_sec = (cells as f64) / (ms_per_generation as f64 / 1000.0);
         draw_text_label(
             &format!("Throughput: {}/s", format_number(cells_per_sec as usize)),
-            px, 415.0, 11.0, 
+            px, top + 103.0, 11.0, 
             Color::from_rgba(100, 200, 255, 255)
         );
     }
     
+    // Active render mode (M cycles, Shift+M changes colors)
+    let view_name = state.cell_history.as_ref().map_or("Plain", |h| h.kind().name());
+    draw_text_label(&format!("View: {} [M]", view_name), px, top + 118.0, 12.0, GRAY);
+    draw_text_label(
+        &format!("Gen/s: {:.1}", state.generations_per_second),
+        px, top + 133.0, 12.0,
+        Color::from_rgba(100, 200, 255, 255)
+    );
+}
+
+/// Speed, generation, run status and zoom readouts
+fn draw_status(state: &GameState, camera: &Camera, rect: Rect) {
+    let (px, top) = (rect.x, rect.y);
+    
     // Define all labels declaratively
     let labels = [
-        ("Speed:", px, 630.0, 16.0, WHITE),
+        ("Speed:", px, top + 14.0, 16.0, WHITE),
         (
-            &format!("{:.0} gen/s", state.updates_per_second),
-            px, 650.0, 14.0,
+            &speed_label(state),
+            px, top + 32.0, 14.0,
             Color::from_rgba(180, 180, 180, 255)
         ),
-        ("Generation:", px, 680.0, 16.0, WHITE),
+        ("Generation:", px, top + 58.0, 16.0, WHITE),
         (
             &format!("{}", state.generation),
-            px, 700.0, 20.0,
+            px, top + 78.0, 20.0,
             Color::from_rgba(0, 255, 150, 255)
         ),
-        ("Status:", px, 735.0, 16.0, WHITE),
+        ("Status:", px, top + 104.0, 16.0, WHITE),
         (
             if state.is_running { "Running" } else { "Paused" },
             px,
-            755.0,
+            top + 122.0,
             16.0,
             if state.is_running {
                 Color::from_rgba(0, 255, 0, 255)
@@ -245,10 +714,10 @@ pub fn draw_controls(
                 Color::from_rgba(255, 165, 0, 255)
             }
         ),
-        ("Zoom:", px, 780.0, 14.0, WHITE),
+        ("Zoom:", px, top + 148.0, 14.0, WHITE),
         (
-            &format!("{:.1}x", camera.zoom),
-            px, 795.0, 14.0,
+            &format_zoom(camera.zoom),
+            px, top + 165.0, 14.0,
             Color::from_rgba(180, 180, 180, 255)
         ),
     ];
@@ -257,20 +726,4 @@ pub fn draw_controls(
     labels.iter().for_each(|(text, x, y, size, color)|  {
         draw_text_label(text, *x, *y, *size, *color);
     });
-    
-    // Draw dropdowns LAST so they appear on top of everything
-    // Draw closed dropdowns first, then open one on top
-    let mut open_dropdown: Option<&Dropdown> = None;
-    for dropdown in dropdowns.iter() {
-        if dropdown.is_open() {
-            open_dropdown = Some(dropdown);
-        } else {
-            dropdown.draw(mouse_pos);
-        }
-    }
-    // Draw open dropdown last so it's on top
-    if let Some(dd) = open_dropdown {
-        dd.draw(mouse_pos);
-    }
 }
-
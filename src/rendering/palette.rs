@@ -0,0 +1,217 @@
+use macroquad::prelude::*;
+use crate::domain::{CellHistory, HistoryKind};
+use crate::export::Theme;
+
+/// Piecewise-linear color ramp sampled with t in [0, 1]
+#[derive(Clone, Debug)]
+pub struct ColorGradient {
+    pub name: &'static str,
+    /// Color stops sorted by position
+    stops: Vec<(f32, Color)>,
+}
+
+impl ColorGradient {
+    /// Create gradient from (position, color) stops; positions are sorted
+    pub fn new(name: &'static str, mut stops: Vec<(f32, Color)>) -> Self {
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { name, stops }
+    }
+
+    /// Interpolated color at `t` (clamped to [0, 1])
+    pub fn sample(&self, t: f32) -> Color {
+        let t = t.clamp(0.0, 1.0);
+        let Some(&(first_pos, first)) = self.stops.first() else {
+            return WHITE;
+        };
+        if t <= first_pos {
+            return first;
+        }
+
+        for pair in self.stops.windows(2) {
+            let (p0, c0) = pair[0];
+            let (p1, c1) = pair[1];
+            if t <= p1 {
+                let f = if p1 > p0 { (t - p0) / (p1 - p0) } else { 1.0 };
+                return Color::new(
+                    c0.r + (c1.r - c0.r) * f,
+                    c0.g + (c1.g - c0.g) * f,
+                    c0.b + (c1.b - c0.b) * f,
+                    c0.a + (c1.a - c0.a) * f,
+                );
+            }
+        }
+
+        self.stops.last().map(|&(_, c)| c).unwrap_or(WHITE)
+    }
+
+    /// Built-in gradients offered for cycling
+    pub fn presets() -> Vec<ColorGradient> {
+        vec![
+            ColorGradient::new("Inferno", vec![
+                (0.0, Color::from_rgba(60, 10, 90, 255)),
+                (0.4, Color::from_rgba(190, 40, 80, 255)),
+                (0.7, Color::from_rgba(250, 130, 20, 255)),
+                (1.0, Color::from_rgba(250, 250, 160, 255)),
+            ]),
+            ColorGradient::new("Ocean", vec![
+                (0.0, Color::from_rgba(20, 40, 120, 255)),
+                (0.5, Color::from_rgba(30, 160, 200, 255)),
+                (1.0, Color::from_rgba(220, 255, 255, 255)),
+            ]),
+            ColorGradient::new("Growth", vec![
+                (0.0, Color::from_rgba(180, 255, 120, 255)),
+                (0.5, Color::from_rgba(0, 200, 150, 255)),
+                (1.0, Color::from_rgba(0, 70, 160, 255)),
+            ]),
+            ColorGradient::new("Ember", vec![
+                (0.0, Color::from_rgba(80, 0, 0, 0)),
+                (0.5, Color::from_rgba(200, 40, 0, 150)),
+                (1.0, Color::from_rgba(255, 200, 60, 230)),
+            ]),
+        ]
+    }
+
+    fn preset(name: &str) -> ColorGradient {
+        Self::presets()
+            .into_iter()
+            .find(|g| g.name == name)
+            .expect("built-in gradient")
+    }
+}
+
+/// Colors for the changes render mode
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChangeColors {
+    pub name: &'static str,
+    /// Dead last generation, alive now
+    pub born: Color,
+    /// Alive in both generations
+    pub survived: Color,
+    /// Alive last generation, dead now
+    pub died: Color,
+}
+
+impl ChangeColors {
+    /// Built-in color sets offered for cycling
+    pub fn presets() -> Vec<ChangeColors> {
+        vec![
+            ChangeColors {
+                name: "Classic",
+                born: Color::from_rgba(60, 230, 90, 255),
+                survived: Color::from_rgba(170, 170, 190, 255),
+                died: Color::from_rgba(230, 50, 50, 200),
+            },
+            // Blue/orange stays distinguishable with red-green color blindness
+            ChangeColors {
+                name: "Contrast",
+                born: Color::from_rgba(40, 140, 255, 255),
+                survived: Color::from_rgba(220, 220, 220, 255),
+                died: Color::from_rgba(255, 150, 0, 200),
+            },
+        ]
+    }
+}
+
+impl Default for ChangeColors {
+    fn default() -> Self {
+        Self::presets().swap_remove(0)
+    }
+}
+
+/// Gradients and scales used by the history render modes
+#[derive(Clone, Debug)]
+pub struct HeatmapPalette {
+    /// Live and dead cells and grid lines without a history layer
+    pub theme: Theme,
+    /// Window background around the grid and behind zoomed-out cells
+    pub background: [u8; 4],
+    /// Live cells by age (young -> old)
+    pub age: ColorGradient,
+    /// Live and once-live cells by total generations alive (cold -> hot)
+    pub heat: ColorGradient,
+    /// Dead cells by remaining trail (faded -> fresh)
+    pub trail: ColorGradient,
+    /// Born / surviving / died cells
+    pub changes: ChangeColors,
+    /// Age at which the age gradient saturates
+    pub age_span: u16,
+    /// Activity at which the heat gradient saturates
+    pub heat_span: u16,
+}
+
+impl HeatmapPalette {
+    /// Gradient used for the given history kind (None for `Changes`,
+    /// which uses fixed colors)
+    pub fn gradient(&self, kind: HistoryKind) -> Option<&ColorGradient> {
+        match kind {
+            HistoryKind::Age => Some(&self.age),
+            HistoryKind::Activity => Some(&self.heat),
+            HistoryKind::Trail => Some(&self.trail),
+            HistoryKind::Changes => None,
+        }
+    }
+
+    /// Switch the gradient (or change colors) for `kind` to the next
+    /// built-in preset
+    pub fn cycle_gradient(&mut self, kind: HistoryKind) {
+        let presets = ColorGradient::presets();
+        let slot = match kind {
+            HistoryKind::Age => &mut self.age,
+            HistoryKind::Activity => &mut self.heat,
+            HistoryKind::Trail => &mut self.trail,
+            HistoryKind::Changes => {
+                let presets = ChangeColors::presets();
+                let current = presets.iter().position(|c| c.name == self.changes.name).unwrap_or(0);
+                self.changes = presets[(current + 1) % presets.len()].clone();
+                return;
+            }
+        };
+        let current = presets.iter().position(|g| g.name == slot.name).unwrap_or(0);
+        *slot = presets[(current + 1) % presets.len()].clone();
+    }
+
+    /// Color for a cell given its history value, or None to fall back
+    /// to the plain alive/dead coloring
+    pub fn cell_color(&self, history: &CellHistory, alive: bool, value: u16) -> Option<Color> {
+        match history.kind() {
+            HistoryKind::Age if alive => {
+                Some(self.age.sample(log_fraction(value.saturating_sub(1), self.age_span)))
+            }
+            // Live cells go through the gradient too; one drawn since the
+            // last generation has not been counted yet
+            HistoryKind::Activity if alive || value > 0 => {
+                Some(self.heat.sample(log_fraction(value.max(1), self.heat_span)))
+            }
+            HistoryKind::Trail if !alive && value > 0 => {
+                Some(self.trail.sample(value as f32 / history.trail_length() as f32))
+            }
+            HistoryKind::Changes => match (alive, value > 0) {
+                (true, false) => Some(self.changes.born),
+                (true, true) => Some(self.changes.survived),
+                (false, true) => Some(self.changes.died),
+                (false, false) => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Default for HeatmapPalette {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            background: [0, 0, 0, 255],
+            age: ColorGradient::preset("Growth"),
+            heat: ColorGradient::preset("Inferno"),
+            trail: ColorGradient::preset("Ember"),
+            changes: ChangeColors::default(),
+            age_span: 200,
+            heat_span: 500,
+        }
+    }
+}
+
+/// Map a counter onto [0, 1] on a log scale so early changes stay visible
+pub(super) fn log_fraction(value: u16, span: u16) -> f32 {
+    (1.0 + value as f32).ln() / (1.0 + span.max(1) as f32).ln()
+}
@@ -0,0 +1,494 @@
+//! CPU rasterizer for the visible part of the grid.
+//!
+//! The visible cells are written into an RGBA buffer (one pixel per cell)
+//! in parallel, straight from the `BitGrid` chunks, then uploaded to a
+//! single texture and drawn as one quad. When zoomed out below one pixel
+//! per cell, each pixel instead covers a square bin of cells and shows
+//! its live fraction.
+
+use macroquad::prelude::*;
+use rayon::prelude::*;
+
+use crate::domain::{BitGrid, CellHistory, HistoryKind};
+use super::HeatmapPalette;
+
+/// Nothing drawn (the clear color shows through)
+pub const TRANSPARENT_RGBA: [u8; 4] = [0, 0, 0, 0];
+
+/// Rectangle of cells to rasterize
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CellRegion {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl CellRegion {
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+/// Rasterize `region` of `grid` into `pixels` (RGBA, row-major, one pixel
+/// per cell). `pixels` is resized to fit. Rows are filled in parallel.
+pub fn rasterize(
+    grid: &BitGrid,
+    history: Option<&CellHistory>,
+    palette: &HeatmapPalette,
+    region: CellRegion,
+    dead: [u8; 4],
+    pixels: &mut Vec<u8>,
+) {
+    let row_bytes = region.width * 4;
+    pixels.resize(row_bytes * region.height, 0);
+    if region.is_empty() {
+        return;
+    }
+
+    pixels
+        .par_chunks_mut(row_bytes)
+        .enumerate()
+        .for_each(|(row, out)| {
+            let y = region.y + row;
+
+            // Births, survivors and deaths come straight from the chunks
+            if let Some(history) = history.filter(|h| h.kind() == HistoryKind::Changes) {
+                changes_row(grid, history, palette, region, y, dead, out);
+                return;
+            }
+            
+            // Other history modes color dead cells too, so visit every cell
+            if let Some(history) = history {
+                for (i, pixel) in out.chunks_exact_mut(4).enumerate() {
+                    let x = region.x + i;
+                    let alive = grid.get(x, y);
+                    let rgba = match palette.cell_color(history, alive, history.get(x, y)) {
+                        Some(color) => color.into(),
+                        None if alive => palette.theme.alive,
+                        None => dead,
+                    };
+                    pixel.copy_from_slice(&rgba);
+                }
+                return;
+            }
+
+            live_cells_row(grid, region, y, palette.theme.alive, dead, out);
+        });
+}
+
+/// Rasterize only the live cells of `region`, in `color`, leaving every
+/// other pixel transparent (for overlays such as diff highlights)
+pub fn rasterize_mask(grid: &BitGrid, region: CellRegion, color: [u8; 4], pixels: &mut Vec<u8>) {
+    let row_bytes = region.width * 4;
+    pixels.resize(row_bytes * region.height, 0);
+    if region.is_empty() {
+        return;
+    }
+
+    pixels
+        .par_chunks_mut(row_bytes)
+        .enumerate()
+        .for_each(|(row, out)| live_cells_row(grid, region, region.y + row, color, TRANSPARENT_RGBA, out));
+}
+
+/// Fill grid row `y` of `region`: `alive` for live cells, `dead` elsewhere
+fn live_cells_row(grid: &BitGrid, region: CellRegion, y: usize, alive: [u8; 4], dead: [u8; 4], out: &mut [u8]) {
+    for pixel in out.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&dead);
+    }
+
+    // Only set bits inside the region need writing
+    let chunks = grid.chunks();
+    let chunk_width = grid.dimensions().0.div_ceil(64);
+    for chunk_x in region_chunks(region) {
+        let bits = chunks[y * chunk_width + chunk_x].0 & region_mask(region, chunk_x);
+        paint_bits(bits, chunk_x, region, alive, out);
+    }
+}
+
+/// Fill grid row `y` of `region` with born, surviving and died colors,
+/// comparing the grid with the history's previous generation bitwise
+fn changes_row(
+    grid: &BitGrid,
+    history: &CellHistory,
+    palette: &HeatmapPalette,
+    region: CellRegion,
+    y: usize,
+    dead: [u8; 4],
+    out: &mut [u8],
+) {
+    for pixel in out.chunks_exact_mut(4) {
+        pixel.copy_from_slice(&dead);
+    }
+
+    let colors = &palette.changes;
+    let (born, survived, died) = (colors.born.into(), colors.survived.into(), colors.died.into());
+    let chunks = grid.chunks();
+    let previous = history.previous_chunks();
+    let chunk_width = grid.dimensions().0.div_ceil(64);
+    for chunk_x in region_chunks(region) {
+        let idx = y * chunk_width + chunk_x;
+        let mask = region_mask(region, chunk_x);
+        let (now, before) = (chunks[idx].0 & mask, previous[idx] & mask);
+        paint_bits(now & !before, chunk_x, region, born, out);
+        paint_bits(now & before, chunk_x, region, survived, out);
+        paint_bits(before & !now, chunk_x, region, died, out);
+    }
+}
+
+/// Chunk columns overlapping `region`
+fn region_chunks(region: CellRegion) -> std::ops::Range<usize> {
+    region.x / 64..(region.x + region.width).div_ceil(64)
+}
+
+/// Bits of chunk column `chunk_x` that fall inside `region`
+fn region_mask(region: CellRegion, chunk_x: usize) -> u64 {
+    let base = chunk_x * 64;
+    let end_x = region.x + region.width;
+    let mut mask = u64::MAX;
+    if base < region.x {
+        mask &= u64::MAX << (region.x - base);
+    }
+    if end_x < base + 64 {
+        mask &= u64::MAX >> (base + 64 - end_x);
+    }
+    mask
+}
+
+/// Write `color` for every set bit of chunk column `chunk_x` into a row
+/// of `region` pixels
+fn paint_bits(mut bits: u64, chunk_x: usize, region: CellRegion, color: [u8; 4], out: &mut [u8]) {
+    while bits != 0 {
+        let x = chunk_x * 64 + bits.trailing_zeros() as usize;
+        let offset = (x - region.x) * 4;
+        out[offset..offset + 4].copy_from_slice(&color);
+        bits &= bits - 1;
+    }
+}
+
+/// Rasterize `region` with `cells_per_pixel` x `cells_per_pixel` cells
+/// per pixel. Pixel brightness of `color` follows the live fraction of
+/// its bin, on a square-root scale so sparse areas stay visible. The image
+/// is `density_image_size(region, cells_per_pixel)` pixels.
+pub fn rasterize_density(
+    grid: &BitGrid,
+    region: CellRegion,
+    cells_per_pixel: usize,
+    color: [u8; 4],
+    pixels: &mut Vec<u8>,
+) {
+    let bin = cells_per_pixel.max(1);
+    let (width, height) = density_image_size(region, bin);
+    let row_bytes = width * 4;
+    pixels.resize(row_bytes * height, 0);
+    if region.is_empty() {
+        return;
+    }
+
+    pixels
+        .par_chunks_mut(row_bytes)
+        .enumerate()
+        .for_each(|(row, out)| density_row(grid, region, bin, row, color, out));
+}
+
+/// Fill one row of density pixels (`out` is RGBA for that image row)
+pub(super) fn density_row(grid: &BitGrid, region: CellRegion, bin: usize, row: usize, color: [u8; 4], out: &mut [u8]) {
+    let end_x = region.x + region.width;
+    let end_y = region.y + region.height;
+    let y0 = region.y + row * bin;
+    let y1 = (y0 + bin).min(end_y);
+
+    for (column, pixel) in out.chunks_exact_mut(4).enumerate() {
+        let x0 = region.x + column * bin;
+        let x1 = (x0 + bin).min(end_x);
+
+        let live: usize = (y0..y1).map(|y| grid.count_alive_in_row(y, x0, x1)).sum();
+        let total = x1.saturating_sub(x0) * y1.saturating_sub(y0);
+        pixel.copy_from_slice(&density_rgba(live, total, color));
+    }
+}
+
+/// Image size in pixels for `region` binned by `cells_per_pixel`
+pub fn density_image_size(region: CellRegion, cells_per_pixel: usize) -> (usize, usize) {
+    let bin = cells_per_pixel.max(1);
+    (region.width.div_ceil(bin), region.height.div_ceil(bin))
+}
+
+/// `color` scaled by the live fraction of a bin
+fn density_rgba(live: usize, total: usize, color: [u8; 4]) -> [u8; 4] {
+    if live == 0 || total == 0 {
+        return TRANSPARENT_RGBA;
+    }
+    let brightness = (live as f32 / total as f32).sqrt();
+    let [r, g, b, _] = color;
+    [
+        (r as f32 * brightness) as u8,
+        (g as f32 * brightness) as u8,
+        (b as f32 * brightness) as u8,
+        255,
+    ]
+}
+
+/// Texture the rasterized region is uploaded to, reused across frames
+pub struct GridRenderer {
+    texture: Option<Texture2D>,
+    texture_size: (usize, usize),
+    pixels: Vec<u8>,
+}
+
+impl GridRenderer {
+    pub fn new() -> Self {
+        Self {
+            texture: None,
+            texture_size: (0, 0),
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Upload `pixels` for a `width` x `height` image and return the texture
+    fn upload(&mut self, width: usize, height: usize) -> &Texture2D {
+        if self.texture.is_none() || self.texture_size != (width, height) {
+            let texture = Texture2D::from_rgba8(width as u16, height as u16, &self.pixels);
+            texture.set_filter(FilterMode::Nearest);
+            self.texture = Some(texture);
+            self.texture_size = (width, height);
+        } else if let Some(texture) = &self.texture {
+            texture.update_from_bytes(width as u32, height as u32, &self.pixels);
+        }
+        self.texture.as_ref().expect("texture uploaded")
+    }
+
+    /// Rasterize `region` and draw it as one quad at (`x`, `y`),
+    /// scaled to `pixel_size` screen pixels per image pixel
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_region(
+        &mut self,
+        grid: &BitGrid,
+        history: Option<&CellHistory>,
+        palette: &HeatmapPalette,
+        region: CellRegion,
+        dead: [u8; 4],
+        x: f32,
+        y: f32,
+        pixel_size: f32,
+    ) {
+        if region.is_empty() {
+            return;
+        }
+
+        let mut pixels = std::mem::take(&mut self.pixels);
+        rasterize(grid, history, palette, region, dead, &mut pixels);
+        self.pixels = pixels;
+
+        self.present(region.width, region.height, x, y, pixel_size);
+    }
+
+    /// Density-bin `region` (level of detail for zoomed-out views) in
+    /// `color` and draw it at (`x`, `y`) with `pixel_size` screen pixels
+    /// per bin
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_density_region(
+        &mut self,
+        grid: &BitGrid,
+        region: CellRegion,
+        cells_per_pixel: usize,
+        color: [u8; 4],
+        x: f32,
+        y: f32,
+        pixel_size: f32,
+    ) {
+        if region.is_empty() {
+            return;
+        }
+
+        let mut pixels = std::mem::take(&mut self.pixels);
+        rasterize_density(grid, region, cells_per_pixel, color, &mut pixels);
+        self.pixels = pixels;
+
+        let (width, height) = density_image_size(region, cells_per_pixel);
+        self.present(width, height, x, y, pixel_size);
+    }
+
+    /// Rasterize the live cells of `region` in `color` over a transparent
+    /// background and draw them at (`x`, `y`)
+    pub fn draw_mask_region(&mut self, grid: &BitGrid, region: CellRegion, color: [u8; 4], x: f32, y: f32, pixel_size: f32) {
+        if region.is_empty() {
+            return;
+        }
+
+        let mut pixels = std::mem::take(&mut self.pixels);
+        rasterize_mask(grid, region, color, &mut pixels);
+        self.pixels = pixels;
+
+        self.present(region.width, region.height, x, y, pixel_size);
+    }
+
+    /// Upload the pixel buffer and draw it as one quad
+    fn present(&mut self, width: usize, height: usize, x: f32, y: f32, pixel_size: f32) {
+        let texture = self.upload(width, height);
+        draw_texture_ex(
+            texture,
+            x,
+            y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(vec2(width as f32 * pixel_size, height as f32 * pixel_size)),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+impl Default for GridRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALIVE_RGBA: [u8; 4] = [0, 255, 150, 255];
+    const DEAD_RGBA: [u8; 4] = [15, 15, 15, 255];
+
+    fn pixel(pixels: &[u8], width: usize, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * width + x) * 4;
+        pixels[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn test_rasterize_matches_grid() {
+        let mut grid = BitGrid::new(200, 20);
+        grid.randomize();
+        let region = CellRegion { x: 37, y: 3, width: 120, height: 10 };
+
+        let mut pixels = Vec::new();
+        rasterize(&grid, None, &HeatmapPalette::default(), region, DEAD_RGBA, &mut pixels);
+
+        assert_eq!(pixels.len(), 120 * 10 * 4);
+        for y in 0..region.height {
+            for x in 0..region.width {
+                let expected = if grid.get(region.x + x, region.y + y) { ALIVE_RGBA } else { DEAD_RGBA };
+                assert_eq!(pixel(&pixels, region.width, x, y), expected, "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rasterize_with_history_colors_cells() {
+        use crate::domain::HistoryKind;
+
+        let mut grid = BitGrid::new(10, 10);
+        grid.set(2, 2, true);
+        let history = CellHistory::new(HistoryKind::Age, &grid);
+        let palette = HeatmapPalette::default();
+        let region = CellRegion { x: 0, y: 0, width: 10, height: 10 };
+
+        let mut pixels = Vec::new();
+        rasterize(&grid, Some(&history), &palette, region, TRANSPARENT_RGBA, &mut pixels);
+
+        let expected: [u8; 4] = palette.age.sample(0.0).into();
+        assert_eq!(pixel(&pixels, 10, 2, 2), expected);
+        assert_eq!(pixel(&pixels, 10, 3, 2), TRANSPARENT_RGBA);
+    }
+
+    #[test]
+    fn test_heat_map_colors_live_cells_by_activity() {
+        use crate::rendering::palette::log_fraction;
+
+        let mut before = BitGrid::new(10, 10);
+        before.set(1, 1, true);
+        before.set(5, 5, true);
+        let mut after = before.clone();
+        after.set(5, 5, false);
+        let mut history = CellHistory::new(HistoryKind::Activity, &before);
+        history.update(&before, &after);
+        // Drawn after the last update, so not counted yet
+        after.set(8, 8, true);
+        let palette = HeatmapPalette::default();
+        let region = CellRegion { x: 0, y: 0, width: 10, height: 10 };
+
+        let mut pixels = Vec::new();
+        rasterize(&after, Some(&history), &palette, region, TRANSPARENT_RGBA, &mut pixels);
+
+        let heat = |value: u16| -> [u8; 4] { palette.heat.sample(log_fraction(value, palette.heat_span)).into() };
+        assert_eq!(pixel(&pixels, 10, 1, 1), heat(2));
+        assert_eq!(pixel(&pixels, 10, 5, 5), heat(1));
+        assert_eq!(pixel(&pixels, 10, 8, 8), heat(1));
+        assert_eq!(pixel(&pixels, 10, 0, 0), TRANSPARENT_RGBA);
+    }
+
+    #[test]
+    fn test_changes_color_births_survivors_and_deaths() {
+        let mut before = BitGrid::new(130, 3);
+        before.set(1, 1, true);
+        before.set(2, 1, true);
+        before.set(3, 1, true);
+        let after = before.evolve(&crate::domain::ConwayRule);
+
+        let mut history = CellHistory::new(HistoryKind::Changes, &before);
+        history.update(&before, &after);
+        let palette = HeatmapPalette::default();
+        let colors = &palette.changes;
+        let region = CellRegion { x: 0, y: 0, width: 130, height: 3 };
+
+        let mut pixels = Vec::new();
+        rasterize(&after, Some(&history), &palette, region, TRANSPARENT_RGBA, &mut pixels);
+
+        let color = |c: Color| -> [u8; 4] { c.into() };
+        assert_eq!(pixel(&pixels, 130, 2, 1), color(colors.survived));
+        assert_eq!(pixel(&pixels, 130, 2, 0), color(colors.born));
+        assert_eq!(pixel(&pixels, 130, 1, 1), color(colors.died));
+        assert_eq!(pixel(&pixels, 130, 5, 1), TRANSPARENT_RGBA);
+
+        // Same answer as the per-cell palette lookup
+        for (x, y) in [(2, 0), (2, 1), (1, 1), (3, 1), (2, 2), (0, 0)] {
+            let expected = palette
+                .cell_color(&history, after.get(x, y), history.get(x, y))
+                .map_or(TRANSPARENT_RGBA, color);
+            assert_eq!(pixel(&pixels, 130, x, y), expected, "({}, {})", x, y);
+        }
+    }
+
+    #[test]
+    fn test_mask_only_colors_live_cells() {
+        let mut grid = BitGrid::new(130, 4);
+        grid.set(64, 1, true);
+        grid.set(129, 2, true);
+        let color = [255, 0, 255, 255];
+        let region = CellRegion { x: 60, y: 0, width: 70, height: 4 };
+
+        let mut pixels = Vec::new();
+        rasterize_mask(&grid, region, color, &mut pixels);
+
+        assert_eq!(pixel(&pixels, 70, 4, 1), color);
+        assert_eq!(pixel(&pixels, 70, 69, 2), color);
+        assert_eq!(pixel(&pixels, 70, 5, 1), TRANSPARENT_RGBA);
+        assert_eq!(pixels.chunks_exact(4).filter(|p| *p == color).count(), 2);
+    }
+
+    #[test]
+    fn test_density_bins_reflect_live_fraction() {
+        let mut grid = BitGrid::new(100, 100);
+        // Top-left 4x4 bin fully alive, next bin a quarter alive
+        for y in 0..4 {
+            for x in 0..4 {
+                grid.set(x, y, true);
+            }
+            grid.set(4, y, true);
+        }
+        let region = CellRegion { x: 0, y: 0, width: 10, height: 10 };
+
+        let mut pixels = Vec::new();
+        rasterize_density(&grid, region, 4, ALIVE_RGBA, &mut pixels);
+
+        assert_eq!(density_image_size(region, 4), (3, 3));
+        assert_eq!(pixels.len(), 3 * 3 * 4);
+        assert_eq!(pixel(&pixels, 3, 0, 0), ALIVE_RGBA);
+        assert_eq!(pixel(&pixels, 3, 1, 0), density_rgba(4, 16, ALIVE_RGBA));
+        assert_eq!(pixel(&pixels, 3, 1, 0)[1], 127, "sqrt(0.25) of full green");
+        assert_eq!(pixel(&pixels, 3, 2, 2), TRANSPARENT_RGBA);
+    }
+}
@@ -0,0 +1,170 @@
+//! Text rasterizer: draws the visible part of a `BitGrid` with Unicode
+//! braille (2x4 dots per character) or half blocks (1x2 dots).
+//!
+//! The camera works in dots the way the window works in pixels, with
+//! `DOT_CELL_SIZE` as the base cell size, so `Camera` pans and zooms the
+//! same way in both frontends. Zoomed out past one cell per dot, a dot is
+//! lit when any cell it covers is alive.
+
+use crate::application::Camera;
+use crate::domain::BitGrid;
+
+/// Dots per cell at zoom 1.0
+pub const DOT_CELL_SIZE: f32 = 1.0;
+
+/// Character set used to draw cells
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Glyphs {
+    /// 2x4 dots per character, highest resolution
+    #[default]
+    Braille,
+    /// 1x2 dots per character, square-ish cells in most fonts
+    HalfBlock,
+}
+
+impl Glyphs {
+    /// Dots per character horizontally and vertically
+    pub fn dots_per_char(self) -> (usize, usize) {
+        match self {
+            Glyphs::Braille => (2, 4),
+            Glyphs::HalfBlock => (1, 2),
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Glyphs::Braille => Glyphs::HalfBlock,
+            Glyphs::HalfBlock => Glyphs::Braille,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Glyphs::Braille => "Braille",
+            Glyphs::HalfBlock => "Half-block",
+        }
+    }
+
+    /// Character for a block of dots, `dots[row][column]`
+    fn glyph(self, dots: [[bool; 2]; 4]) -> char {
+        match self {
+            Glyphs::Braille => {
+                // Unicode braille dot numbering: 1-3 down the left column,
+                // 4-6 down the right, 7 and 8 on the bottom row
+                const BITS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+                let mut code = 0;
+                for (row, bits) in dots.iter().zip(BITS) {
+                    for (&dot, bit) in row.iter().zip(bits) {
+                        if dot {
+                            code |= bit;
+                        }
+                    }
+                }
+                char::from_u32(0x2800 + code).expect("braille block")
+            }
+            Glyphs::HalfBlock => match (dots[0][0], dots[1][0]) {
+                (true, true) => '█',
+                (true, false) => '▀',
+                (false, true) => '▄',
+                (false, false) => ' ',
+            },
+        }
+    }
+}
+
+/// Viewport size in dots for a text area of `columns` x `rows` characters
+pub fn viewport_dots(glyphs: Glyphs, columns: usize, rows: usize) -> (f32, f32) {
+    let (dx, dy) = glyphs.dots_per_char();
+    ((columns * dx) as f32, (rows * dy) as f32)
+}
+
+/// Half-open range of cells covered by dot `dot` along one axis, clipped
+/// to `0..limit` (empty when the dot is off the grid)
+fn cell_span(dot: usize, offset: f32, dot_size: f32, limit: usize) -> (usize, usize) {
+    let start = ((dot as f32 - offset) / dot_size).floor();
+    let end = ((dot as f32 + 1.0 - offset) / dot_size).floor().max(start + 1.0);
+    let clip = |v: f32| v.clamp(0.0, limit as f32) as usize;
+    (clip(start), clip(end))
+}
+
+/// Draw the grid as `rows` lines of `columns` characters
+pub fn render_lines(grid: &BitGrid, camera: &Camera, glyphs: Glyphs, columns: usize, rows: usize) -> Vec<String> {
+    let (width, height) = grid.dimensions();
+    let (dots_x, dots_y) = glyphs.dots_per_char();
+    let dot_size = DOT_CELL_SIZE * camera.zoom;
+
+    let column_spans: Vec<_> = (0..columns * dots_x)
+        .map(|dot| cell_span(dot, camera.offset_x, dot_size, width))
+        .collect();
+
+    // Whether any cell under dot (column, row) is alive
+    let lit = |column: usize, (y0, y1): (usize, usize)| {
+        let (x0, x1) = column_spans[column];
+        x0 < x1 && (y0..y1).any(|y| grid.count_alive_in_row(y, x0, x1) > 0)
+    };
+
+    (0..rows)
+        .map(|row| {
+            let row_spans: Vec<_> = (0..dots_y)
+                .map(|dy| cell_span(row * dots_y + dy, camera.offset_y, dot_size, height))
+                .collect();
+            (0..columns)
+                .map(|column| {
+                    let mut dots = [[false; 2]; 4];
+                    for (row_dots, &span) in dots.iter_mut().zip(&row_spans) {
+                        for (dx, dot) in row_dots.iter_mut().take(dots_x).enumerate() {
+                            *dot = lit(column * dots_x + dx, span);
+                        }
+                    }
+                    glyphs.glyph(dots)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_braille_dots_map_to_cells() {
+        let mut grid = BitGrid::new(8, 8);
+        grid.set(0, 0, true);
+        grid.set(1, 3, true);
+        grid.set(2, 1, true);
+
+        let lines = render_lines(&grid, &Camera::new(), Glyphs::Braille, 2, 1);
+        // First character: dots 1 (0,0) and 8 (1,3); second: dot 2 (0,1)
+        assert_eq!(lines, vec!["\u{2881}\u{2802}".to_string()]);
+    }
+
+    #[test]
+    fn test_half_blocks_and_offset() {
+        let mut grid = BitGrid::new(4, 4);
+        grid.set(1, 0, true);
+        grid.set(1, 1, true);
+        grid.set(2, 1, true);
+
+        let lines = render_lines(&grid, &Camera::new(), Glyphs::HalfBlock, 4, 2);
+        assert_eq!(lines, vec![" █▄ ".to_string(), "    ".to_string()]);
+
+        // Panned one dot right: column 0 is now off the grid
+        let mut camera = Camera::new();
+        camera.pan(1.0, 0.0);
+        let lines = render_lines(&grid, &camera, Glyphs::HalfBlock, 4, 1);
+        assert_eq!(lines, vec!["  █▄".to_string()]);
+    }
+
+    #[test]
+    fn test_zoomed_out_dot_covers_several_cells() {
+        let mut grid = BitGrid::new(64, 64);
+        grid.set(7, 5, true);
+
+        // Four cells per dot: the live cell lands in dot (1, 1)
+        let mut camera = Camera::new();
+        camera.zoom = 0.25;
+        let lines = render_lines(&grid, &camera, Glyphs::Braille, 1, 1);
+        assert_eq!(lines, vec!["\u{2810}".to_string()]);
+    }
+}
@@ -0,0 +1,209 @@
+//! Key bindings for the terminal frontend, mirroring the window's
+//! bindings where the same key exists.
+
+use std::sync::Arc;
+
+use crate::application::{Command, GameState};
+use crate::domain::{all_rules, presets};
+use super::{Key, TuiView, DOT_CELL_SIZE};
+
+/// Dots panned per key press (tripled with Shift)
+const PAN_STEP: f32 = 8.0;
+
+/// Zoom factor per key press
+const ZOOM_STEP: f32 = 1.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PickerKind {
+    Rule,
+    Pattern,
+}
+
+/// A list the user picks one entry from with the arrow keys and Enter
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Picker {
+    pub kind: PickerKind,
+    pub items: Vec<String>,
+    pub selected: usize,
+}
+
+impl Picker {
+    /// Rule list with the active rule selected
+    pub fn rules(state: &GameState) -> Self {
+        let rules = all_rules();
+        let selected = rules.iter().position(|(_, rule)| rule.name() == state.rule.name()).unwrap_or(0);
+        Self {
+            kind: PickerKind::Rule,
+            items: rules.into_iter().map(|(name, _)| name.to_string()).collect(),
+            selected,
+        }
+    }
+
+    pub fn patterns() -> Self {
+        Self {
+            kind: PickerKind::Pattern,
+            items: presets::all_patterns().iter().map(|p| p.name.to_string()).collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self.kind {
+            PickerKind::Rule => "Rule",
+            PickerKind::Pattern => "Pattern",
+        }
+    }
+
+    /// Move the selection, wrapping around
+    fn move_by(&mut self, delta: isize) {
+        let len = self.items.len() as isize;
+        self.selected = (self.selected as isize + delta).rem_euclid(len.max(1)) as usize;
+    }
+}
+
+/// Apply one key press
+pub fn handle_key(state: GameState, view: &mut TuiView, key: Key) -> GameState {
+    if view.picker.is_some() {
+        return handle_picker_key(state, view, key);
+    }
+
+    // Same commands as the window's keyboard bindings
+    let command = match key {
+        Key::Char(' ') => Some(Command::TogglePlay),
+        Key::Char('n') => Some(Command::Step),
+        Key::Char('c') => Some(Command::Clear),
+        Key::Char('r') => Some(Command::Randomize),
+        Key::Char('+' | '=') => Some(Command::AdjustSpeed(1.0)),
+        Key::Char('-') => Some(Command::AdjustSpeed(-1.0)),
+        Key::Char(']') => Some(Command::DoubleStepSize),
+        Key::Char('[') => Some(Command::HalveStepSize),
+        Key::Char('x') => Some(Command::ToggleMaxSpeed),
+        _ => None,
+    };
+    if let Some(command) = command {
+        return state.apply(command);
+    }
+
+    let viewport = view.viewport();
+    let camera = &mut view.camera;
+    match key {
+        Key::Char('q') | Key::Interrupt => view.quit = true,
+        Key::Char('R') => view.picker = Some(Picker::rules(&state)),
+        Key::Char('P') => view.picker = Some(Picker::patterns()),
+        Key::Char('g') => view.glyphs = view.glyphs.toggle(),
+        Key::Char('h') => camera.reset(),
+        Key::Char('f') => {
+            let (width, height) = state.grid.dimensions();
+            let rect = state.grid.bounding_box().unwrap_or((0, 0, width - 1, height - 1));
+            camera.fit_rect(rect, viewport, DOT_CELL_SIZE);
+        }
+        Key::Char('i') => camera.zoom_at(ZOOM_STEP, (viewport.0 / 2.0, viewport.1 / 2.0)),
+        Key::Char('o') => camera.zoom_at(1.0 / ZOOM_STEP, (viewport.0 / 2.0, viewport.1 / 2.0)),
+        // Moving the view left shifts the grid right, as with A in the window
+        Key::Left | Key::Char('a') => camera.pan(PAN_STEP, 0.0),
+        Key::Right | Key::Char('d') => camera.pan(-PAN_STEP, 0.0),
+        Key::Up | Key::Char('w') => camera.pan(0.0, PAN_STEP),
+        Key::Down | Key::Char('s') => camera.pan(0.0, -PAN_STEP),
+        Key::Char('A') => camera.pan(PAN_STEP * 3.0, 0.0),
+        Key::Char('D') => camera.pan(-PAN_STEP * 3.0, 0.0),
+        Key::Char('W') => camera.pan(0.0, PAN_STEP * 3.0),
+        Key::Char('S') => camera.pan(0.0, -PAN_STEP * 3.0),
+        _ => {}
+    }
+    state
+}
+
+/// Arrow keys (or j/k) move the selection, Enter applies it, Escape closes
+fn handle_picker_key(state: GameState, view: &mut TuiView, key: Key) -> GameState {
+    let Some(picker) = view.picker.as_mut() else {
+        return state;
+    };
+
+    match key {
+        Key::Up | Key::Char('k') => picker.move_by(-1),
+        Key::Down | Key::Char('j') => picker.move_by(1),
+        Key::Escape | Key::Char('q') => view.picker = None,
+        Key::Interrupt => view.quit = true,
+        Key::Enter => {
+            let picker = view.picker.take().expect("picker open");
+            return apply_pick(state, view, &picker);
+        }
+        _ => {}
+    }
+    state
+}
+
+fn apply_pick(state: GameState, view: &TuiView, picker: &Picker) -> GameState {
+    match picker.kind {
+        PickerKind::Rule => {
+            let (_, rule) = all_rules().into_iter().nth(picker.selected).expect("rule index");
+            state.apply(Command::SetRule(Arc::from(rule)))
+        }
+        PickerKind::Pattern => {
+            // Stamp the pattern at the center of the view
+            let pattern = presets::all_patterns().swap_remove(picker.selected);
+            let (width, height) = state.grid.dimensions();
+            let (vw, vh) = view.viewport();
+            let (cx, cy) = view.camera.screen_to_grid(vw / 2.0, vh / 2.0, DOT_CELL_SIZE);
+            let max_x = width.saturating_sub(pattern.width);
+            let max_y = height.saturating_sub(pattern.height);
+            let x = (cx as isize - pattern.width as isize / 2).clamp(0, max_x as isize) as usize;
+            let y = (cy as isize - pattern.height as isize / 2).clamp(0, max_y as isize) as usize;
+            state.apply(Command::PlacePattern { pattern, x, y })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_drive_game_state() {
+        let mut view = TuiView::new(40, 12);
+        let mut state = GameState::new(64, 64);
+
+        state = handle_key(state, &mut view, Key::Char(' '));
+        assert!(state.is_running);
+        state = handle_key(state, &mut view, Key::Char(' '));
+        state = handle_key(state, &mut view, Key::Char('n'));
+        assert!(!state.is_running);
+        assert_eq!(state.generation, 1);
+
+        handle_key(state, &mut view, Key::Char('q'));
+        assert!(view.quit);
+    }
+
+    #[test]
+    fn test_rule_picker_sets_rule() {
+        let mut view = TuiView::new(40, 12);
+        let mut state = GameState::new(64, 64);
+
+        state = handle_key(state, &mut view, Key::Char('R'));
+        assert_eq!(view.picker.as_ref().map(|p| p.selected), Some(0));
+        // Typing goes to the picker while it is open
+        state = handle_key(state, &mut view, Key::Down);
+        state = handle_key(state, &mut view, Key::Char('n'));
+        assert_eq!(state.generation, 0);
+        state = handle_key(state, &mut view, Key::Enter);
+
+        assert!(view.picker.is_none());
+        assert_eq!(state.rule.name(), "HighLife");
+    }
+
+    #[test]
+    fn test_pattern_picker_places_at_view_center() {
+        let mut view = TuiView::new(32, 10);
+        let mut state = GameState::new(64, 64);
+
+        state = handle_key(state, &mut view, Key::Char('P'));
+        state = handle_key(state, &mut view, Key::Enter);
+
+        // 32x8 characters of braille = 64x32 dots, centered on cell (32, 16)
+        let glider = presets::glider();
+        let (min_x, min_y, max_x, max_y) = state.grid.bounding_box().expect("pattern placed");
+        assert_eq!(state.population(), glider.cells.len());
+        assert!(min_x <= 32 && 32 <= max_x + 1, "x {}..={}", min_x, max_x);
+        assert!(min_y <= 16 && 16 <= max_y + 1, "y {}..={}", min_y, max_y);
+    }
+}
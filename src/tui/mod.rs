@@ -0,0 +1,56 @@
+//! Terminal frontend.
+//!
+//! Draws the grid with braille or half-block characters and drives the
+//! same `GameState` and `Camera` as the window, so simulations behave
+//! identically over SSH without a display. Only the terminal I/O lives
+//! here; every state change still goes through `Command`.
+
+pub mod canvas;
+pub mod terminal;
+mod input;
+mod screen;
+
+use crate::application::Camera;
+
+pub use canvas::{Glyphs, DOT_CELL_SIZE};
+pub use input::{handle_key, Picker, PickerKind};
+pub use screen::render_frame;
+pub use terminal::Key;
+
+/// Text rows below the grid (status and key help)
+pub const FOOTER_ROWS: usize = 2;
+
+/// Frontend state that is not part of the simulation
+pub struct TuiView {
+    pub camera: Camera,
+    pub glyphs: Glyphs,
+    /// Open rule or pattern list (None = closed)
+    pub picker: Option<Picker>,
+    /// Terminal size in characters
+    pub columns: usize,
+    pub rows: usize,
+    pub quit: bool,
+}
+
+impl TuiView {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Self {
+            camera: Camera::new(),
+            glyphs: Glyphs::default(),
+            picker: None,
+            columns,
+            rows,
+            quit: false,
+        }
+    }
+
+    /// Grid area size in dots, the camera's screen space
+    pub fn viewport(&self) -> (f32, f32) {
+        canvas::viewport_dots(self.glyphs, self.columns, self.grid_rows())
+    }
+
+    /// Text rows available to the grid
+    pub fn grid_rows(&self) -> usize {
+        self.rows.saturating_sub(FOOTER_ROWS)
+    }
+}
@@ -0,0 +1,92 @@
+//! Composes a full terminal frame: grid, picker overlay, status and help.
+
+use crate::application::GameState;
+use crate::rendering::{format_number, speed_label};
+use super::canvas::render_lines;
+use super::{Picker, TuiView};
+
+const HELP: &str =
+    "Space:Play N:Step R:Rand C:Clear WASD:Pan I/O:Zoom F:Fit H:Home +/-:Speed [ ]:Skip X:Max \
+     Shift+R:Rule Shift+P:Pattern G:Glyphs Q:Quit";
+
+const REVERSE: &str = "\x1b[7m";
+const RESET: &str = "\x1b[0m";
+
+/// Truncate or pad `text` to exactly `width` characters
+fn fit(text: &str, width: usize) -> String {
+    let mut line: String = text.chars().take(width).collect();
+    let len = line.chars().count();
+    line.extend(std::iter::repeat_n(' ', width - len));
+    line
+}
+
+fn status_line(state: &GameState, view: &TuiView) -> String {
+    let status = if state.is_running { "Running" } else { "Paused" };
+    let mut line = format!(
+        " {} | Gen {} | Pop {} | {} | {} | {} | Zoom {:.2}x | {}",
+        status,
+        format_number(state.generation as usize),
+        format_number(state.population()),
+        state.rule.name(),
+        state.algorithm.name(),
+        speed_label(state),
+        view.camera.zoom,
+        view.glyphs.name(),
+    );
+    if let Some(hit) = &state.breakpoint_hit {
+        line.push_str(&format!(" | Break: {}", hit.condition));
+    }
+    line
+}
+
+/// Draw `picker` over the top-left corner of the grid lines
+fn overlay_picker(lines: &mut [String], picker: &Picker) {
+    let width = picker.items.iter().map(|item| item.chars().count()).max().unwrap_or(0).max(picker.title().len()) + 4;
+    let title = format!(" {}:", picker.title());
+    let entries = std::iter::once((title, false)).chain(
+        picker.items.iter().enumerate().map(|(i, item)| (format!("  {}", item), i == picker.selected)),
+    );
+
+    for (line, (text, selected)) in lines.iter_mut().zip(entries) {
+        let rest: String = line.chars().skip(width).collect();
+        let boxed = fit(&text, width.min(line.chars().count()));
+        *line = if selected {
+            format!("{}{}{}{}", REVERSE, boxed, RESET, rest)
+        } else {
+            format!("{}{}", boxed, rest)
+        };
+    }
+}
+
+/// The whole screen as one string, starting with a cursor-home escape
+pub fn render_frame(state: &GameState, view: &TuiView) -> String {
+    let columns = view.columns;
+    let mut lines = render_lines(&state.grid, &view.camera, view.glyphs, columns, view.grid_rows());
+    if let Some(picker) = &view.picker {
+        overlay_picker(&mut lines, picker);
+    }
+    lines.push(format!("{}{}{}", REVERSE, fit(&status_line(state, view), columns), RESET));
+    lines.push(fit(HELP, columns));
+
+    format!("\x1b[H{}", lines.join("\r\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tui::Key;
+
+    #[test]
+    fn test_frame_fills_terminal() {
+        let mut view = TuiView::new(30, 8);
+        let state = crate::tui::handle_key(GameState::new(64, 64), &mut view, Key::Char('R'));
+
+        let frame = render_frame(&state, &view);
+        let lines: Vec<_> = frame.trim_start_matches("\x1b[H").split("\r\n").collect();
+        assert_eq!(lines.len(), 8);
+        assert!(lines[0].starts_with(" Rule:"));
+        assert!(lines[1].contains(REVERSE) && lines[1].contains("Conway"));
+        assert!(lines[6].contains("Paused | Gen 0"));
+        assert_eq!(lines[7].chars().count(), 30);
+    }
+}
@@ -0,0 +1,136 @@
+//! Minimal terminal control: raw mode through `stty`, ANSI escapes for
+//! the alternate screen, and decoding of key presses from stdin.
+
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// A decoded key press
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Backspace,
+    /// Ctrl+C (raw mode delivers it as a byte instead of a signal)
+    Interrupt,
+}
+
+/// Decode the bytes of one read from stdin
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let text = String::from_utf8_lossy(bytes);
+    let mut chars = text.chars().peekable();
+    let mut keys = Vec::new();
+
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+                // Skip parameters such as the "15" in "\x1b[15~"
+                while chars.next_if(|c| ('0'..='?').contains(c)).is_some() {}
+                match chars.next() {
+                    Some('A') => Key::Up,
+                    Some('B') => Key::Down,
+                    Some('C') => Key::Right,
+                    Some('D') => Key::Left,
+                    // Unsupported sequence (function keys, ...)
+                    _ => continue,
+                }
+            }
+            '\x1b' => Key::Escape,
+            '\r' | '\n' => Key::Enter,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x03' => Key::Interrupt,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// Run `stty` on the controlling terminal
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!("stty {} failed", args.join(" "))));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Terminal size as (columns, rows)
+pub fn size() -> Option<(usize, usize)> {
+    let text = stty(&["size"]).ok()?;
+    let (rows, columns) = text.split_once(' ')?;
+    Some((columns.parse().ok()?, rows.parse().ok()?))
+}
+
+/// Raw, no-echo input on the alternate screen with the cursor hidden.
+/// The previous terminal state is restored on drop.
+pub struct Session {
+    saved_mode: String,
+}
+
+impl Session {
+    pub fn start() -> io::Result<Self> {
+        let saved_mode = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        let mut out = io::stdout();
+        write!(out, "\x1b[?1049h\x1b[?25l\x1b[2J")?;
+        out.flush()?;
+        Ok(Self { saved_mode })
+    }
+}
+
+impl Drop for Session {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = write!(out, "\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = out.flush();
+        let _ = stty(&[&self.saved_mode]);
+    }
+}
+
+/// Read stdin on a background thread so the frame loop never blocks
+pub fn spawn_key_reader() -> Receiver<Vec<Key>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut stdin = io::stdin();
+        let mut buffer = [0u8; 64];
+        loop {
+            match stdin.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => {
+                    if sender.send(parse_keys(&buffer[..n])).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+    receiver
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_keys() {
+        assert_eq!(
+            parse_keys(b"a \x1b[A\x1b[D\r\x7f\x03"),
+            vec![Key::Char('a'), Key::Char(' '), Key::Up, Key::Left, Key::Enter, Key::Backspace, Key::Interrupt]
+        );
+        assert_eq!(parse_keys(b"\x1b"), vec![Key::Escape]);
+        // Unknown sequences are dropped without eating the next key
+        assert_eq!(parse_keys(b"\x1b[Hq\x1b[15~n"), vec![Key::Char('q'), Key::Char('n')]);
+    }
+}
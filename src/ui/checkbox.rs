@@ -0,0 +1,73 @@
+use macroquad::prelude::*;
+
+/// Labeled on/off toggle; clicking the box or the label flips it
+#[derive(Clone)]
+pub struct Checkbox {
+    x: f32,
+    y: f32,
+    width: f32,
+    label: String,
+    checked: bool,
+}
+
+impl Checkbox {
+    pub const HEIGHT: f32 = 20.0;
+    const BOX_SIZE: f32 = 14.0;
+
+    pub fn new(x: f32, y: f32, width: f32, label: impl Into<String>, checked: bool) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            label: label.into(),
+            checked,
+        }
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.checked
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    /// Update position for responsive layout
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Check if mouse is over the box or its label
+    pub fn is_hovered(&self, mouse_pos: (f32, f32)) -> bool {
+        mouse_pos.0 >= self.x
+            && mouse_pos.0 <= self.x + self.width
+            && mouse_pos.1 >= self.y
+            && mouse_pos.1 <= self.y + Self::HEIGHT
+    }
+
+    /// Toggle on click and return true if the state changed
+    pub fn update(&mut self, mouse_pos: (f32, f32)) -> bool {
+        if self.is_hovered(mouse_pos) && is_mouse_button_pressed(MouseButton::Left) {
+            self.checked = !self.checked;
+            return true;
+        }
+        false
+    }
+
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        let box_y = self.y + (Self::HEIGHT - Self::BOX_SIZE) / 2.0;
+        let border = if self.is_hovered(mouse_pos) {
+            Color::from_rgba(100, 149, 237, 255)
+        } else {
+            WHITE
+        };
+
+        draw_rectangle(self.x, box_y, Self::BOX_SIZE, Self::BOX_SIZE, Color::from_rgba(45, 45, 45, 255));
+        if self.checked {
+            draw_rectangle(self.x + 3.0, box_y + 3.0, Self::BOX_SIZE - 6.0, Self::BOX_SIZE - 6.0, Color::from_rgba(70, 130, 180, 255));
+        }
+        draw_rectangle_lines(self.x, box_y, Self::BOX_SIZE, Self::BOX_SIZE, 1.5, border);
+        draw_text(&self.label, self.x + Self::BOX_SIZE + 8.0, self.y + 15.0, 16.0, WHITE);
+    }
+}
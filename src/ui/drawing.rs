@@ -0,0 +1,125 @@
+use macroquad::prelude::*;
+
+use crate::domain::MAX_BRUSH_SIZE;
+use super::layout::{Rect, Stack};
+use super::{Button, Slider, PANEL_WIDTH};
+
+/// Width of the "Tool"/"Brush"/"Mirror" captions left of their buttons
+const CAPTION_WIDTH: f32 = 62.0;
+
+const ROW_HEIGHT: f32 = 26.0;
+
+/// Something the user changed in the drawing section
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DrawingEvent {
+    NextTool,
+    NextBrushShape,
+    NextSymmetry,
+    BrushSize(usize),
+}
+
+/// Widgets of the drawing section: buttons cycling through the tools,
+/// brush shapes and symmetry modes, and a brush size slider
+pub struct DrawingControls {
+    /// Names of the current tool, brush shape and symmetry
+    values: [String; 3],
+    /// Tool, brush shape and symmetry buttons (empty while collapsed)
+    buttons: Vec<Button>,
+    /// Rows of the buttons and their captions (empty while collapsed)
+    rows: Vec<Rect>,
+    brush_size: Slider,
+}
+
+impl DrawingControls {
+    const CAPTIONS: [&str; 3] = ["Tool", "Brush", "Mirror"];
+
+    pub fn new() -> Self {
+        Self {
+            values: Default::default(),
+            buttons: Vec::new(),
+            rows: Vec::new(),
+            brush_size: Slider::new(0.0, 0.0, PANEL_WIDTH, "Brush size", 1.0, MAX_BRUSH_SIZE as f32, 1.0)
+                .with_step(1.0),
+        }
+    }
+
+    /// Show the current tool, brush shape, symmetry and brush size
+    pub fn set_values(&mut self, tool: &str, brush_shape: &str, symmetry: &str, brush_size: usize) {
+        self.values = [tool.to_string(), brush_shape.to_string(), symmetry.to_string()];
+        self.brush_size.set_value(brush_size as f32);
+    }
+
+    /// Whether the brush size slider is being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.brush_size.is_dragging()
+    }
+
+    /// Lay the section out on `stack`
+    pub fn place(&mut self, stack: &mut Stack) {
+        self.rows = (0..self.values.len()).map(|_| stack.row(ROW_HEIGHT)).collect();
+        self.buttons = self.rows.iter().zip(&self.values).map(|(row, value)| {
+            Button::new(row.x + CAPTION_WIDTH, row.y, row.width - CAPTION_WIDTH, row.height, value.clone())
+        }).collect();
+        let row = stack.row(Slider::HEIGHT);
+        self.brush_size.set_position(row.x, row.y);
+    }
+
+    /// Forget the layout while the section is collapsed
+    pub fn hide(&mut self) {
+        self.buttons.clear();
+        self.rows.clear();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        !self.rows.is_empty()
+    }
+
+    /// Tooltip for the part of the section under the mouse
+    pub fn hint_at(&self, mouse_pos: (f32, f32)) -> Option<&'static str> {
+        const HINTS: [&str; 3] = [
+            "Click to switch tool; right-drag draws dead cells",
+            "Click to switch brush shape",
+            "Click to switch mirror symmetry",
+        ];
+        if let Some(i) = self.buttons.iter().position(|b| b.is_hovered(mouse_pos)) {
+            return Some(HINTS[i]);
+        }
+        (self.is_visible() && self.brush_size.is_hovered(mouse_pos)).then_some("Brush width in cells")
+    }
+
+    /// Handle clicks; returns what the user changed this frame
+    pub fn update(&mut self, mouse_pos: (f32, f32)) -> Vec<DrawingEvent> {
+        let mut events = Vec::new();
+        if !self.is_visible() {
+            return events;
+        }
+
+        let cycles = [DrawingEvent::NextTool, DrawingEvent::NextBrushShape, DrawingEvent::NextSymmetry];
+        for (button, event) in self.buttons.iter().zip(cycles) {
+            if button.is_clicked(mouse_pos) {
+                events.push(event);
+            }
+        }
+        if self.brush_size.update(mouse_pos) {
+            events.push(DrawingEvent::BrushSize(self.brush_size.value() as usize));
+        }
+        events
+    }
+
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        for (row, caption) in self.rows.iter().zip(Self::CAPTIONS) {
+            draw_text(caption, row.x + 2.0, row.y + 18.0, 16.0, WHITE);
+        }
+        self.buttons.iter().for_each(|button| button.draw(mouse_pos));
+        if !self.is_visible() {
+            return;
+        }
+        self.brush_size.draw(mouse_pos);
+    }
+}
+
+impl Default for DrawingControls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
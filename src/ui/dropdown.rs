@@ -1,6 +1,11 @@
 use macroquad::prelude::*;
 
-/// Dropdown selector UI component
+/// Dropdown selector UI component.
+///
+/// While open, typing filters the items (case-insensitive substring),
+/// Up/Down/PageUp/PageDown move the highlight, Enter picks it and Escape
+/// closes. Long lists show at most `max_visible` rows (fewer if the
+/// screen is short) and scroll with the mouse wheel.
 #[derive(Clone)]
 pub struct Dropdown {
     x: f32,
@@ -11,6 +16,14 @@ pub struct Dropdown {
     selected: usize,
     is_open: bool,
     label: String,
+    /// Most rows the open menu shows before scrolling
+    max_visible: usize,
+    /// Type-to-filter text, cleared whenever the menu opens or closes
+    query: String,
+    /// Keyboard cursor, as a position in the filtered list
+    highlighted: usize,
+    /// First filtered item shown in the menu
+    scroll: usize,
 }
 
 impl Dropdown {
@@ -24,161 +37,304 @@ impl Dropdown {
             selected: 0,
             is_open: false,
             label: label.into(),
+            max_visible: 10,
+            query: String::new(),
+            highlighted: 0,
+            scroll: 0,
         }
     }
-    
+
+    /// Show at most `rows` items at once (builder pattern)
+    pub fn with_max_visible(mut self, rows: usize) -> Self {
+        self.max_visible = rows.max(1);
+        self
+    }
+
     /// Get currently selected index
     pub fn selected(&self) -> usize {
         self.selected
     }
-    
+
     /// Set selected index
     pub fn set_selected(&mut self, index: usize) {
         if index < self.items.len() {
             self.selected = index;
         }
     }
-    
+
     /// Check if dropdown is open
     pub fn is_open(&self) -> bool {
         self.is_open
     }
-    
+
+    /// Open the menu with the current selection highlighted and in view
+    pub fn open(&mut self) {
+        self.is_open = true;
+        self.query.clear();
+        self.highlighted = self.selected;
+        self.scroll = 0;
+        self.scroll_to_highlight(self.visible_rows());
+        // Drop characters typed before opening so they don't become a search
+        while get_char_pressed().is_some() {}
+    }
+
     /// Close the dropdown
     pub fn close(&mut self) {
         self.is_open = false;
+        self.query.clear();
+    }
+
+    /// Current search text
+    pub fn query(&self) -> &str {
+        &self.query
     }
-    
+
     /// Update position for responsive layout
     pub fn set_position(&mut self, x: f32, y: f32) {
         self.x = x;
         self.y = y;
     }
-    
+
+    /// Indices of the items matching the search text
+    pub fn matches(&self) -> Vec<usize> {
+        let query = self.query.to_lowercase();
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| item.to_lowercase().contains(&query))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Append a character to the search and jump to the first match
+    pub fn push_query(&mut self, c: char) {
+        self.query.push(c);
+        self.highlighted = 0;
+        self.scroll = 0;
+    }
+
+    /// Remove the last search character
+    pub fn pop_query(&mut self) {
+        self.query.pop();
+        self.highlighted = 0;
+        self.scroll = 0;
+    }
+
+    /// Move the keyboard highlight by `delta` matches, scrolling it into
+    /// view in a menu of `rows` rows
+    pub fn move_highlight(&mut self, delta: isize, rows: usize) {
+        let count = self.matches().len();
+        if count == 0 {
+            return;
+        }
+        self.highlighted = self.highlighted.saturating_add_signed(delta).min(count - 1);
+        self.scroll_to_highlight(rows);
+    }
+
+    /// Scroll the menu by `delta` rows
+    pub fn scroll_by(&mut self, delta: isize, rows: usize) {
+        let max_scroll = self.matches().len().saturating_sub(rows);
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+
+    fn scroll_to_highlight(&mut self, rows: usize) {
+        if self.highlighted < self.scroll {
+            self.scroll = self.highlighted;
+        } else if self.highlighted >= self.scroll + rows {
+            self.scroll = self.highlighted + 1 - rows;
+        }
+    }
+
+    /// Select the highlighted match and close. Returns true if the
+    /// selection changed.
+    pub fn confirm(&mut self) -> bool {
+        let picked = self.matches().get(self.highlighted).copied();
+        self.close();
+        match picked {
+            Some(index) if index != self.selected => {
+                self.selected = index;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Menu rows that fit between the main button and `screen_height`,
+    /// capped at `max_visible`
+    fn rows_fitting(&self, screen_height: f32) -> usize {
+        let space = ((screen_height - self.y - self.height) / self.height).floor().max(1.0) as usize;
+        space.min(self.max_visible)
+    }
+
+    fn visible_rows(&self) -> usize {
+        self.rows_fitting(screen_height())
+    }
+
+    /// Number of menu rows drawn: the visible matches, or one row saying
+    /// nothing matched
+    fn shown_rows(&self, matches: usize) -> usize {
+        matches.saturating_sub(self.scroll).min(self.visible_rows()).max(1)
+    }
+
     /// Draw dropdown without handling interaction (for rendering only)
     pub fn draw(&self, mouse_pos: (f32, f32)) {
         // Draw label
         draw_text(&self.label, self.x, self.y - 5.0, 14.0, GRAY);
-        
+
         // Main button
         let button_color = if self.is_hovered_main(mouse_pos) {
             Color::from_rgba(100, 149, 237, 255)
         } else {
             Color::from_rgba(70, 130, 180, 255)
         };
-        
+
         draw_rectangle(self.x, self.y, self.width, self.height, button_color);
         draw_rectangle_lines(self.x, self.y, self.width, self.height, 2.0, WHITE);
-        
-        // Selected item text - truncate if too long
-        let text = &self.items[self.selected];
+
+        // Selected item text, or the search being typed
         let font_size = 16.0;
         let max_width = self.width - 30.0; // Leave space for arrow
-        
-        // Measure and potentially truncate text
-        let text_measure = measure_text(text, None, font_size as u16, 1.0);
-        let display_text = if text_measure.width > max_width {
-            // Truncate and add ellipsis
-            let mut truncated = text.clone();
-            while measure_text(&format!("{}...", truncated), None, font_size as u16, 1.0).width > max_width && truncated.len() > 0 {
-                truncated.pop();
-            }
-            format!("{}...", truncated)
+        if self.is_open && !self.query.is_empty() {
+            let text = truncate(&format!("{}_", self.query), max_width, font_size);
+            draw_text(&text, self.x + 5.0, self.y + 21.0, font_size, Color::from_rgba(255, 230, 120, 255));
         } else {
-            text.clone()
-        };
-        
-        draw_text(&display_text, self.x + 5.0, self.y + 21.0, font_size, WHITE);
-        
+            let text = truncate(&self.items[self.selected], max_width, font_size);
+            draw_text(&text, self.x + 5.0, self.y + 21.0, font_size, WHITE);
+        }
+
         // Dropdown arrow
         draw_text("▼", self.x + self.width - 18.0, self.y + 21.0, 14.0, WHITE);
-        
+
         // Draw dropdown menu if open
         if self.is_open {
-            // Draw opaque background for entire dropdown menu
-            let menu_height = self.items.len() as f32 * self.height;
-            draw_rectangle(
-                self.x,
-                self.y + self.height,
-                self.width,
-                menu_height,
-                Color::from_rgba(30, 30, 30, 255) // Fully opaque dark background
-            );
-            
-            for (i, item) in self.items.iter().enumerate() {
-                let item_y = self.y + self.height + (i as f32 * self.height);
-                
-                let item_color = if self.is_hovered_item(mouse_pos, i) {
-                    Color::from_rgba(100, 149, 237, 255) // Blue when hovered
-                } else if i == self.selected {
-                    Color::from_rgba(50, 100, 150, 255) // Darker blue when selected
-                } else {
-                    Color::from_rgba(45, 45, 45, 255) // Opaque gray for unselected
-                };
-                
-                draw_rectangle(self.x, item_y, self.width, self.height, item_color);
-                draw_rectangle_lines(self.x, item_y, self.width, self.height, 1.0, Color::from_rgba(80, 80, 80, 255));
-                
-                // Truncate item text too
-                let item_measure = measure_text(item, None, font_size as u16, 1.0);
-                let item_display = if item_measure.width > self.width - 10.0 {
-                    let mut truncated = item.clone();
-                    while measure_text(&format!("{}...", truncated), None, font_size as u16, 1.0).width > self.width - 10.0 && truncated.len() > 0 {
-                        truncated.pop();
-                    }
-                    format!("{}...", truncated)
-                } else {
-                    item.clone()
-                };
-                
-                draw_text(&item_display, self.x + 5.0, item_y + 21.0, font_size, WHITE);
-            }
-            
-            // Draw border around entire menu
-            draw_rectangle_lines(
-                self.x,
-                self.y + self.height,
-                self.width,
-                menu_height,
-                2.0,
-                WHITE
-            );
-        }
-    }
-    
+            self.draw_menu(mouse_pos, font_size);
+        }
+    }
+
+    fn draw_menu(&self, mouse_pos: (f32, f32), font_size: f32) {
+        let matches = self.matches();
+        let rows = self.shown_rows(matches.len());
+        let menu_y = self.y + self.height;
+        let menu_height = rows as f32 * self.height;
+
+        // Draw opaque background for entire dropdown menu
+        draw_rectangle(
+            self.x,
+            menu_y,
+            self.width,
+            menu_height,
+            Color::from_rgba(30, 30, 30, 255) // Fully opaque dark background
+        );
+
+        if matches.is_empty() {
+            draw_text("No matches", self.x + 5.0, menu_y + 21.0, font_size, GRAY);
+        }
+
+        for (row, &index) in matches.iter().skip(self.scroll).take(rows).enumerate() {
+            let item_y = menu_y + row as f32 * self.height;
+
+            let item_color = if self.is_hovered_item(mouse_pos, row) || self.scroll + row == self.highlighted {
+                Color::from_rgba(100, 149, 237, 255) // Blue when hovered
+            } else if index == self.selected {
+                Color::from_rgba(50, 100, 150, 255) // Darker blue when selected
+            } else {
+                Color::from_rgba(45, 45, 45, 255) // Opaque gray for unselected
+            };
+
+            draw_rectangle(self.x, item_y, self.width, self.height, item_color);
+            draw_rectangle_lines(self.x, item_y, self.width, self.height, 1.0, Color::from_rgba(80, 80, 80, 255));
+
+            let item_display = truncate(&self.items[index], self.width - 10.0, font_size);
+            draw_text(&item_display, self.x + 5.0, item_y + 21.0, font_size, WHITE);
+        }
+
+        // Scrollbar when not every match fits
+        if matches.len() > rows {
+            let thumb_height = (menu_height * rows as f32 / matches.len() as f32).max(10.0);
+            let thumb_y = menu_y + (menu_height - thumb_height) * self.scroll as f32 / (matches.len() - rows) as f32;
+            draw_rectangle(self.x + self.width - 5.0, thumb_y, 3.0, thumb_height, Color::from_rgba(200, 200, 200, 255));
+        }
+
+        // Draw border around entire menu
+        draw_rectangle_lines(
+            self.x,
+            menu_y,
+            self.width,
+            menu_height,
+            2.0,
+            WHITE
+        );
+    }
+
     /// Handle interaction and return true if selection changed
     pub fn update(&mut self, mouse_pos: (f32, f32)) -> bool {
-        let mut changed = false;
-        
         // Handle click on main button
         if self.is_hovered_main(mouse_pos) && is_mouse_button_pressed(MouseButton::Left) {
-            self.is_open = !self.is_open;
+            if self.is_open {
+                self.close();
+            } else {
+                self.open();
+            }
             return false; // Opening/closing dropdown is not a selection change
         }
-        
-        // Handle dropdown menu if open
-        if self.is_open {
-            for i in 0..self.items.len() {
-                // Handle item click
-                if self.is_hovered_item(mouse_pos, i) && is_mouse_button_pressed(MouseButton::Left) {
-                    if self.selected != i {
-                        self.selected = i;
-                        changed = true;
-                    }
-                    self.is_open = false;
-                    return changed;
-                }
+
+        if !self.is_open {
+            return false;
+        }
+        let rows = self.visible_rows();
+
+        // Type-to-filter and keyboard navigation
+        while let Some(c) = get_char_pressed() {
+            if !c.is_control() {
+                self.push_query(c);
             }
-            
-            // Close if clicked outside
-            if is_mouse_button_pressed(MouseButton::Left) && !self.is_hovered_any(mouse_pos) {
-                self.is_open = false;
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.pop_query();
+        }
+        let page = rows as isize;
+        let moves = [(KeyCode::Down, 1), (KeyCode::Up, -1), (KeyCode::PageDown, page), (KeyCode::PageUp, -page)];
+        for (key, delta) in moves {
+            if is_key_pressed(key) {
+                self.move_highlight(delta, rows);
             }
         }
-        
-        changed
+        if is_key_pressed(KeyCode::Escape) {
+            self.close();
+            return false;
+        }
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            return self.confirm();
+        }
+
+        // Scroll with the wheel anywhere over the dropdown
+        let wheel = mouse_wheel().1;
+        if wheel != 0.0 && self.is_hovered_any(mouse_pos) {
+            self.scroll_by(-wheel.signum() as isize, rows);
+        }
+
+        // Handle item click
+        let matches = self.matches();
+        for row in 0..self.shown_rows(matches.len()) {
+            if self.is_hovered_item(mouse_pos, row) && is_mouse_button_pressed(MouseButton::Left) {
+                let Some(&index) = matches.get(self.scroll + row) else {
+                    return false;
+                };
+                let changed = self.selected != index;
+                self.selected = index;
+                self.close();
+                return changed;
+            }
+        }
+
+        // Close if clicked outside
+        if is_mouse_button_pressed(MouseButton::Left) && !self.is_hovered_any(mouse_pos) {
+            self.close();
+        }
+
+        false
     }
-    
+
     /// Draw dropdown and handle interaction (convenience method)
     /// Returns true if selection changed
     pub fn draw_and_update(&mut self, mouse_pos: (f32, f32)) -> bool {
@@ -186,31 +342,98 @@ impl Dropdown {
         self.draw(mouse_pos);
         changed
     }
-    
+
     fn is_hovered_main(&self, mouse_pos: (f32, f32)) -> bool {
-        mouse_pos.0 >= self.x 
-            && mouse_pos.0 <= self.x + self.width 
-            && mouse_pos.1 >= self.y 
+        mouse_pos.0 >= self.x
+            && mouse_pos.0 <= self.x + self.width
+            && mouse_pos.1 >= self.y
             && mouse_pos.1 <= self.y + self.height
     }
-    
-    fn is_hovered_item(&self, mouse_pos: (f32, f32), index: usize) -> bool {
-        let item_y = self.y + self.height + (index as f32 * self.height);
-        mouse_pos.0 >= self.x 
-            && mouse_pos.0 <= self.x + self.width 
-            && mouse_pos.1 >= item_y 
+
+    /// Whether the mouse is over the `row`th visible menu row
+    fn is_hovered_item(&self, mouse_pos: (f32, f32), row: usize) -> bool {
+        let item_y = self.y + self.height + (row as f32 * self.height);
+        mouse_pos.0 >= self.x
+            && mouse_pos.0 <= self.x + self.width
+            && mouse_pos.1 >= item_y
             && mouse_pos.1 <= item_y + self.height
     }
-    
+
     fn is_hovered_any(&self, mouse_pos: (f32, f32)) -> bool {
         if self.is_hovered_main(mouse_pos) {
             return true;
         }
-        for i in 0..self.items.len() {
-            if self.is_hovered_item(mouse_pos, i) {
-                return true;
-            }
+        let rows = self.shown_rows(self.matches().len());
+        (0..rows).any(|row| self.is_hovered_item(mouse_pos, row))
+    }
+}
+
+/// `text` shortened with an ellipsis to fit `max_width`
+fn truncate(text: &str, max_width: f32, font_size: f32) -> String {
+    if measure_text(text, None, font_size as u16, 1.0).width <= max_width {
+        return text.to_string();
+    }
+    let mut truncated = text.to_string();
+    while measure_text(&format!("{}...", truncated), None, font_size as u16, 1.0).width > max_width && !truncated.is_empty() {
+        truncated.pop();
+    }
+    format!("{}...", truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dropdown(items: &[&str]) -> Dropdown {
+        let items = items.iter().map(|s| s.to_string()).collect();
+        Dropdown::new(0.0, 0.0, 100.0, "Pattern", items)
+    }
+
+    #[test]
+    fn test_search_filters_case_insensitively() {
+        let mut dropdown = dropdown(&["Glider", "Blinker", "Glider Gun", "LWSS"]);
+        for c in "gli".chars() {
+            dropdown.push_query(c);
         }
-        false
+        assert_eq!(dropdown.matches(), vec![0, 2]);
+
+        dropdown.move_highlight(1, 5);
+        assert!(dropdown.confirm());
+        assert_eq!(dropdown.selected(), 2);
+        assert_eq!(dropdown.query(), "");
+
+        dropdown.push_query('z');
+        assert!(dropdown.matches().is_empty());
+        assert!(!dropdown.confirm());
+        assert_eq!(dropdown.selected(), 2);
+    }
+
+    #[test]
+    fn test_highlight_scrolls_into_view() {
+        let names: Vec<String> = (0..20).map(|i| format!("Item {}", i)).collect();
+        let refs: Vec<&str> = names.iter().map(String::as_str).collect();
+        let mut dropdown = dropdown(&refs).with_max_visible(5);
+
+        dropdown.move_highlight(7, 5);
+        assert_eq!((dropdown.highlighted, dropdown.scroll), (7, 3));
+        dropdown.move_highlight(100, 5);
+        assert_eq!((dropdown.highlighted, dropdown.scroll), (19, 15));
+        dropdown.move_highlight(-18, 5);
+        assert_eq!((dropdown.highlighted, dropdown.scroll), (1, 1));
+
+        dropdown.scroll_by(-5, 5);
+        assert_eq!(dropdown.scroll, 0);
+        dropdown.scroll_by(50, 5);
+        assert_eq!(dropdown.scroll, 15);
+    }
+
+    #[test]
+    fn test_rows_capped_by_screen_space() {
+        let mut dropdown = dropdown(&["a"; 30]).with_max_visible(12);
+        dropdown.set_position(0.0, 100.0);
+        assert_eq!(dropdown.rows_fitting(1000.0), 12);
+        // 270px below the button fits 9 rows of 30px
+        assert_eq!(dropdown.rows_fitting(400.0), 9);
+        assert_eq!(dropdown.rows_fitting(120.0), 1);
     }
 }
@@ -0,0 +1,261 @@
+//! Layout building blocks for the side panel: rectangles, a vertical
+//! stack that hands out rows top to bottom, collapsible sections and a
+//! scroll area for content taller than the window.
+
+use macroquad::prelude::*;
+
+/// Axis-aligned rectangle in screen coordinates
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Rect {
+    pub const fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    pub fn contains(&self, pos: (f32, f32)) -> bool {
+        pos.0 >= self.x && pos.0 <= self.x + self.width && pos.1 >= self.y && pos.1 <= self.bottom()
+    }
+
+    /// Whether any part of the rectangle overlaps `other`
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.bottom()
+            && other.y < self.bottom()
+    }
+}
+
+/// Full-width rows stacked top to bottom with a fixed gap between them
+#[derive(Clone, Debug)]
+pub struct Stack {
+    x: f32,
+    top: f32,
+    width: f32,
+    spacing: f32,
+    cursor: f32,
+}
+
+impl Stack {
+    pub fn new(x: f32, y: f32, width: f32, spacing: f32) -> Self {
+        Self { x, top: y, width, spacing, cursor: y }
+    }
+
+    /// Claim the next row of `height` pixels
+    pub fn row(&mut self, height: f32) -> Rect {
+        let rect = Rect::new(self.x, self.cursor, self.width, height);
+        self.cursor += height + self.spacing;
+        rect
+    }
+
+    /// Leave `height` pixels empty
+    pub fn space(&mut self, height: f32) {
+        self.cursor += height;
+    }
+
+    pub fn width(&self) -> f32 {
+        self.width
+    }
+
+    /// Height used so far, including the gap after the last row
+    pub fn height(&self) -> f32 {
+        self.cursor - self.top
+    }
+}
+
+/// Clickable header that shows or hides the rows placed after it
+#[derive(Clone, Debug)]
+pub struct Section {
+    title: String,
+    collapsed: bool,
+    header: Rect,
+}
+
+impl Section {
+    pub const HEADER_HEIGHT: f32 = 22.0;
+
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            collapsed: false,
+            header: Rect::default(),
+        }
+    }
+
+    /// Start collapsed (builder pattern)
+    pub fn collapsed(mut self) -> Self {
+        self.collapsed = true;
+        self
+    }
+
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+    }
+
+    /// Place the header on `stack`; returns true if the section's
+    /// contents should be placed after it
+    pub fn place(&mut self, stack: &mut Stack) -> bool {
+        self.header = stack.row(Self::HEADER_HEIGHT);
+        !self.collapsed
+    }
+
+    /// Toggle on a header click; returns true if the section toggled
+    pub fn update(&mut self, mouse_pos: (f32, f32)) -> bool {
+        if self.header.contains(mouse_pos) && is_mouse_button_pressed(MouseButton::Left) {
+            self.collapsed = !self.collapsed;
+            return true;
+        }
+        false
+    }
+
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        let Rect { x, y, width, height } = self.header;
+        let color = if self.header.contains(mouse_pos) {
+            Color::from_rgba(60, 60, 60, 255)
+        } else {
+            Color::from_rgba(45, 45, 45, 255)
+        };
+        draw_rectangle(x, y, width, height, color);
+        draw_line(x, y + height, x + width, y + height, 1.0, Color::from_rgba(80, 80, 80, 255));
+
+        let marker = if self.collapsed { "+" } else { "-" };
+        draw_text(marker, x + 6.0, y + 16.0, 18.0, GRAY);
+        draw_text(&self.title, x + 20.0, y + 16.0, 16.0, WHITE);
+    }
+}
+
+/// Scrolled window onto a stack that may be taller than the viewport.
+/// Content is laid out between `begin` and `end` each frame; the height
+/// measured by `end` bounds the scroll offset.
+#[derive(Clone, Debug, Default)]
+pub struct ScrollArea {
+    viewport: Rect,
+    offset: f32,
+    content_height: f32,
+}
+
+impl ScrollArea {
+    /// Pixels scrolled per wheel notch
+    pub const WHEEL_STEP: f32 = 40.0;
+    pub const SCROLLBAR_WIDTH: f32 = 4.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start laying out content for `viewport`, shifted by the scroll offset
+    pub fn begin(&mut self, viewport: Rect, spacing: f32) -> Stack {
+        self.viewport = viewport;
+        Stack::new(viewport.x, viewport.y - self.offset, viewport.width, spacing)
+    }
+
+    /// Record how tall the content laid out on `stack` was
+    pub fn end(&mut self, stack: &Stack) {
+        self.content_height = stack.height();
+        self.offset = self.offset.clamp(0.0, self.max_offset());
+    }
+
+    pub fn offset(&self) -> f32 {
+        self.offset
+    }
+
+    /// How far the content can scroll (0 when it fits)
+    pub fn max_offset(&self) -> f32 {
+        (self.content_height - self.viewport.height).max(0.0)
+    }
+
+    pub fn scroll_by(&mut self, delta: f32) {
+        self.offset = (self.offset + delta).clamp(0.0, self.max_offset());
+    }
+
+    /// Whether `rect` (in laid-out coordinates) is at least partly visible
+    pub fn is_visible(&self, rect: &Rect) -> bool {
+        rect.intersects(&self.viewport)
+    }
+
+    /// Scroll with the mouse wheel while the cursor is over the viewport.
+    /// Returns true if the wheel was used, so it doesn't also zoom.
+    pub fn handle_wheel(&mut self, mouse_pos: (f32, f32)) -> bool {
+        let wheel = mouse_wheel().1;
+        if wheel == 0.0 || !self.viewport.contains(mouse_pos) || self.max_offset() == 0.0 {
+            return false;
+        }
+        self.scroll_by(-wheel.signum() * Self::WHEEL_STEP);
+        true
+    }
+
+    /// Thin scrollbar along the right edge, only when content overflows
+    pub fn draw_scrollbar(&self) {
+        if self.max_offset() == 0.0 {
+            return;
+        }
+        let Rect { x, y, width, height } = self.viewport;
+        let thumb_height = (height * height / self.content_height).max(20.0);
+        let thumb_y = y + (height - thumb_height) * self.offset / self.max_offset();
+        let bar_x = x + width - Self::SCROLLBAR_WIDTH;
+        draw_rectangle(bar_x, y, Self::SCROLLBAR_WIDTH, height, Color::from_rgba(40, 40, 40, 255));
+        draw_rectangle(bar_x, thumb_y, Self::SCROLLBAR_WIDTH, thumb_height, Color::from_rgba(120, 120, 120, 255));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_hands_out_rows() {
+        let mut stack = Stack::new(10.0, 20.0, 100.0, 5.0);
+        assert_eq!(stack.row(30.0), Rect::new(10.0, 20.0, 100.0, 30.0));
+        stack.space(10.0);
+        assert_eq!(stack.row(8.0), Rect::new(10.0, 65.0, 100.0, 8.0));
+        assert_eq!(stack.height(), 58.0);
+    }
+
+    #[test]
+    fn test_collapsed_section_skips_contents() {
+        let mut stack = Stack::new(0.0, 0.0, 100.0, 0.0);
+        let mut section = Section::new("Run").collapsed();
+        assert!(!section.place(&mut stack));
+        assert_eq!(stack.height(), Section::HEADER_HEIGHT);
+
+        section.set_collapsed(false);
+        assert!(section.place(&mut stack));
+    }
+
+    #[test]
+    fn test_scroll_offset_bounded_by_content() {
+        let mut scroll = ScrollArea::new();
+        let viewport = Rect::new(0.0, 0.0, 100.0, 200.0);
+
+        let mut stack = scroll.begin(viewport, 0.0);
+        stack.row(500.0);
+        scroll.end(&stack);
+        assert_eq!(scroll.max_offset(), 300.0);
+
+        scroll.scroll_by(1000.0);
+        assert_eq!(scroll.offset(), 300.0);
+        let mut stack = scroll.begin(viewport, 0.0);
+        let row = stack.row(500.0);
+        assert_eq!(row.y, -300.0);
+        assert!(scroll.is_visible(&row));
+
+        // Content shrinking below the viewport pulls the offset back
+        let mut stack = scroll.begin(viewport, 0.0);
+        stack.row(150.0);
+        scroll.end(&stack);
+        assert_eq!(scroll.offset(), 0.0);
+    }
+}
@@ -1,12 +1,32 @@
 mod button;
 mod dropdown;
+mod slider;
+mod checkbox;
+mod text_input;
+mod tooltip;
+pub mod layout;
+mod panel;
+mod rule_editor;
+mod drawing;
+mod status_line;
 
 pub use button::Button;
 pub use dropdown::Dropdown;
+pub use slider::Slider;
+pub use checkbox::Checkbox;
+pub use text_input::TextInput;
+pub use tooltip::{Tooltip, TOOLTIP_DELAY};
+pub use layout::Rect;
+pub use panel::{Panel, PanelLayout, DROPDOWN_LABEL_HEIGHT};
+pub use rule_editor::{RuleEditorControls, RuleEditorEvent};
+pub use drawing::{DrawingControls, DrawingEvent};
+pub use status_line::{StatusLine, STATUS_DURATION};
 
 // UI constants - now functions for responsive layout
 use macroquad::prelude::{screen_width, screen_height};
 
+use crate::domain::MAX_GRID_CELLS;
+
 pub const PANEL_WIDTH: f32 = 180.0;
 pub const BUTTON_HEIGHT: f32 = 40.0;
 pub const CELL_SIZE: f32 = 10.0;
@@ -27,17 +47,44 @@ pub fn grid_area_height() -> f32 {
 }
 
 /// Grid size options - including stress test sizes
-pub const GRID_SIZES: &[(usize, &str)] = &[
-    (50, "50×50"),
-    (100, "100×100"),
-    (200, "200×200"),
-    (500, "500×500"),
-    (1000, "1000×1000"),
-    (2000, "2K×2K"),
-    (5000, "5K×5K"),
-    (10000, "10K×10K"),
+pub const GRID_SIZES: &[((usize, usize), &str)] = &[
+    ((50, 50), "50×50"),
+    ((100, 100), "100×100"),
+    ((200, 200), "200×200"),
+    ((500, 500), "500×500"),
+    ((1000, 1000), "1000×1000"),
+    ((1920, 1080), "1920×1080"),
+    ((2000, 2000), "2K×2K"),
+    ((5000, 5000), "5K×5K"),
+    ((10000, 10000), "10K×10K"),
 ];
 
+/// Label of the grid size entry after the presets, for sizes typed in
+pub const CUSTOM_GRID_SIZE: &str = "Custom…";
+
+/// Parse a grid size typed as "WxH" (also "W×H" or "W X H")
+pub fn parse_grid_size(text: &str) -> Result<(usize, usize), String> {
+    let (w, h) = text
+        .split_once(['x', 'X', '×'])
+        .ok_or_else(|| format!("expected WxH, got '{}'", text))?;
+    let parse = |part: &str| part.trim().parse::<usize>().map_err(|_| format!("invalid number '{}'", part.trim()));
+    let (width, height) = (parse(w)?, parse(h)?);
+
+    if width == 0 || height == 0 {
+        return Err("dimensions must be positive".to_string());
+    }
+    if width.saturating_mul(height) > MAX_GRID_CELLS {
+        return Err(format!("{}x{} is larger than {} cells", width, height, MAX_GRID_CELLS));
+    }
+    Ok((width, height))
+}
+
+/// Index of `size` in the grid size dropdown: its preset, or the custom
+/// entry after the presets
+pub fn grid_size_index(size: (usize, usize)) -> usize {
+    GRID_SIZES.iter().position(|(preset, _)| *preset == size).unwrap_or(GRID_SIZES.len())
+}
+
 /// Algorithm names for dropdown - matches Algorithm::all() order
 /// Explicit naming: Naive (1 byte/cell), BitPacked (1 bit/cell), BitSIMD (bit-packed + SIMD)
 pub const ALGORITHMS: &[&str] = &[
@@ -51,14 +98,34 @@ pub const ALGORITHMS: &[&str] = &[
     "TempBlock+Par",
 ];
 
-/// Create UI buttons with standard layout
-/// Button positions adjusted to make room for algorithm dropdown
-pub fn create_buttons() -> Vec<Button> {
-    let px = panel_x();
-    vec![
-        Button::new(px, 470.0, PANEL_WIDTH, BUTTON_HEIGHT, "Play/Pause"),
-        Button::new(px, 520.0, PANEL_WIDTH, BUTTON_HEIGHT, "Clear"),
-        Button::new(px, 570.0, PANEL_WIDTH, BUTTON_HEIGHT, "Random"),
-    ]
+/// Create the Play/Pause, Clear and Random buttons where the panel
+/// layout puts them (none while their section is collapsed)
+pub fn create_buttons(layout: &PanelLayout) -> Vec<Button> {
+    let labels = ["Play/Pause", "Clear", "Random"];
+    layout.buttons.map_or_else(Vec::new, |rects| {
+        rects.iter()
+            .zip(labels)
+            .map(|(r, label)| Button::new(r.x, r.y, r.width, r.height, label))
+            .collect()
+    })
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_grid_size() {
+        assert_eq!(parse_grid_size("1920x1080"), Ok((1920, 1080)));
+        assert_eq!(parse_grid_size(" 4096 X 64 "), Ok((4096, 64)));
+        assert_eq!(parse_grid_size("63×65"), Ok((63, 65)));
+        assert!(parse_grid_size("100").is_err());
+        assert!(parse_grid_size("0x10").is_err());
+        assert!(parse_grid_size("ax10").is_err());
+        assert!(parse_grid_size("100000x100000").is_err());
+
+        assert_eq!(grid_size_index((100, 100)), 1);
+        assert_eq!(grid_size_index((63, 65)), GRID_SIZES.len());
+    }
+}
@@ -0,0 +1,303 @@
+//! Arrangement of the right-hand control panel. Every frame `arrange`
+//! stacks the sections top to bottom for the current window size and
+//! scroll offset; widgets and text blocks in a collapsed section get no
+//! rectangle and are neither drawn nor clickable.
+
+use macroquad::prelude::*;
+
+use super::layout::{Rect, ScrollArea, Section};
+use super::{Checkbox, DrawingControls, RuleEditorControls, Slider, StatusLine, TextInput, Tooltip, panel_x, BUTTON_HEIGHT, PANEL_WIDTH};
+
+/// Gap between rows
+const SPACING: f32 = 6.0;
+
+/// Dropdown rows hold the label above a 30px box
+const DROPDOWN_ROW_HEIGHT: f32 = 48.0;
+
+/// Offset of a dropdown's box below the top of its row
+pub const DROPDOWN_LABEL_HEIGHT: f32 = 18.0;
+
+/// Heights of the text blocks drawn by the renderer
+const STATUS_HEIGHT: f32 = 170.0;
+const INFO_HEIGHT: f32 = 136.0;
+const HELP_HEIGHT: f32 = 97.0;
+
+/// Where everything in the panel goes this frame (None = section collapsed)
+#[derive(Clone, Debug, Default)]
+pub struct PanelLayout {
+    /// Grid size, rule, algorithm and pattern dropdown rows
+    pub dropdowns: Option<[Rect; 4]>,
+    /// Width × height entry below the dropdowns
+    pub grid_size: Option<Rect>,
+    pub resize_centered: Option<Rect>,
+    pub auto_expand: Option<Rect>,
+    /// Play/Pause, Clear and Random
+    pub buttons: Option<[Rect; 3]>,
+    pub speed: Option<Rect>,
+    /// Generations per update entry
+    pub step_size: Option<Rect>,
+    pub stats_graph: Option<Rect>,
+    pub inspector: Option<Rect>,
+    /// Run-until condition entry
+    pub run_until: Option<Rect>,
+    /// Speed, generation, run status and zoom readouts
+    pub status: Option<Rect>,
+    /// Grid size, population and performance figures
+    pub info: Option<Rect>,
+    /// Key bindings
+    pub help: Option<Rect>,
+}
+
+pub struct Panel {
+    scroll: ScrollArea,
+    simulation: Section,
+    rules: Section,
+    drawing: Section,
+    run: Section,
+    status: Section,
+    info: Section,
+    help: Section,
+    /// Any grid size, typed as WxH
+    pub grid_size: TextInput,
+    /// Resize around the middle of the grid instead of its top-left corner
+    pub resize_centered: Checkbox,
+    /// Grow the grid when live cells near an edge
+    pub auto_expand: Checkbox,
+    /// Updates per second
+    pub speed: Slider,
+    /// Generations per update, any number rather than only the powers of
+    /// two the keys step through
+    pub step_size: TextInput,
+    /// Population graph overlay
+    pub stats_graph: Checkbox,
+    /// Cell readout on hover
+    pub inspector: Checkbox,
+    /// Condition to run until, e.g. "pop < 100"
+    pub run_until: TextInput,
+    pub rule_editor: RuleEditorControls,
+    pub drawing_tools: DrawingControls,
+    /// Outcome of the last export, snapshot or reload
+    pub status_line: StatusLine,
+    tooltip: Tooltip,
+    layout: PanelLayout,
+}
+
+impl Panel {
+    pub fn new() -> Self {
+        Self {
+            scroll: ScrollArea::new(),
+            simulation: Section::new("Simulation"),
+            rules: Section::new("Rule editor").collapsed(),
+            drawing: Section::new("Drawing"),
+            run: Section::new("Run"),
+            status: Section::new("Status"),
+            info: Section::new("Performance"),
+            help: Section::new("Controls"),
+            grid_size: TextInput::new(0.0, 0.0, PANEL_WIDTH, "Size (WxH, Enter applies)")
+                .with_filter(|c| c.is_ascii_digit() || matches!(c, 'x' | 'X' | '×'))
+                .with_max_len(16),
+            resize_centered: Checkbox::new(0.0, 0.0, PANEL_WIDTH, "Resize from center", true),
+            auto_expand: Checkbox::new(0.0, 0.0, PANEL_WIDTH, "Auto-expand", false),
+            speed: Slider::new(0.0, 0.0, PANEL_WIDTH, "Updates/s", 1.0, 60.0, 10.0).with_step(1.0),
+            step_size: TextInput::new(0.0, 0.0, PANEL_WIDTH, "Step size (Enter applies)")
+                .with_filter(|c| c.is_ascii_digit())
+                .with_max_len(8),
+            stats_graph: Checkbox::new(0.0, 0.0, PANEL_WIDTH, "Population graph", false),
+            inspector: Checkbox::new(0.0, 0.0, PANEL_WIDTH, "Cell inspector", false),
+            run_until: TextInput::new(0.0, 0.0, PANEL_WIDTH, "Run until (Enter starts)").with_max_len(32),
+            rule_editor: RuleEditorControls::new(),
+            drawing_tools: DrawingControls::new(),
+            status_line: StatusLine::new(),
+            tooltip: Tooltip::new(),
+            layout: PanelLayout::default(),
+        }
+    }
+
+    /// Lay the panel out for the current window size and scroll offset
+    pub fn arrange(&mut self) -> &PanelLayout {
+        let viewport = Rect::new(panel_x(), 0.0, PANEL_WIDTH, screen_height());
+        let mut stack = self.scroll.begin(viewport, SPACING);
+        let mut layout = PanelLayout::default();
+        stack.space(4.0);
+
+        if self.simulation.place(&mut stack) {
+            layout.dropdowns = Some(std::array::from_fn(|_| stack.row(DROPDOWN_ROW_HEIGHT)));
+            layout.grid_size = Some(stack.row(DROPDOWN_LABEL_HEIGHT + TextInput::HEIGHT));
+            layout.resize_centered = Some(stack.row(Checkbox::HEIGHT));
+            layout.auto_expand = Some(stack.row(Checkbox::HEIGHT));
+        }
+        if self.rules.place(&mut stack) {
+            self.rule_editor.place(&mut stack);
+        } else {
+            self.rule_editor.hide();
+        }
+        if self.drawing.place(&mut stack) {
+            self.drawing_tools.place(&mut stack);
+        } else {
+            self.drawing_tools.hide();
+        }
+        if self.run.place(&mut stack) {
+            layout.buttons = Some(std::array::from_fn(|_| stack.row(BUTTON_HEIGHT)));
+            layout.speed = Some(stack.row(Slider::HEIGHT));
+            layout.step_size = Some(stack.row(DROPDOWN_LABEL_HEIGHT + TextInput::HEIGHT));
+            layout.stats_graph = Some(stack.row(Checkbox::HEIGHT));
+            layout.inspector = Some(stack.row(Checkbox::HEIGHT));
+            layout.run_until = Some(stack.row(DROPDOWN_LABEL_HEIGHT + TextInput::HEIGHT));
+        }
+        if self.status.place(&mut stack) {
+            layout.status = Some(stack.row(STATUS_HEIGHT));
+        }
+        if self.info.place(&mut stack) {
+            layout.info = Some(stack.row(INFO_HEIGHT));
+        }
+        if self.help.place(&mut stack) {
+            layout.help = Some(stack.row(HELP_HEIGHT));
+        }
+        self.scroll.end(&stack);
+
+        for (input, rect) in [
+            (&mut self.grid_size, layout.grid_size),
+            (&mut self.step_size, layout.step_size),
+            (&mut self.run_until, layout.run_until),
+        ] {
+            match rect {
+                Some(rect) => input.set_position(rect.x, rect.y + DROPDOWN_LABEL_HEIGHT),
+                None => input.blur(),
+            }
+        }
+        for (checkbox, rect) in [(&mut self.resize_centered, layout.resize_centered), (&mut self.auto_expand, layout.auto_expand)] {
+            if let Some(rect) = rect {
+                checkbox.set_position(rect.x, rect.y);
+            }
+        }
+        if let Some(rect) = layout.speed {
+            self.speed.set_position(rect.x, rect.y);
+        }
+        for (checkbox, rect) in [(&mut self.stats_graph, layout.stats_graph), (&mut self.inspector, layout.inspector)] {
+            if let Some(rect) = rect {
+                checkbox.set_position(rect.x, rect.y);
+            }
+        }
+        self.layout = layout;
+        &self.layout
+    }
+
+    pub fn layout(&self) -> &PanelLayout {
+        &self.layout
+    }
+
+    /// Whether a text field in the panel has keyboard focus
+    pub fn is_typing(&self) -> bool {
+        self.grid_size.is_focused()
+            || self.step_size.is_focused()
+            || self.run_until.is_focused()
+            || self.rule_editor.is_typing()
+    }
+
+    /// Section toggles, scrolling and tooltips. `covered` is true while an
+    /// open dropdown menu lies over the panel and takes the clicks and the
+    /// wheel. Returns
+    /// true if the mouse wheel scrolled the panel (and so should not zoom
+    /// the grid).
+    pub fn handle_input(&mut self, mouse_pos: (f32, f32), delta_time: f32, covered: bool) -> bool {
+        if !covered {
+            for section in [&mut self.simulation, &mut self.rules, &mut self.drawing, &mut self.run, &mut self.status, &mut self.info, &mut self.help] {
+                section.update(mouse_pos);
+            }
+        }
+        self.status_line.update(delta_time);
+        let hint = self.hint_at(mouse_pos).filter(|_| !covered);
+        self.tooltip.update(hint, mouse_pos, delta_time);
+        !covered && self.scroll.handle_wheel(mouse_pos)
+    }
+
+    /// Tooltip text for the widget under the mouse
+    fn hint_at(&self, mouse_pos: (f32, f32)) -> Option<&'static str> {
+        const BUTTON_HINTS: [&str; 3] = ["Start or pause (Space)", "Kill every cell (C)", "Random fill (R)"];
+        let layout = &self.layout;
+        let hovered = |rect: Option<Rect>| rect.is_some_and(|r| r.contains(mouse_pos));
+
+        if let Some(buttons) = layout.buttons
+            && let Some(i) = buttons.iter().position(|b| b.contains(mouse_pos))
+        {
+            return Some(BUTTON_HINTS[i]);
+        }
+        if hovered(layout.grid_size) {
+            return Some("Any width × height, e.g. 1920x1080");
+        }
+        if hovered(layout.resize_centered) {
+            return Some("Keep the middle in place when resizing (else the top-left)");
+        }
+        if hovered(layout.auto_expand) {
+            return Some("Grow the grid when live cells near an edge");
+        }
+        if hovered(layout.speed) {
+            return Some("Generations per second at step size 1 (+/-)");
+        }
+        if hovered(layout.step_size) {
+            return Some("Generations per update, 1 to 1048576 ([ and ] halve and double)");
+        }
+        if hovered(layout.stats_graph) {
+            return Some("Population over time (G)");
+        }
+        if hovered(layout.inspector) {
+            return Some("Neighbors, fate and object of the hovered cell (I)");
+        }
+        if hovered(layout.run_until) {
+            return Some("gen 500, pop < 100, pop > 2000, region 10,10 40,30, cell 5,5 or stable");
+        }
+        self.rule_editor.hint_at(mouse_pos).or_else(|| self.drawing_tools.hint_at(mouse_pos))
+    }
+
+    /// Section headers and the panel's own widgets
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        for section in [&self.simulation, &self.rules, &self.drawing, &self.run, &self.status, &self.info, &self.help] {
+            section.draw(mouse_pos);
+        }
+        if self.layout.grid_size.is_some() {
+            self.grid_size.draw(mouse_pos);
+        }
+        if self.layout.resize_centered.is_some() {
+            self.resize_centered.draw(mouse_pos);
+        }
+        if self.layout.auto_expand.is_some() {
+            self.auto_expand.draw(mouse_pos);
+        }
+        if self.layout.speed.is_some() {
+            self.speed.draw(mouse_pos);
+        }
+        if self.layout.step_size.is_some() {
+            self.step_size.draw(mouse_pos);
+        }
+        if self.layout.stats_graph.is_some() {
+            self.stats_graph.draw(mouse_pos);
+        }
+        if self.layout.inspector.is_some() {
+            self.inspector.draw(mouse_pos);
+        }
+        if self.layout.run_until.is_some() {
+            self.run_until.draw(mouse_pos);
+        }
+        self.rule_editor.draw(mouse_pos);
+        self.drawing_tools.draw(mouse_pos);
+    }
+
+    /// Whether a slider in the panel is being dragged (and so owns the mouse)
+    pub fn is_dragging(&self) -> bool {
+        self.speed.is_dragging() || self.drawing_tools.is_dragging()
+    }
+
+    /// Scrollbar, status line and tooltip, drawn after everything else in
+    /// the panel
+    pub fn draw_overlay(&self) {
+        self.scroll.draw_scrollbar();
+        self.status_line.draw(panel_x(), PANEL_WIDTH);
+        self.tooltip.draw();
+    }
+}
+
+impl Default for Panel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,200 @@
+use macroquad::prelude::*;
+
+use super::layout::{Rect, Stack};
+use super::{Button, Checkbox, TextInput, PANEL_WIDTH};
+
+/// Width of the "B"/"S" column in front of the toggles
+const ROW_LABEL_WIDTH: f32 = 18.0;
+
+/// Horizontal distance between neighbor-count toggles
+const TOGGLE_PITCH: f32 = 18.0;
+
+/// Something the user did in the rule editor
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleEditorEvent {
+    /// A birth (or survival) toggle for `neighbors` was switched
+    Toggled { birth: bool, neighbors: usize, on: bool },
+    /// B/S notation typed into the text field and submitted
+    Typed(String),
+    Randomize,
+    Mutate,
+    /// Pick the `n`th recently used rule
+    Recall(usize),
+}
+
+/// Widgets of the rule editor section: the rule's B/S notation as an
+/// editable field, a birth and a survival toggle per neighbor count,
+/// Random/Mutate buttons and the recently used rules
+pub struct RuleEditorControls {
+    notation: TextInput,
+    birth: [Checkbox; 9],
+    survival: [Checkbox; 9],
+    actions: Vec<Button>,
+    recent: Vec<Button>,
+    /// Notation of recently used rules, most recent first
+    recent_labels: Vec<String>,
+    /// Rows of the digit headings and the birth and survival toggles
+    /// (None while the section is collapsed)
+    rows: Option<[Rect; 3]>,
+}
+
+impl RuleEditorControls {
+    pub fn new() -> Self {
+        let toggle = || Checkbox::new(0.0, 0.0, TOGGLE_PITCH - 2.0, "", false);
+        Self {
+            notation: TextInput::new(0.0, 0.0, PANEL_WIDTH, "Rule (B/S, Enter applies)")
+                .with_filter(|c| c.is_ascii_alphanumeric() || c == '/')
+                .with_max_len(24),
+            birth: std::array::from_fn(|_| toggle()),
+            survival: std::array::from_fn(|_| toggle()),
+            actions: Vec::new(),
+            recent: Vec::new(),
+            recent_labels: Vec::new(),
+            rows: None,
+        }
+    }
+
+    /// Show `birth` and `survival` (neighbor-count bit masks) and the
+    /// notation, unless the user is typing a new one
+    pub fn set_rule(&mut self, birth: u16, survival: u16, notation: &str) {
+        for n in 0..9 {
+            self.birth[n].set_checked(birth & (1 << n) != 0);
+            self.survival[n].set_checked(survival & (1 << n) != 0);
+        }
+        if !self.notation.is_focused() {
+            self.notation.set_text(notation);
+        }
+    }
+
+    /// Notation of the recently used rules, most recent first
+    pub fn set_recent(&mut self, labels: Vec<String>) {
+        self.recent_labels = labels;
+    }
+
+    /// Flag a rejected notation on its field until the user edits it
+    pub fn reject(&mut self, message: impl Into<String>) {
+        self.notation.reject(message);
+    }
+
+    /// Drop notation being typed, so the field shows the current rule
+    /// again (e.g. after the rule changed elsewhere)
+    pub fn stop_typing(&mut self) {
+        self.notation.blur();
+    }
+
+    /// Whether the notation field has keyboard focus
+    pub fn is_typing(&self) -> bool {
+        self.notation.is_focused()
+    }
+
+    /// Lay the editor out on `stack`
+    pub fn place(&mut self, stack: &mut Stack) {
+        let row = stack.row(18.0 + TextInput::HEIGHT);
+        self.notation.set_position(row.x, row.y + 18.0);
+
+        let rows = [stack.row(12.0), stack.row(Checkbox::HEIGHT), stack.row(Checkbox::HEIGHT)];
+        self.rows = Some(rows);
+        for (toggles, row) in [&mut self.birth, &mut self.survival].into_iter().zip(&rows[1..]) {
+            for (n, toggle) in toggles.iter_mut().enumerate() {
+                toggle.set_position(row.x + ROW_LABEL_WIDTH + n as f32 * TOGGLE_PITCH, row.y);
+            }
+        }
+
+        let row = stack.row(28.0);
+        let half = (row.width - 6.0) / 2.0;
+        self.actions = vec![
+            Button::new(row.x, row.y, half, row.height, "Random"),
+            Button::new(row.x + half + 6.0, row.y, half, row.height, "Mutate"),
+        ];
+
+        self.recent = self.recent_labels.iter().map(|label| {
+            let row = stack.row(24.0);
+            Button::new(row.x, row.y, row.width, row.height, label.clone())
+        }).collect();
+    }
+
+    /// Forget the layout while the section is collapsed
+    pub fn hide(&mut self) {
+        self.notation.blur();
+        self.rows = None;
+        self.actions.clear();
+        self.recent.clear();
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.rows.is_some()
+    }
+
+    /// Tooltip for the part of the editor under the mouse
+    pub fn hint_at(&self, mouse_pos: (f32, f32)) -> Option<&'static str> {
+        let over = |toggles: &[Checkbox; 9]| toggles.iter().any(|t| t.is_hovered(mouse_pos));
+        if !self.is_visible() {
+            None
+        } else if over(&self.birth) {
+            Some("Dead cells with this many neighbors are born")
+        } else if over(&self.survival) {
+            Some("Live cells with this many neighbors survive")
+        } else if self.actions.first().is_some_and(|b| b.is_hovered(mouse_pos)) {
+            Some("Random rule (never B0)")
+        } else if self.actions.get(1).is_some_and(|b| b.is_hovered(mouse_pos)) {
+            Some("Flip one birth or survival count")
+        } else if self.recent.iter().any(|b| b.is_hovered(mouse_pos)) {
+            Some("Recently used rule")
+        } else {
+            None
+        }
+    }
+
+    /// Handle clicks and typing; returns what the user did this frame
+    pub fn update(&mut self, mouse_pos: (f32, f32)) -> Vec<RuleEditorEvent> {
+        let mut events = Vec::new();
+        if !self.is_visible() {
+            return events;
+        }
+
+        if self.notation.update(mouse_pos) {
+            events.push(RuleEditorEvent::Typed(self.notation.text().to_string()));
+        }
+        for (birth, toggles) in [(true, &mut self.birth), (false, &mut self.survival)] {
+            for (neighbors, toggle) in toggles.iter_mut().enumerate() {
+                if toggle.update(mouse_pos) {
+                    events.push(RuleEditorEvent::Toggled { birth, neighbors, on: toggle.is_checked() });
+                }
+            }
+        }
+        if self.actions.first().is_some_and(|b| b.is_clicked(mouse_pos)) {
+            events.push(RuleEditorEvent::Randomize);
+        }
+        if self.actions.get(1).is_some_and(|b| b.is_clicked(mouse_pos)) {
+            events.push(RuleEditorEvent::Mutate);
+        }
+        if let Some(index) = self.recent.iter().position(|b| b.is_clicked(mouse_pos)) {
+            events.push(RuleEditorEvent::Recall(index));
+        }
+        events
+    }
+
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        let Some([digits, birth_row, survival_row]) = self.rows else {
+            return;
+        };
+
+        self.notation.draw(mouse_pos);
+        for n in 0..9 {
+            let x = digits.x + ROW_LABEL_WIDTH + n as f32 * TOGGLE_PITCH + 3.0;
+            draw_text(&n.to_string(), x, digits.y + 11.0, 14.0, GRAY);
+        }
+        for (label, toggles, row) in [("B", &self.birth, birth_row), ("S", &self.survival, survival_row)] {
+            toggles.iter().for_each(|toggle| toggle.draw(mouse_pos));
+            draw_text(label, row.x + 2.0, row.y + 15.0, 16.0, WHITE);
+        }
+        self.actions.iter().for_each(|button| button.draw(mouse_pos));
+        self.recent.iter().for_each(|button| button.draw(mouse_pos));
+    }
+}
+
+impl Default for RuleEditorControls {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,149 @@
+use macroquad::prelude::*;
+
+/// Horizontal slider with a label and value readout, dragged with the
+/// left mouse button
+#[derive(Clone)]
+pub struct Slider {
+    x: f32,
+    y: f32,
+    width: f32,
+    label: String,
+    min: f32,
+    max: f32,
+    value: f32,
+    /// Values snap to multiples of this above `min` (0 = continuous)
+    step: f32,
+    dragging: bool,
+}
+
+impl Slider {
+    /// Label line plus track
+    pub const HEIGHT: f32 = 34.0;
+
+    pub fn new(x: f32, y: f32, width: f32, label: impl Into<String>, min: f32, max: f32, value: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            label: label.into(),
+            min,
+            max,
+            value: value.clamp(min, max),
+            step: 0.0,
+            dragging: false,
+        }
+    }
+
+    /// Snap values to multiples of `step` (builder pattern)
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self.value = self.snap(self.value);
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Set the value (clamped and snapped). Ignored while dragging so
+    /// syncing from the model doesn't fight the mouse.
+    pub fn set_value(&mut self, value: f32) {
+        if !self.dragging {
+            self.value = self.snap(value);
+        }
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging
+    }
+
+    /// Update position for responsive layout
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Check if mouse is over the track
+    pub fn is_hovered(&self, mouse_pos: (f32, f32)) -> bool {
+        mouse_pos.0 >= self.x
+            && mouse_pos.0 <= self.x + self.width
+            && mouse_pos.1 >= self.y + 14.0
+            && mouse_pos.1 <= self.y + Self::HEIGHT
+    }
+
+    /// Value for a mouse x position along the track
+    pub fn value_at(&self, mouse_x: f32) -> f32 {
+        let t = ((mouse_x - self.x) / self.width).clamp(0.0, 1.0);
+        self.snap(self.min + t * (self.max - self.min))
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = value.clamp(self.min, self.max);
+        if self.step > 0.0 {
+            (self.min + ((value - self.min) / self.step).round() * self.step).min(self.max)
+        } else {
+            value
+        }
+    }
+
+    /// Handle dragging and return true if the value changed
+    pub fn update(&mut self, mouse_pos: (f32, f32)) -> bool {
+        if self.is_hovered(mouse_pos) && is_mouse_button_pressed(MouseButton::Left) {
+            self.dragging = true;
+        }
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+        if !self.dragging {
+            return false;
+        }
+
+        let value = self.value_at(mouse_pos.0);
+        let changed = value != self.value;
+        self.value = value;
+        changed
+    }
+
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        draw_text(&self.label, self.x, self.y + 12.0, 14.0, GRAY);
+        let readout = if self.step >= 1.0 {
+            format!("{:.0}", self.value)
+        } else {
+            format!("{:.2}", self.value)
+        };
+        let readout_width = measure_text(&readout, None, 14, 1.0).width;
+        draw_text(&readout, self.x + self.width - readout_width, self.y + 12.0, 14.0, WHITE);
+
+        let track_y = self.y + 24.0;
+        let t = (self.value - self.min) / (self.max - self.min).max(f32::EPSILON);
+        let knob_x = self.x + t * self.width;
+        draw_rectangle(self.x, track_y - 2.0, self.width, 4.0, Color::from_rgba(60, 60, 60, 255));
+        draw_rectangle(self.x, track_y - 2.0, knob_x - self.x, 4.0, Color::from_rgba(70, 130, 180, 255));
+
+        let knob_color = if self.dragging || self.is_hovered(mouse_pos) {
+            Color::from_rgba(100, 149, 237, 255)
+        } else {
+            WHITE
+        };
+        draw_circle(knob_x.clamp(self.x + 6.0, self.x + self.width - 6.0), track_y, 6.0, knob_color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_at_snaps_and_clamps() {
+        let slider = Slider::new(100.0, 0.0, 200.0, "Speed", 1.0, 61.0, 10.0).with_step(1.0);
+        assert_eq!(slider.value_at(100.0), 1.0);
+        assert_eq!(slider.value_at(200.0), 31.0);
+        assert_eq!(slider.value_at(203.0), 32.0);
+        assert_eq!(slider.value_at(50.0), 1.0);
+        assert_eq!(slider.value_at(999.0), 61.0);
+
+        let mut slider = slider;
+        slider.set_value(12.4);
+        assert_eq!(slider.value(), 12.0);
+    }
+}
@@ -0,0 +1,109 @@
+use macroquad::prelude::*;
+
+/// Seconds a message stays up
+pub const STATUS_DURATION: f32 = 4.0;
+
+/// One-line message at the foot of the panel, such as where an export
+/// was saved or why it failed. A new message replaces the old one; each
+/// disappears after `STATUS_DURATION` seconds.
+#[derive(Clone, Debug, Default)]
+pub struct StatusLine {
+    text: String,
+    is_error: bool,
+    remaining: f32,
+}
+
+impl StatusLine {
+    pub const HEIGHT: f32 = 22.0;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show a confirmation
+    pub fn info(&mut self, text: impl Into<String>) {
+        self.set(text.into(), false);
+    }
+
+    /// Show a failure
+    pub fn error(&mut self, text: impl Into<String>) {
+        self.set(text.into(), true);
+    }
+
+    /// Show either message of a result
+    pub fn report(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(text) => self.info(text),
+            Err(text) => self.error(text),
+        }
+    }
+
+    fn set(&mut self, text: String, is_error: bool) {
+        self.text = text;
+        self.is_error = is_error;
+        self.remaining = STATUS_DURATION;
+    }
+
+    /// The message currently shown, if any
+    pub fn message(&self) -> Option<&str> {
+        (self.remaining > 0.0).then_some(self.text.as_str())
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.is_error
+    }
+
+    /// Count down the message's time on screen
+    pub fn update(&mut self, delta_time: f32) {
+        self.remaining = (self.remaining - delta_time).max(0.0);
+    }
+
+    /// Draw the message across the bottom of the panel starting at `x`
+    pub fn draw(&self, x: f32, width: f32) {
+        let Some(text) = self.message() else {
+            return;
+        };
+        let y = screen_height() - Self::HEIGHT;
+        draw_rectangle(x, y, width, Self::HEIGHT, Color::from_rgba(15, 15, 15, 240));
+        let color = if self.is_error {
+            Color::from_rgba(255, 110, 110, 255)
+        } else {
+            Color::from_rgba(150, 220, 170, 255)
+        };
+
+        // Long messages keep their start, like dropdown labels
+        let font_size = 13.0;
+        let max_width = width - 10.0;
+        let mut shown = text.to_string();
+        if measure_text(&shown, None, font_size as u16, 1.0).width > max_width {
+            while !shown.is_empty() && measure_text(&format!("{}...", shown), None, font_size as u16, 1.0).width > max_width {
+                shown.pop();
+            }
+            shown.push_str("...");
+        }
+        draw_text(&shown, x + 5.0, y + 15.0, font_size, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_expires_and_is_replaced() {
+        let mut status = StatusLine::new();
+        assert_eq!(status.message(), None);
+
+        status.report(Ok("Saved a.png".to_string()));
+        status.update(STATUS_DURATION - 1.0);
+        assert_eq!(status.message(), Some("Saved a.png"));
+        assert!(!status.is_error());
+
+        status.error("Disk full");
+        status.update(STATUS_DURATION - 1.0);
+        assert_eq!(status.message(), Some("Disk full"), "a new message restarts the timer");
+        assert!(status.is_error());
+        status.update(1.0);
+        assert_eq!(status.message(), None);
+    }
+}
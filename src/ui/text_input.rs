@@ -0,0 +1,217 @@
+use macroquad::prelude::*;
+
+/// Single-line text field. Click to focus, Enter submits, Escape or a
+/// click elsewhere gives focus back. Rejected text shows an error in
+/// place of the label until it is edited.
+#[derive(Clone)]
+pub struct TextInput {
+    x: f32,
+    y: f32,
+    width: f32,
+    label: String,
+    text: String,
+    focused: bool,
+    /// Why the submitted text was rejected
+    error: Option<String>,
+    max_len: usize,
+    /// Characters the field accepts
+    filter: fn(char) -> bool,
+}
+
+impl TextInput {
+    pub const HEIGHT: f32 = 28.0;
+
+    pub fn new(x: f32, y: f32, width: f32, label: impl Into<String>) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            label: label.into(),
+            text: String::new(),
+            focused: false,
+            error: None,
+            max_len: 64,
+            filter: |c| !c.is_control(),
+        }
+    }
+
+    /// Only accept characters matching `filter` (builder pattern)
+    pub fn with_filter(mut self, filter: fn(char) -> bool) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Limit the text to `max_len` characters (builder pattern)
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: impl Into<String>) {
+        self.text = text.into();
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn focus(&mut self) {
+        self.focused = true;
+        // Drop characters typed before focusing (e.g. the shortcut key)
+        while get_char_pressed().is_some() {}
+    }
+
+    pub fn blur(&mut self) {
+        self.focused = false;
+        self.error = None;
+    }
+
+    /// Refuse the submitted text: show `message` and keep the text focused
+    /// so it can be corrected
+    pub fn reject(&mut self, message: impl Into<String>) {
+        self.focused = true;
+        self.error = Some(message.into());
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Update position for responsive layout
+    pub fn set_position(&mut self, x: f32, y: f32) {
+        self.x = x;
+        self.y = y;
+    }
+
+    /// Check if mouse is over the field
+    pub fn is_hovered(&self, mouse_pos: (f32, f32)) -> bool {
+        mouse_pos.0 >= self.x
+            && mouse_pos.0 <= self.x + self.width
+            && mouse_pos.1 >= self.y
+            && mouse_pos.1 <= self.y + Self::HEIGHT
+    }
+
+    /// Append a typed character; returns false if it was rejected
+    pub fn insert(&mut self, c: char) -> bool {
+        if !(self.filter)(c) || self.text.chars().count() >= self.max_len {
+            return false;
+        }
+        self.text.push(c);
+        self.error = None;
+        true
+    }
+
+    pub fn backspace(&mut self) {
+        self.text.pop();
+        self.error = None;
+    }
+
+    /// Handle focus and typing; returns true when Enter submits the text
+    pub fn update(&mut self, mouse_pos: (f32, f32)) -> bool {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            if self.is_hovered(mouse_pos) {
+                if !self.focused {
+                    self.focus();
+                }
+            } else {
+                self.blur();
+            }
+        }
+        if !self.focused {
+            return false;
+        }
+
+        while let Some(c) = get_char_pressed() {
+            self.insert(c);
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            self.backspace();
+        }
+        if is_key_pressed(KeyCode::Escape) {
+            self.blur();
+        }
+        if is_key_pressed(KeyCode::Enter) || is_key_pressed(KeyCode::KpEnter) {
+            self.focused = false;
+            return true;
+        }
+        false
+    }
+
+    pub fn draw(&self, mouse_pos: (f32, f32)) {
+        let error_color = Color::from_rgba(255, 90, 90, 255);
+        let (label, label_color) = match &self.error {
+            Some(error) => (error, error_color),
+            None => (&self.label, GRAY),
+        };
+        draw_text(label, self.x, self.y - 5.0, 14.0, label_color);
+
+        let border = if self.error.is_some() {
+            error_color
+        } else if self.focused {
+            Color::from_rgba(0, 255, 150, 255)
+        } else if self.is_hovered(mouse_pos) {
+            Color::from_rgba(100, 149, 237, 255)
+        } else {
+            WHITE
+        };
+        draw_rectangle(self.x, self.y, self.width, Self::HEIGHT, Color::from_rgba(20, 20, 20, 255));
+        draw_rectangle_lines(self.x, self.y, self.width, Self::HEIGHT, 1.5, border);
+
+        // Show the end of text too long for the field
+        let font_size = 16.0;
+        let max_width = self.width - 14.0;
+        let mut shown = self.text.as_str();
+        while !shown.is_empty() && measure_text(shown, None, font_size as u16, 1.0).width > max_width {
+            let mut chars = shown.chars();
+            chars.next();
+            shown = chars.as_str();
+        }
+        draw_text(shown, self.x + 5.0, self.y + 19.0, font_size, WHITE);
+
+        // Blinking caret
+        if self.focused && get_time().fract() < 0.5 {
+            let caret_x = self.x + 6.0 + measure_text(shown, None, font_size as u16, 1.0).width;
+            draw_line(caret_x, self.y + 6.0, caret_x, self.y + Self::HEIGHT - 6.0, 1.5, WHITE);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_respects_filter_and_length() {
+        let mut input = TextInput::new(0.0, 0.0, 100.0, "Size")
+            .with_filter(|c| c.is_ascii_digit() || c == 'x')
+            .with_max_len(4);
+        for c in "12a x3456".chars() {
+            input.insert(c);
+        }
+        assert_eq!(input.text(), "12x3");
+
+        input.backspace();
+        assert_eq!(input.text(), "12x");
+    }
+
+    #[test]
+    fn test_error_lasts_until_edited() {
+        let mut input = TextInput::new(0.0, 0.0, 100.0, "Size");
+        input.insert('9');
+        input.reject("Size must be WxH");
+        assert_eq!(input.error(), Some("Size must be WxH"));
+        assert!(input.is_focused());
+        assert_eq!(input.text(), "9", "rejected text stays for correcting");
+
+        input.insert('x');
+        assert_eq!(input.error(), None);
+
+        input.reject("Size must be WxH");
+        input.blur();
+        assert_eq!(input.error(), None);
+    }
+}
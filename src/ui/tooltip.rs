@@ -0,0 +1,90 @@
+use macroquad::prelude::*;
+
+/// Seconds the mouse must rest on a widget before its tooltip shows
+pub const TOOLTIP_DELAY: f32 = 0.5;
+
+/// Hint text that appears next to the cursor after hovering a widget for
+/// a moment. One tooltip is shared by all widgets: each frame it is told
+/// which text (if any) is under the mouse.
+#[derive(Clone, Debug, Default)]
+pub struct Tooltip {
+    text: Option<String>,
+    hover_time: f32,
+    mouse_pos: (f32, f32),
+}
+
+impl Tooltip {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `text` is the hint of the hovered widget; a different text (or
+    /// none) restarts the delay
+    pub fn update(&mut self, text: Option<&str>, mouse_pos: (f32, f32), delta_time: f32) {
+        if self.text.as_deref() != text {
+            self.text = text.map(str::to_owned);
+            self.hover_time = 0.0;
+        }
+        if self.text.is_some() {
+            self.hover_time += delta_time;
+        }
+        self.mouse_pos = mouse_pos;
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.text.is_some() && self.hover_time >= TOOLTIP_DELAY
+    }
+
+    pub fn draw(&self) {
+        let Some(text) = self.text.as_deref().filter(|_| self.is_visible()) else {
+            return;
+        };
+        let font_size = 14.0;
+        let size = (measure_text(text, None, font_size as u16, 1.0).width + 12.0, 22.0);
+        let (x, y) = placement(size, self.mouse_pos, (screen_width(), screen_height()));
+
+        draw_rectangle(x, y, size.0, size.1, Color::from_rgba(20, 20, 20, 240));
+        draw_rectangle_lines(x, y, size.0, size.1, 1.0, Color::from_rgba(120, 120, 120, 255));
+        draw_text(text, x + 6.0, y + 15.0, font_size, WHITE);
+    }
+}
+
+/// Top-left corner for a box of `size` below and right of the cursor,
+/// flipped to the other side where it would leave the screen
+fn placement(size: (f32, f32), mouse_pos: (f32, f32), screen: (f32, f32)) -> (f32, f32) {
+    let mut x = mouse_pos.0 + 12.0;
+    let mut y = mouse_pos.1 + 18.0;
+    if x + size.0 > screen.0 {
+        x = (mouse_pos.0 - size.0 - 4.0).max(0.0);
+    }
+    if y + size.1 > screen.1 {
+        y = (mouse_pos.1 - size.1 - 4.0).max(0.0);
+    }
+    (x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tooltip_waits_for_delay() {
+        let mut tooltip = Tooltip::new();
+        tooltip.update(Some("Clear (C)"), (10.0, 10.0), 0.3);
+        assert!(!tooltip.is_visible());
+        tooltip.update(Some("Clear (C)"), (10.0, 10.0), 0.3);
+        assert!(tooltip.is_visible());
+
+        // Moving to another widget restarts the delay
+        tooltip.update(Some("Random (R)"), (10.0, 60.0), 0.3);
+        assert!(!tooltip.is_visible());
+        tooltip.update(None, (10.0, 60.0), 1.0);
+        assert!(!tooltip.is_visible());
+    }
+
+    #[test]
+    fn test_placement_stays_on_screen() {
+        assert_eq!(placement((100.0, 20.0), (50.0, 50.0), (800.0, 600.0)), (62.0, 68.0));
+        assert_eq!(placement((100.0, 20.0), (750.0, 590.0), (800.0, 600.0)), (646.0, 566.0));
+    }
+}